@@ -6,15 +6,31 @@
 //! This ensures that you can respond to application lifecycles, and so
 //! routing things around works correctly.
 
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
 
 use alchemy_styles::{StyleSheet, THEME_ENGINE};
-use alchemy_lifecycle::traits::AppDelegate;
+use alchemy_lifecycle::RENDER_ENGINE;
+use alchemy_lifecycle::native::AppHandle;
+use alchemy_lifecycle::traits::{AppDelegate, AppLifecycle, Backend};
 
 use crate::window::WindowManager;
 
+/// Returns the `Backend` for whichever platform feature was enabled, if any. The `cocoa`
+/// backend wins if more than one is enabled.
 #[cfg(feature = "cocoa")]
-pub use alchemy_cocoa::app::{App as PlatformAppBridge};
+fn default_backend() -> Option<Arc<Backend>> {
+    Some(Arc::new(alchemy_cocoa::CocoaBackend::new()))
+}
+
+#[cfg(all(feature = "headless", not(feature = "cocoa")))]
+fn default_backend() -> Option<Arc<Backend>> {
+    Some(Arc::new(alchemy_headless::HeadlessBackend::new()))
+}
+
+#[cfg(not(any(feature = "cocoa", feature = "headless")))]
+fn default_backend() -> Option<Arc<Backend>> {
+    None
+}
 
 /// A default delegate that is mostly used for creating the initial struct,
 /// without requiring the actual `AppDelegate` from the user. Will ideally
@@ -22,44 +38,101 @@ pub use alchemy_cocoa::app::{App as PlatformAppBridge};
 struct DefaultAppDelegate;
 impl AppDelegate for DefaultAppDelegate {}
 
-/// The Application structure itself. It holds a Mutex'd platform bridge, to
-/// handle communicating with the platform-specific app instance, along with a
-/// delegate to forward events to. The `ThemeEngine` and `WindowManager` are
-/// also stored here for easy access.
+/// The Application structure itself. It holds the `Backend` that everything is rendered
+/// through, along with a delegate to forward events to. The `ThemeEngine` and
+/// `WindowManager` are also stored here for easy access.
 pub struct App {
-    pub(crate) bridge: Mutex<Option<PlatformAppBridge>>,
+    pub(crate) backend: RwLock<Option<Arc<Backend>>>,
     pub delegate: Mutex<Box<AppDelegate>>,
     pub windows: WindowManager
 }
 
 impl App {
-    /// Creates a new app, allocated on the heap. Provides a pointer to
-    /// said allocated instance so that the platform-specific app instances
-    /// can loop events back around.
+    /// Creates a new app, allocated on the heap, using the default `Backend` for
+    /// whichever platform feature is enabled. State updates get batched up and flushed on
+    /// the next tick of whichever `Backend` is active.
     pub(crate) fn new() -> Arc<App> {
-        let app = Arc::new(App {
-            bridge: Mutex::new(None),
+        RENDER_ENGINE.set_dispatcher(|handler| {
+            crate::SHARED_APP.backend().dispatch(handler);
+        });
+
+        Arc::new(App {
+            backend: RwLock::new(default_backend()),
             delegate: Mutex::new(Box::new(DefaultAppDelegate {})),
             windows: WindowManager::new()
-        });
+        })
+    }
 
-        let app_ptr: *const App = &*app;
-        app.configure_bridge(app_ptr);
-        app
+    /// Sets the `Backend` that this app renders through. This is how you plug in a backend
+    /// that doesn't ship with Alchemy; it needs to happen before any `Window` (or other native
+    /// backed component) is created, as nodes from one backend mean nothing to another.
+    pub fn set_backend<B: Backend + 'static>(&self, backend: B) {
+        let mut lock = self.backend.write().unwrap();
+        *lock = Some(Arc::new(backend));
     }
 
-    /// Handles providing the app pointer to the inner bridge.
-    pub(crate) fn configure_bridge(&self, ptr: *const App) {
-        let mut bridge = self.bridge.lock().unwrap();
-        *bridge = Some(PlatformAppBridge::new(ptr));
+    /// Returns the `Backend` that this app renders through.
+    ///
+    /// This panics if no backend is set - either enable a platform feature (e.g, `cocoa`), or
+    /// call `set_backend()` first.
+    pub fn backend(&self) -> Arc<Backend> {
+        let lock = self.backend.read().unwrap();
+        match &*lock {
+            Some(backend) => backend.clone(),
+            None => { panic!("No Backend configured! Enable a platform feature (e.g, `cocoa`), or call `set_backend()`."); }
+        }
     }
 
     /// Convenience method for registering one-off styles. Typically, you would want 
     /// to store your stylesheets as separate files, to enable hot-reloading - but it's
     /// conceivable that you might want to just have them in your app, too, and this enables
     /// that use case.
+    ///
+    /// Registering more than one stylesheet for a theme merges them, with later registrations
+    /// winning where they set the same properties (regardless of specificity). Any open windows
+    /// are restyled.
     pub fn register_styles(&self, theme_key: &str, stylesheet: StyleSheet) {
         THEME_ENGINE.register_styles(theme_key, stylesheet);
+        self.windows.restyle_all();
+    }
+
+    /// Registers a stylesheet for a theme under `id`, replacing any stylesheet already registered
+    /// with that id. Any open windows are restyled.
+    pub fn replace_styles(&self, theme_key: &str, id: &str, stylesheet: StyleSheet) {
+        THEME_ENGINE.replace_styles(theme_key, id, stylesheet);
+        self.windows.restyle_all();
+    }
+
+    /// Unregisters the stylesheet registered for a theme under `id`, returning whether there was
+    /// one. Any open windows are restyled.
+    pub fn unregister_styles(&self, theme_key: &str, id: &str) -> bool {
+        let unregistered = THEME_ENGINE.unregister_styles(theme_key, id);
+        self.windows.restyle_all();
+        unregistered
+    }
+
+    /// Returns the active theme - the one windows are styled with, unless part of a tree picks
+    /// its own via the `theme` attribute. This is `default` to begin with.
+    pub fn theme(&self) -> String {
+        THEME_ENGINE.active_theme()
+    }
+
+    /// Sets the active theme, and restyles every open window with it. Anything the theme doesn't
+    /// set falls back to the `default` theme.
+    pub fn set_theme(&self, theme_key: &str) {
+        THEME_ENGINE.set_active_theme(theme_key);
+        self.windows.restyle_all();
+    }
+
+    /// Watches theme files for changes, reloading them and restyling every open window as they
+    /// change. Component state is left alone, as nothing re-renders. This happens automatically
+    /// in debug builds; in release, you can opt in by calling this before `run()`.
+    pub fn watch_themes(&self) {
+        THEME_ENGINE.watch(|| {
+            crate::SHARED_APP.backend().dispatch(Box::new(|| {
+                crate::SHARED_APP.windows.restyle_all();
+            }));
+        });
     }
 
     /// Runs the app instance, by setting the necessary delegate and forwarding the run call
@@ -71,54 +144,55 @@ impl App {
             *delegate = Box::new(state);
         }
 
-        let lock = self.bridge.lock().unwrap();
-        if let Some(bridge) = &*lock {
-            bridge.run();
-        }
+        #[cfg(debug_assertions)]
+        self.watch_themes();
+
+        let app_ptr: *const App = self;
+        self.backend().run(AppHandle::new(app_ptr));
     }
 }
 
-/// Implementing `AppDelegate` for `App` serves two purposes - for one, we're able to
+/// Implementing `AppLifecycle` for `App` serves two purposes - for one, we're able to
 /// separate the inner implementaton from the abstract one by referring to a trait type, avoiding
 /// a cyclical dependency... and two, it allows us to react to these events on the App layer for
 /// our own purposes, while still forwarding them on to the delegate.
-impl AppDelegate for App {
+impl AppLifecycle for App {
     /// Called when the application will finish launching.
-    fn will_finish_launching(&mut self) {
+    fn will_finish_launching(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.will_finish_launching();
     }
     
     /// Called when the application did finish launching.
-    fn did_finish_launching(&mut self) { 
+    fn did_finish_launching(&self) { 
         let mut delegate = self.delegate.lock().unwrap();
         delegate.did_finish_launching();
     }
 
     /// Called when the application will become active. We can use this, for instance, 
     /// to resume rendering cycles and so on. 
-    fn will_become_active(&mut self) {
+    fn will_become_active(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.will_become_active();
     }
 
     /// Called when the application did become active. We can use this, for instance, 
     /// to resume rendering cycles and so on.
-    fn did_become_active(&mut self) {
+    fn did_become_active(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.did_become_active();
     }
 
     /// Called when the application will resigned active. We can use this, for instance, 
     /// to pause rendering cycles and so on.
-    fn will_resign_active(&mut self) {
+    fn will_resign_active(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.will_resign_active();
     }
 
     /// Called when the application has resigned active. We can use this, for instance, 
     /// to pause rendering cycles and so on.
-    fn did_resign_active(&mut self) {
+    fn did_resign_active(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.did_resign_active();
     }
@@ -132,15 +206,13 @@ impl AppDelegate for App {
     }
 
     /// Called when the application is about to terminate.
-    fn will_terminate(&mut self) {
+    fn will_terminate(&self) {
         let mut delegate = self.delegate.lock().unwrap();
         delegate.will_terminate();
     }
 
-    /// This is a private method, and you should not attempt to call it or
-    /// rely on it. It exists to enable easy loopback of Window-level events
-    /// on some platforms.
-    fn _window_will_close(&self, window_id: usize) {
+    /// Called when a window is about to close, so the window manager can let go of it.
+    fn window_will_close(&self, window_id: usize) {
         self.windows.will_close(window_id);
     }
 }
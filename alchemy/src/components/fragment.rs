@@ -29,7 +29,7 @@ impl Fragment {
 }
 
 impl Props for Fragment {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, _: &std::any::Any) {}
 }
 
 impl Component for Fragment {
@@ -1,9 +1,7 @@
-//! Handles hoisting per-platform specific Text components.
-//! Each platform needs the freedom to do some specific things,
-//! hence why they're all (somewhat annoyingly, but lovingly) re-implemented 
-//! as bridges.
+//! Implements the Text component. The native node behind it comes from (and is
+//! managed by) whichever `Backend` the shared app is using.
 
-use std::sync::{Mutex};
+use std::sync::Mutex;
 
 use alchemy_styles::styles::{Appearance, Layout};
 
@@ -12,8 +10,7 @@ use alchemy_lifecycle::error::Error;
 use alchemy_lifecycle::rsx::RSX;
 use alchemy_lifecycle::traits::{Component, Props, PlatformSpecificNodeType};
 
-#[cfg(feature = "cocoa")]
-use alchemy_cocoa::text::{Text as PlatformTextBridge};
+use crate::SHARED_APP;
 
 pub struct TextProps;
 
@@ -25,7 +22,10 @@ pub struct TextProps;
 /// ```
 /// <Text styles=["styleKey1", "styleKey2"] />
 /// ```
-pub struct Text(Mutex<PlatformTextBridge>);
+pub struct Text {
+    node: PlatformSpecificNodeType,
+    text: Mutex<String>
+}
 
 impl Text {
     pub fn default_props() -> TextProps { TextProps {} }
@@ -38,19 +38,21 @@ impl Text {
 }
 
 impl Props for Text {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, _: &std::any::Any) {}
 }
 
 impl Component for Text {
     fn new(_: ComponentKey) -> Text {
-        Text(Mutex::new(PlatformTextBridge::new()))
+        Text {
+            node: SHARED_APP.backend().create_text(),
+            text: Mutex::new(String::new())
+        }
     }
 
     fn has_native_backing_node(&self) -> bool { true }
     
     fn borrow_native_backing_node(&self) -> Option<PlatformSpecificNodeType> {
-        let bridge = self.0.lock().unwrap();
-        Some(bridge.borrow_native_backing_node())
+        Some(self.node.clone())
     }
 
     // Shouldn't be allowed to have child <Text> elements... or, should it?
@@ -58,13 +60,17 @@ impl Component for Text {
     //fn append_child_component(&self, _component: &Component) {}
 
     fn apply_styles(&self, appearance: &Appearance, layout: &Layout) {
-        let mut bridge = self.0.lock().unwrap();
-        bridge.apply_styles(appearance, layout);
+        SHARED_APP.backend().apply_styles(&self.node, appearance, layout);
     }
 
     fn component_did_mount(&mut self) {
-        let mut bridge = self.0.lock().unwrap();
-        bridge.render();
+        let text = self.text.lock().unwrap();
+        SHARED_APP.backend().set_text(&self.node, &text);
+    }
+
+    fn component_did_update(&mut self) {
+        let text = self.text.lock().unwrap();
+        SHARED_APP.backend().set_text(&self.node, &text);
     }
 
     // This one is a bit tricky, due to the way we have to do props + children in Rust.
@@ -76,8 +82,8 @@ impl Component for Text {
             _ => String::new()
         }).collect::<String>();
         
-        let mut bridge = self.0.lock().unwrap();
-        bridge.set_text(text);
+        let mut current = self.text.lock().unwrap();
+        *current = text;
         
         Ok(RSX::None)
     }
@@ -1,9 +1,5 @@
-//! Handles hoisting per-platform specific View components.
-//! Each platform needs the freedom to do some specific things,
-//! hence why they're all (somewhat annoyingly, but lovingly) re-implemented 
-//! as bridges.
-
-use std::sync::Mutex;
+//! Implements the View component. The native node behind it comes from (and is
+//! managed by) whichever `Backend` the shared app is using.
 
 use alchemy_styles::{Appearance, Layout};
 
@@ -12,11 +8,9 @@ use alchemy_lifecycle::error::Error;
 use alchemy_lifecycle::rsx::RSX;
 use alchemy_lifecycle::traits::{Component, Props, PlatformSpecificNodeType};
 
+use crate::SHARED_APP;
 use crate::components::Fragment;
 
-#[cfg(feature = "cocoa")]
-use alchemy_cocoa::view::{View as PlatformViewBridge};
-
 pub struct ViewProps;
 
 /// Views are the most basic piece of the API. If you want to display something, you'll
@@ -28,13 +22,13 @@ pub struct ViewProps;
 /// <View styles=["styleKey1", "styleKey2"] />
 /// ```
 pub struct View {
-    bridge: Mutex<PlatformViewBridge>
+    node: PlatformSpecificNodeType
 }
 
 impl Default for View {
     fn default() -> View {
         View {
-            bridge: Mutex::new(PlatformViewBridge::new())
+            node: SHARED_APP.backend().create_view()
         }
     }
 }
@@ -46,7 +40,7 @@ impl View {
 }
 
 impl Props for View {
-    fn set_props(&mut self, _: &mut std::any::Any) {}
+    fn set_props(&mut self, _: &std::any::Any) {}
 }
 
 impl Component for View {
@@ -57,23 +51,32 @@ impl Component for View {
     fn has_native_backing_node(&self) -> bool { true }
     
     fn borrow_native_backing_node(&self) -> Option<PlatformSpecificNodeType> {
-        let bridge = self.bridge.lock().unwrap();
-        Some(bridge.borrow_native_backing_node())
+        Some(self.node.clone())
     }
 
     fn append_child_node(&self, node: PlatformSpecificNodeType) {
-        let mut bridge = self.bridge.lock().unwrap();
-        bridge.append_child(node);
+        SHARED_APP.backend().append_child(&self.node, &node);
+    }
+
+    fn insert_child_node(&self, index: usize, node: PlatformSpecificNodeType) {
+        SHARED_APP.backend().insert_child(&self.node, index, &node);
+    }
+
+    fn replace_child_node(&self, old: PlatformSpecificNodeType, new: PlatformSpecificNodeType) {
+        SHARED_APP.backend().replace_child(&self.node, &old, &new);
+    }
+
+    fn remove_child_node(&self, node: PlatformSpecificNodeType) {
+        SHARED_APP.backend().remove_child(&self.node, &node);
     }
 
     fn apply_styles(&self, appearance: &Appearance, layout: &Layout) {
-        let mut bridge = self.bridge.lock().unwrap();
-        bridge.apply_styles(appearance, layout);
+        SHARED_APP.backend().apply_styles(&self.node, appearance, layout);
     }
 
     fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
         Ok(RSX::node("Fragment", "".into(), |key| {
             Box::new(<Fragment as Component>::new(key))
-        }, Box::new(ViewProps {}), children))
+        }, ViewProps {}, children))
     }
 }
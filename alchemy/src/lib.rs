@@ -9,12 +9,16 @@ use std::sync::Arc;
 pub use lazy_static::lazy_static;
 use proc_macro_hack::proc_macro_hack;
 
-pub use alchemy_lifecycle::{ComponentKey, text};
+pub use alchemy_lifecycle::{ComponentKey, RENDER_ENGINE, text};
+pub use alchemy_lifecycle::native::{AppHandle, NativeNode};
 pub use alchemy_lifecycle::traits::{
-    AppDelegate, Component, Props as ComponentProps, WindowDelegate
+    AppDelegate, Backend, Component, PlatformSpecificNodeType, Props as ComponentProps, WindowDelegate
 };
 
 pub use alchemy_lifecycle::error::Error;
+pub use alchemy_lifecycle::events::{
+    Event, EventHandler, EventHandlers, EventPhase, FocusEvent, KeyEvent, Modifiers, PointerEvent, SyntheticEvent
+};
 pub use alchemy_lifecycle::rsx::{
     RSX, VirtualNode, VirtualText
 };
@@ -24,9 +28,18 @@ pub use alchemy_macros::rsx;
 
 #[proc_macro_hack]
 pub use alchemy_macros::styles;
+
+#[proc_macro_hack]
+pub use alchemy_macros::include_styles;
 pub use alchemy_macros::Props;
 
-pub use alchemy_styles::{Color, styles as style_attributes, SpacedSet, StyleSheet, StylesList};
+pub use alchemy_styles::{Color, ColorScheme, styles as style_attributes, SpacedSet, StyleSheet, StylesList};
+pub use alchemy_styles::selector::PseudoClass;
+
+/// The headless backend exposes its in-memory widget tree, so that apps can be inspected
+/// after they've been mounted and laid out.
+#[cfg(feature = "headless")]
+pub use alchemy_headless as headless;
 
 mod app;
 use app::App;
@@ -37,6 +37,15 @@ impl WindowManager {
         }
     }
 
+    /// Restyles every open window, e.g after a theme has been reloaded.
+    pub(crate) fn restyle_all(&self) {
+        let windows = self.0.lock().unwrap();
+        for window in windows.iter() {
+            let mut window = window.lock().unwrap();
+            window.restyle();
+        }
+    }
+
     /// On a `will_close` event, our delegates will loop back here and notify that a window
     /// with x id is closing, and should be removed. The `WindowDelegate` `will_close()` event
     /// is fired here.
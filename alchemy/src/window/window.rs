@@ -4,17 +4,15 @@
 use std::sync::{Arc, Mutex};
 
 use alchemy_lifecycle::{ComponentKey, RENDER_ENGINE};
+use alchemy_lifecycle::native::AppHandle;
 use alchemy_lifecycle::rsx::RSX;
-use alchemy_lifecycle::traits::{Component, WindowDelegate};
+use alchemy_lifecycle::traits::{Component, PlatformSpecificNodeType, WindowDelegate};
 
-use alchemy_styles::{Appearance, Style, StylesList, THEME_ENGINE};
+use alchemy_styles::{Appearance, MediaEnvironment, Style, StylesList, THEME_ENGINE};
 
 use crate::{App, SHARED_APP};
 use crate::components::View;
 
-#[cfg(feature = "cocoa")]
-use alchemy_cocoa::window::{Window as PlatformWindowBridge};
-
 /// AppWindow contains the inner details of a Window. It's guarded by a Mutex on `Window`,
 /// and you shouldn't create this yourself, but it's documented here so you can understand what
 /// it holds.
@@ -23,7 +21,7 @@ pub struct AppWindow {
     pub style_keys: StylesList,
     pub title: String,
     pub dimensions: (f64, f64, f64, f64),
-    pub bridge: PlatformWindowBridge,
+    pub bridge: PlatformSpecificNodeType,
     pub delegate: Box<WindowDelegate>,
     pub render_key: ComponentKey
 }
@@ -34,14 +32,14 @@ impl AppWindow {
     /// and determining what needs to be changed. This also calculates and applies layout and
     /// styling.
     ///
+    /// `@media` rules are evaluated against the window's dimensions and the system's color scheme
+    /// as of this call.
+    ///
     /// This method is called on the `show` event, and in rare cases can be useful to call
     /// directly.
     pub fn render(&mut self) {
-        let mut style = Style::default();
-        let mut appearance = Appearance::default();
-        THEME_ENGINE.configure_styles_for_keys(&self.style_keys, &mut style, &mut appearance);
-
-        self.bridge.apply_styles(&appearance);
+        let media = self.media_environment();
+        self.apply_styles(&media);
 
         let children = match self.delegate.render() {
             Ok(opt) => opt,
@@ -51,34 +49,61 @@ impl AppWindow {
             }
         };
 
-        match RENDER_ENGINE.diff_and_render_root(self.render_key, (
-            self.dimensions.2,
-            self.dimensions.3
-        ), children) {
+        match RENDER_ENGINE.diff_and_render_root(self.render_key, media, children) {
             Ok(_) => { }
             Err(e) => { eprintln!("Error rendering window! {}", e); }
         }
     }
 
+    /// Restyles a window and everything in it against the current stylesheets, without
+    /// re-rendering anything - e.g, after a theme has been reloaded.
+    pub fn restyle(&mut self) {
+        let media = self.media_environment();
+        self.apply_styles(&media);
+
+        if let Err(e) = RENDER_ENGINE.restyle(self.render_key) {
+            eprintln!("Error restyling window! {}", e);
+        }
+    }
+
+    /// What `@media` rules get evaluated against: the window's dimensions, and the system's
+    /// current color scheme.
+    fn media_environment(&self) -> MediaEnvironment {
+        MediaEnvironment {
+            width: self.dimensions.2 as f32,
+            height: self.dimensions.3 as f32,
+            color_scheme: SHARED_APP.backend().color_scheme()
+        }
+    }
+
+    /// Applies the styles for the window itself (as opposed to its contents).
+    fn apply_styles(&self, media: &MediaEnvironment) {
+        let mut style = Style::default();
+        let mut appearance = Appearance::default();
+        THEME_ENGINE.configure_styles_for_keys(&self.style_keys, media, &mut style, &mut appearance);
+
+        SHARED_APP.backend().apply_window_styles(&self.bridge, &appearance);
+    }
+
     pub fn set_title(&mut self, title: &str) {
         self.title = title.into();
-        self.bridge.set_title(title);
+        SHARED_APP.backend().set_window_title(&self.bridge, title);
     }
 
     pub fn set_dimensions(&mut self, x: f64, y: f64, width: f64, height: f64) {
         self.dimensions = (x, y, width, height);
-        self.bridge.set_dimensions(x, y, width, height);
+        SHARED_APP.backend().set_window_dimensions(&self.bridge, x, y, width, height);
     }
 
     /// Renders and calls through to the native platform window show method.
     pub fn show(&mut self) {
         self.render();
-        self.bridge.show();
+        SHARED_APP.backend().show_window(&self.bridge);
     }
 
     /// Calls through to the native platform window close method.
     pub fn close(&mut self) {
-        self.bridge.close();
+        SHARED_APP.backend().close_window(&self.bridge);
     }
 }
 
@@ -95,7 +120,7 @@ impl Window {
         
         // This unwrap() is fine, since we implement View ourselves in Alchemy
         let backing_node = view.borrow_native_backing_node().unwrap();
-        let bridge = PlatformWindowBridge::new(window_id, backing_node, shared_app_ptr);
+        let bridge = SHARED_APP.backend().create_window(window_id, &backing_node, AppHandle::new(shared_app_ptr));
 
         let key = match RENDER_ENGINE.register_root_component(view) {
             Ok(key) => key,
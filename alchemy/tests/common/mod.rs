@@ -0,0 +1,97 @@
+//! Shared setup for the integration tests, which render through the headless backend and then
+//! inspect the resulting node tree.
+//!
+//! The runloop, the render tree and registered stylesheets are all global, so tests take turns
+//! via `setup()`, and wait on queued updates via `flush()`.
+
+#![allow(dead_code)]
+
+use std::sync::{Mutex, MutexGuard};
+
+use alchemy::{headless, include_styles, lazy_static, AppDelegate, Error, RSX, Window, WindowDelegate};
+
+lazy_static! {
+    static ref SERIAL: Mutex<()> = Mutex::new(());
+}
+
+/// Takes the lock that keeps tests from running over each other, and (re)registers the styles
+/// they share. A test that panicked while holding the lock doesn't fail the others.
+pub fn setup() -> MutexGuard<'static, ()> {
+    let guard = SERIAL.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    alchemy::shared_app().replace_styles("default", "tests", include_styles!("tests/common/styles.css"));
+    guard
+}
+
+struct Idle;
+impl AppDelegate for Idle {}
+
+/// Runs the headless runloop until it's out of work - e.g, until anything queued via
+/// `set_state()` has been flushed.
+pub fn flush() {
+    alchemy::shared_app().run(Idle);
+}
+
+/// A `WindowDelegate` that renders whatever the closure it wraps returns.
+pub struct Render<F>(pub F);
+
+impl<F: Fn() -> RSX + Send + Sync> WindowDelegate for Render<F> {
+    fn render(&self) -> Result<RSX, Error> {
+        Ok((self.0)())
+    }
+}
+
+/// Opens a 600x600 window titled `title`, rendering `render` into it.
+pub fn open<F: Fn() -> RSX + Send + Sync + 'static>(title: &str, render: F) -> Window {
+    let mut window = Window::new(Render(render));
+    window.set_title(title);
+    window.set_dimensions(0., 0., 600., 600.);
+    window.show();
+    window
+}
+
+/// Returns the id of the content view for the window titled `title`.
+pub fn content_view(title: &str) -> headless::NodeId {
+    headless::windows().into_iter()
+        .find(|window| window.title == title)
+        .map(|window| window.content_view)
+        .expect("no window with that title")
+}
+
+/// Returns the node at `id`, which has to exist.
+pub fn node(id: headless::NodeId) -> headless::Node {
+    headless::node(id).expect("node was removed")
+}
+
+/// Returns the ids of the children of the node at `id`.
+pub fn children(id: headless::NodeId) -> Vec<headless::NodeId> {
+    node(id).children
+}
+
+/// Returns the text of each child of the node at `id`.
+pub fn texts(id: headless::NodeId) -> Vec<String> {
+    children(id).into_iter().map(|child| node(child).text).collect()
+}
+
+/// Returns the background color of the node at `id`, as `(red, green, blue)`.
+pub fn background(id: headless::NodeId) -> (u8, u8, u8) {
+    let color = node(id).background_color;
+    (color.red, color.green, color.blue)
+}
+
+/// A log of lifecycle calls, for tests to check what fired and in what order.
+pub struct Log(Mutex<Vec<String>>);
+
+impl Log {
+    pub fn new() -> Log {
+        Log(Mutex::new(vec![]))
+    }
+
+    pub fn push<S: Into<String>>(&self, entry: S) {
+        self.0.lock().unwrap().push(entry.into());
+    }
+
+    /// Returns everything logged since the last call, and clears it.
+    pub fn take(&self) -> Vec<String> {
+        std::mem::replace(&mut *self.0.lock().unwrap(), vec![])
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests for error boundaries: render errors propagate up to the nearest boundary, which swaps in
+//! its fallback, and the subtree that failed is unmounted.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Mutex;
+
+use alchemy::{lazy_static, rsx, text, Component, ComponentKey, Error, Props, RSX, Text, View, RENDER_ENGINE};
+
+use common::{children, content_view, flush, open, setup, texts, Log};
+
+lazy_static! {
+    static ref LOG: Log = Log::new();
+    static ref BOMB_KEYS: Mutex<Vec<ComponentKey>> = Mutex::new(vec![]);
+}
+
+#[derive(Props)]
+struct Boundary {
+    failed: Option<String>
+}
+
+impl Component for Boundary {
+    fn new(_key: ComponentKey) -> Boundary {
+        Boundary { failed: None }
+    }
+
+    fn is_error_boundary(&self) -> bool {
+        true
+    }
+
+    fn get_derived_state_from_error(&mut self, error: &Error) {
+        self.failed = Some(error.to_string());
+    }
+
+    fn component_did_catch(&mut self, error: &Error) {
+        LOG.push(format!("caught {}", error));
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        match &self.failed {
+            Some(message) => Ok(rsx! {
+                <Text styles=["row"]>{text!("fallback: {}", message)}</Text>
+            }),
+
+            None => Ok(rsx! {
+                <View styles=["panel"]>{children}</View>
+            })
+        }
+    }
+}
+
+#[derive(Props)]
+struct Bomb {
+    #[props] armed: bool,
+    exploded: bool
+}
+
+impl Component for Bomb {
+    fn new(key: ComponentKey) -> Bomb {
+        BOMB_KEYS.lock().unwrap().push(key);
+        Bomb { armed: false, exploded: false }
+    }
+
+    fn component_did_mount(&mut self) {
+        LOG.push("bomb mounted");
+    }
+
+    fn component_will_unmount(&mut self) {
+        LOG.push("bomb unmounted");
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        if self.armed || self.exploded {
+            return Err("boom".into());
+        }
+
+        Ok(rsx! {
+            <Text styles=["row"]>"Ticking"</Text>
+        })
+    }
+}
+
+fn reset() {
+    BOMB_KEYS.lock().unwrap().clear();
+    LOG.take();
+}
+
+#[test]
+fn failed_mounts_render_the_fallback() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("failed mount", || rsx! {
+        <View styles=["list"]>
+            <Boundary>
+                <Bomb armed={true} />
+            </Boundary>
+            <Text styles=["row"]>"Sibling"</Text>
+        </View>
+    });
+
+    let list = children(content_view("failed mount"))[0];
+    assert_eq!(texts(list), ["fallback: boom", "Sibling"]);
+
+    // The bomb never mounted, so it doesn't get unmounted either.
+    assert_eq!(LOG.take(), ["caught boom"]);
+}
+
+#[test]
+fn failed_updates_unmount_the_subtree_and_render_the_fallback() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("failed update", || rsx! {
+        <View styles=["list"]>
+            <Boundary>
+                <Bomb />
+            </Boundary>
+        </View>
+    });
+
+    let list = children(content_view("failed update"))[0];
+    let panel = children(list)[0];
+    assert_eq!(texts(panel), ["Ticking"]);
+    assert_eq!(LOG.take(), ["bomb mounted"]);
+
+    let key = BOMB_KEYS.lock().unwrap()[0];
+    RENDER_ENGINE.set_state(key, |bomb: &mut Bomb| { bomb.exploded = true; });
+    flush();
+    assert_eq!(texts(list), ["fallback: boom"]);
+    assert_eq!(LOG.take(), ["bomb unmounted", "caught boom"]);
+}
@@ -0,0 +1,322 @@
+//! Tests for synthetic events: dispatch through the capture and bubble phases, and stopping or
+//! preventing them along the way. Also covers hit testing, which is how a point in a window
+//! finds the component an event is for, and the `:hover`, `:active` and `:focus` states that
+//! pointer and focus events leave behind.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Mutex;
+
+use alchemy::{
+    headless, lazy_static, rsx, Component, ComponentKey, Error, Event, FocusEvent, PointerEvent, Props,
+    PseudoClass, RSX, SyntheticEvent, View, Window, RENDER_ENGINE
+};
+use alchemy::style_attributes::Point;
+
+use common::{background, children, content_view, open, setup, Log};
+
+lazy_static! {
+    static ref LOG: Log = Log::new();
+    static ref PROBES: Mutex<Vec<(String, ComponentKey)>> = Mutex::new(vec![]);
+}
+
+/// Wraps its children in a `View` styled with `look`, and registers its key under `name` so that
+/// tests can target it.
+#[derive(Props)]
+struct Probe {
+    #[props] name: String,
+    #[props] look: String,
+    key: ComponentKey
+}
+
+impl Component for Probe {
+    fn new(key: ComponentKey) -> Probe {
+        Probe { name: String::new(), look: String::new(), key: key }
+    }
+
+    fn component_did_mount(&mut self) {
+        PROBES.lock().unwrap().push((self.name.clone(), self.key));
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <View styles={self.look.as_str()}>{children}</View>
+        })
+    }
+}
+
+fn probe(name: &str) -> ComponentKey {
+    PROBES.lock().unwrap().iter().find(|(probe, _)| probe == name).unwrap().1
+}
+
+/// Hit tests `(x, y)` from the probe named `root`, returning the names of the probes along the
+/// path to whatever was hit.
+fn hit(root: &str, x: f32, y: f32) -> Vec<String> {
+    let (key, mut path) = match RENDER_ENGINE.hit_test(probe(root), Point { x: x, y: y }).unwrap() {
+        Some(hit) => hit,
+        None => { return vec![]; }
+    };
+
+    path.push(key);
+    let probes = PROBES.lock().unwrap();
+    path.iter().filter_map(|key| {
+        probes.iter().find(|(_, probe)| probe == key).map(|(name, _)| name.clone())
+    }).collect()
+}
+
+fn reset() {
+    PROBES.lock().unwrap().clear();
+    LOG.take();
+}
+
+/// A handler that logs `entry`, along with the phase it ran in.
+fn log<T>(entry: &'static str) -> impl Fn(&mut SyntheticEvent<T>) + Send + Sync + 'static {
+    move |event| LOG.push(format!("{} {:?}", entry, event.phase))
+}
+
+/// Like `log()`, but stops the event from going any further.
+fn stop<T>(entry: &'static str) -> impl Fn(&mut SyntheticEvent<T>) + Send + Sync + 'static {
+    move |event| {
+        LOG.push(format!("{} {:?}", entry, event.phase));
+        event.stop_propagation();
+    }
+}
+
+fn click() -> Event {
+    Event::Click(PointerEvent { x: 0., y: 0., button: 0 })
+}
+
+#[test]
+fn events_capture_down_to_the_target_and_bubble_back_up() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("capture and bubble", || rsx! {
+        <Probe name="outer" onClickCapture={log("outer capture")} onClick={log("outer bubble")}>
+            <Probe name="inner" onClickCapture={log("inner capture")} onClick={log("inner bubble")}>
+                <Probe name="target" onClickCapture={log("target capture")} onClick={log("target bubble")} />
+            </Probe>
+        </Probe>
+    });
+
+    let prevented = RENDER_ENGINE.dispatch_event(probe("target"), click()).unwrap();
+    assert!(!prevented);
+    assert_eq!(LOG.take(), [
+        "outer capture Capturing",
+        "inner capture Capturing",
+        "target capture AtTarget",
+        "target bubble AtTarget",
+        "inner bubble Bubbling",
+        "outer bubble Bubbling"
+    ]);
+
+    // Handlers for other events don't fire, and anything below the target isn't involved.
+    RENDER_ENGINE.dispatch_event(probe("inner"), Event::PointerUp(PointerEvent::default())).unwrap();
+    assert!(LOG.take().is_empty());
+
+    RENDER_ENGINE.dispatch_event(probe("inner"), click()).unwrap();
+    assert_eq!(LOG.take(), [
+        "outer capture Capturing",
+        "inner capture AtTarget",
+        "inner bubble AtTarget",
+        "outer bubble Bubbling"
+    ]);
+}
+
+#[test]
+fn stop_propagation_ends_dispatch_in_either_phase() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("stop propagation", || rsx! {
+        <Probe name="outer" onClickCapture={log("outer capture")} onClick={log("outer bubble")}>
+            <Probe name="capturing" onClickCapture={stop("capturing capture")} onClick={log("capturing bubble")}>
+                <Probe name="first" onClick={log("first bubble")} />
+            </Probe>
+            <Probe name="bubbling" onClick={stop("bubbling bubble")}>
+                <Probe name="second" onClick={log("second bubble")} />
+            </Probe>
+        </Probe>
+    });
+
+    RENDER_ENGINE.dispatch_event(probe("first"), click()).unwrap();
+    assert_eq!(LOG.take(), ["outer capture Capturing", "capturing capture Capturing"]);
+
+    RENDER_ENGINE.dispatch_event(probe("second"), click()).unwrap();
+    assert_eq!(LOG.take(), [
+        "outer capture Capturing",
+        "second bubble AtTarget",
+        "bubbling bubble Bubbling"
+    ]);
+}
+
+#[test]
+fn prevent_default_is_reported_without_stopping_dispatch() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("prevent default", || rsx! {
+        <Probe name="outer" onClick={log("outer bubble")}>
+            <Probe name="target" onClick={|event: &mut SyntheticEvent<PointerEvent>| {
+                LOG.push("target bubble");
+                event.prevent_default();
+            }} />
+        </Probe>
+    });
+
+    assert!(RENDER_ENGINE.dispatch_event(probe("target"), click()).unwrap());
+    assert_eq!(LOG.take(), ["target bubble", "outer bubble Bubbling"]);
+
+    assert!(!RENDER_ENGINE.dispatch_event(probe("outer"), click()).unwrap());
+    assert_eq!(LOG.take(), ["outer bubble AtTarget"]);
+}
+
+#[test]
+fn hit_testing_finds_the_topmost_visible_component() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("paint order", || rsx! {
+        <Probe name="stage" look="stage">
+            <Probe name="under" look="tile" />
+            <Probe name="over" look="tile" />
+        </Probe>
+    });
+
+    // Later siblings are painted over earlier ones.
+    assert_eq!(hit("stage", 50., 50.), ["stage", "over"]);
+    assert_eq!(hit("stage", 200., 200.), ["stage"]);
+    assert!(hit("stage", 400., 400.).is_empty());
+
+    reset();
+    let _window = open("display none", || rsx! {
+        <Probe name="stage" look="stage">
+            <Probe name="under" look="tile" />
+            <Probe name="over" look="tile hidden">
+                <Probe name="inside" look="tile" />
+            </Probe>
+        </Probe>
+    });
+
+    // Nothing under a `display: none` node can be hit, either.
+    assert_eq!(hit("stage", 50., 50.), ["stage", "under"]);
+}
+
+#[test]
+fn overflow_hidden_clips_hit_testing() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("clipping", || rsx! {
+        <Probe name="stage" look="stage">
+            <Probe name="clipped" look="corner clip">
+                <Probe name="clipped spill" look="tile" />
+            </Probe>
+            <Probe name="unclipped" look="corner lower">
+                <Probe name="unclipped spill" look="tile" />
+            </Probe>
+        </Probe>
+    });
+
+    // Both spill 50px out of their parent, but only the unclipped one can be hit out there.
+    assert_eq!(hit("stage", 160., 10.), ["stage", "clipped", "clipped spill"]);
+    assert_eq!(hit("stage", 190., 60.), ["stage"]);
+    assert_eq!(hit("stage", 190., 160.), ["stage", "unclipped", "unclipped spill"]);
+}
+
+const IDLE: (u8, u8, u8) = (0, 0, 0xff);
+const HOVERED: (u8, u8, u8) = (0xff, 0, 0);
+const PRESSED: (u8, u8, u8) = (0, 0xff, 0);
+const FOCUSED: (u8, u8, u8) = (0xff, 0xff, 0);
+const DISABLED: (u8, u8, u8) = (0x77, 0x77, 0x77);
+
+/// Opens a card with two chips in it, returning the window along with the nodes for the card and
+/// each chip.
+fn chips(title: &str) -> (Window, headless::NodeId, headless::NodeId, headless::NodeId) {
+    let window = open(title, || rsx! {
+        <Probe name="card" look="card">
+            <Probe name="a" look="chip" />
+            <Probe name="b" look="chip" />
+        </Probe>
+    });
+
+    let card = children(content_view(title))[0];
+    let nodes = children(card);
+    (window, card, nodes[0], nodes[1])
+}
+
+fn pointer(event: fn(PointerEvent) -> Event, name: &str) {
+    RENDER_ENGINE.dispatch_event(probe(name), event(PointerEvent::default())).unwrap();
+}
+
+fn focus(event: fn(FocusEvent) -> Event, name: &str) {
+    RENDER_ENGINE.dispatch_event(probe(name), event(FocusEvent::default())).unwrap();
+}
+
+#[test]
+fn hover_follows_the_pointer_along_with_its_ancestors() {
+    let _guard = setup();
+    reset();
+    let (_window, card, a, b) = chips("hover");
+    assert_eq!((background(card), background(a), background(b)), ((0, 0, 0), IDLE, IDLE));
+
+    pointer(Event::PointerMove, "a");
+    assert_eq!((background(card), background(a), background(b)), ((0x33, 0x33, 0x33), HOVERED, IDLE));
+
+    // Moving across to a sibling un-hovers the chip that was left, but not the card they share.
+    pointer(Event::PointerMove, "b");
+    assert_eq!((background(card), background(a), background(b)), ((0x33, 0x33, 0x33), IDLE, HOVERED));
+
+    // The card's `View` is rendered by its `Probe`, so it's underneath rather than along the path.
+    pointer(Event::PointerMove, "card");
+    assert_eq!((background(card), background(a), background(b)), ((0, 0, 0), IDLE, IDLE));
+}
+
+#[test]
+fn pressing_sets_active_until_release() {
+    let _guard = setup();
+    reset();
+    let (_window, card, a, b) = chips("active");
+
+    pointer(Event::PointerDown, "a");
+    assert_eq!((background(a), background(b)), (PRESSED, IDLE));
+
+    pointer(Event::PointerUp, "a");
+    assert_eq!((background(card), background(a), background(b)), ((0, 0, 0), IDLE, IDLE));
+}
+
+#[test]
+fn focus_only_applies_to_the_target() {
+    let _guard = setup();
+    reset();
+    let (_window, card, a, b) = chips("focus");
+
+    focus(Event::Focus, "a");
+    assert_eq!((background(card), background(a), background(b)), ((0, 0, 0), FOCUSED, IDLE));
+
+    // Focusing something else takes it away from whatever had it.
+    focus(Event::Focus, "b");
+    assert_eq!((background(a), background(b)), (IDLE, FOCUSED));
+
+    focus(Event::Blur, "b");
+    assert_eq!((background(a), background(b)), (IDLE, IDLE));
+}
+
+#[test]
+fn element_states_can_be_set_directly() {
+    let _guard = setup();
+    reset();
+    let (_window, _, a, b) = chips("disabled");
+
+    RENDER_ENGINE.set_element_state(probe("b"), PseudoClass::Disabled, true).unwrap();
+    assert_eq!((background(a), background(b)), (IDLE, DISABLED));
+
+    // `:disabled` comes last in the stylesheet, so it wins out over hovering.
+    pointer(Event::PointerMove, "b");
+    assert_eq!(background(b), DISABLED);
+
+    RENDER_ENGINE.set_element_state(probe("b"), PseudoClass::Disabled, false).unwrap();
+    assert_eq!(background(b), HOVERED);
+}
@@ -0,0 +1,90 @@
+//! Tests for the headless backend itself: that it records windows and nodes the way a native
+//! backend would be told to draw them, and that its runloop fires the app lifecycle and drains
+//! dispatched work in order.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Arc;
+
+use alchemy::{headless, rsx, AppDelegate, Text, View};
+
+use common::{children, content_view, flush, node, open, setup, Log};
+
+#[test]
+fn windows_and_nodes_are_recorded() {
+    let _guard = setup();
+
+    let window = open("recorded", || rsx! {
+        <View styles=["list"]>
+            <Text styles=["banner"]>"Hello"</Text>
+        </View>
+    });
+
+    let record = headless::windows().into_iter().find(|record| record.title == "recorded").unwrap();
+    assert_eq!(record.dimensions, (0., 0., 600., 600.));
+    assert!(record.visible);
+
+    let list = children(content_view("recorded"))[0];
+    assert_eq!(node(list).kind, headless::NodeKind::View);
+    assert_eq!(node(list).frame, headless::Frame { x: 0., y: 0., width: 600., height: 600. });
+
+    let text = node(children(list)[0]);
+    assert_eq!(text.kind, headless::NodeKind::Text);
+    assert_eq!(text.text, "Hello");
+    assert_eq!(text.parent, Some(list));
+    assert_eq!(text.frame, headless::Frame { x: 0., y: 0., width: 100., height: 40. });
+    assert_eq!((text.background_color.red, text.background_color.green, text.background_color.blue), (0x33, 0x66, 0x99));
+    assert_eq!((text.text_color.red, text.text_color.green, text.text_color.blue), (255, 255, 255));
+
+    window.close();
+    assert!(!headless::window(record.id).map_or(false, |record| record.visible));
+}
+
+struct Recorder(Arc<Log>);
+
+impl AppDelegate for Recorder {
+    fn will_finish_launching(&mut self) { self.0.push("will finish launching"); }
+    fn did_finish_launching(&mut self) { self.0.push("did finish launching"); }
+    fn will_become_active(&mut self) { self.0.push("will become active"); }
+    fn did_become_active(&mut self) { self.0.push("did become active"); }
+    fn will_resign_active(&mut self) { self.0.push("will resign active"); }
+    fn did_resign_active(&mut self) { self.0.push("did resign active"); }
+    fn will_terminate(&mut self) { self.0.push("will terminate"); }
+}
+
+#[test]
+fn the_runloop_fires_the_lifecycle_and_drains_dispatched_work() {
+    let _guard = setup();
+    let log = Arc::new(Log::new());
+
+    for i in 0..2 {
+        let log = log.clone();
+        headless::dispatch(move || log.push(format!("dispatched {}", i)));
+    }
+
+    alchemy::shared_app().run(Recorder(log.clone()));
+    assert_eq!(log.take(), [
+        "will finish launching",
+        "did finish launching",
+        "will become active",
+        "did become active",
+        "dispatched 0",
+        "dispatched 1",
+        "will resign active",
+        "did resign active",
+        "will terminate"
+    ]);
+
+    // Anything queued after `terminate()` waits for the next run.
+    headless::terminate();
+    let later = log.clone();
+    headless::dispatch(move || later.push("after terminate"));
+
+    alchemy::shared_app().run(Recorder(log.clone()));
+    assert!(!log.take().contains(&"after terminate".to_string()));
+
+    flush();
+    assert_eq!(log.take(), ["after terminate"]);
+}
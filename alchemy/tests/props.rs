@@ -0,0 +1,154 @@
+//! Tests for `#[derive(Props)]`: props are copied into the component whenever it's rendered,
+//! defaults fill in whatever isn't set, and required props that are missing surface as render
+//! errors.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Mutex;
+
+use alchemy::{lazy_static, rsx, text, Component, ComponentKey, ComponentProps, Error, Props, RSX, Text, View, RENDER_ENGINE};
+
+use common::{children, content_view, flush, node, open, setup, texts};
+
+lazy_static! {
+    static ref LABEL: Mutex<&'static str> = Mutex::new("");
+    static ref BADGE_KEYS: Mutex<Vec<ComponentKey>> = Mutex::new(vec![]);
+}
+
+#[derive(Props)]
+struct Badge {
+    #[props] label: String,
+    #[props(default = 3)] count: usize,
+    suffix: String
+}
+
+impl Component for Badge {
+    fn new(key: ComponentKey) -> Badge {
+        BADGE_KEYS.lock().unwrap().push(key);
+        Badge { label: String::new(), count: 0, suffix: String::new() }
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <Text styles=["row"]>{text!("{} {}{}", self.label, self.count, self.suffix)}</Text>
+        })
+    }
+}
+
+#[derive(Props)]
+struct Labelled {
+    #[props(required)] label: String
+}
+
+impl Component for Labelled {
+    fn new(_key: ComponentKey) -> Labelled {
+        Labelled { label: String::new() }
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <Text styles=["row"]>{text!("{}", self.label)}</Text>
+        })
+    }
+}
+
+#[derive(Props)]
+struct Tagged<T: Clone + Default + Send + Sync + 'static> {
+    #[props] value: T
+}
+
+/// Renders the error message in place of its children if any of them fail.
+#[derive(Props)]
+struct Fallback {
+    failed: Option<String>
+}
+
+impl Component for Fallback {
+    fn new(_key: ComponentKey) -> Fallback {
+        Fallback { failed: None }
+    }
+
+    fn is_error_boundary(&self) -> bool {
+        true
+    }
+
+    fn get_derived_state_from_error(&mut self, error: &Error) {
+        self.failed = Some(error.to_string());
+    }
+
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        match &self.failed {
+            Some(message) => Ok(rsx! {
+                <Text styles=["row"]>{text!("{}", message)}</Text>
+            }),
+
+            None => Ok(rsx! {
+                <View styles=["panel"]>{children}</View>
+            })
+        }
+    }
+}
+
+fn badges() -> RSX {
+    let label = *LABEL.lock().unwrap();
+
+    rsx! {
+        <View styles=["list"]>
+            <Badge label={label.to_string()} />
+            <Badge label="fixed" count={5usize} />
+        </View>
+    }
+}
+
+#[test]
+fn props_are_copied_in_and_state_is_left_alone() {
+    let _guard = setup();
+    BADGE_KEYS.lock().unwrap().clear();
+    *LABEL.lock().unwrap() = "a";
+    let window = open("props", badges);
+    let list = children(content_view("props"))[0];
+    assert_eq!(texts(list), ["a 3", "fixed 5"]);
+
+    let key = BADGE_KEYS.lock().unwrap()[0];
+    RENDER_ENGINE.set_state(key, |badge: &mut Badge| { badge.suffix = "!".into(); });
+    flush();
+    assert_eq!(texts(list), ["a 3!", "fixed 5"]);
+
+    *LABEL.lock().unwrap() = "b";
+    window.render();
+    assert_eq!(texts(list), ["b 3!", "fixed 5"]);
+}
+
+#[test]
+fn generic_components_take_their_props() {
+    let mut tagged = Tagged { value: 0usize };
+    let mut props = Tagged::<usize>::default_props();
+    assert_eq!(props.value, 0);
+
+    props.value = 7;
+    tagged.set_props(&props);
+    assert_eq!(tagged.value, 7);
+}
+
+#[test]
+fn missing_required_props_are_caught_as_render_errors() {
+    let _guard = setup();
+
+    let _window = open("missing props", || rsx! {
+        <View styles=["list"]>
+            <Fallback>
+                <Labelled />
+            </Fallback>
+            <Fallback>
+                <Labelled label="Present" />
+            </Fallback>
+        </View>
+    });
+
+    let list = children(content_view("missing props"))[0];
+    let nodes = children(list);
+    assert_eq!(node(nodes[0]).text, "<Labelled> is missing required props: label");
+    assert_eq!(texts(nodes[1]), ["Present"]);
+}
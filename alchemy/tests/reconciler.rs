@@ -0,0 +1,295 @@
+//! Tests for how the reconciler diffs children: matching them up by key, replacing them when
+//! their tag changes, handing new props to the ones that stay, and cleaning up after them when
+//! they're removed.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Mutex;
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use alchemy::{headless, lazy_static, rsx, text, Component, ComponentKey, Error, Props, RSX, Text, View, RENDER_ENGINE};
+
+use common::{children, content_view, flush, node, open, setup, texts, Log};
+
+lazy_static! {
+    static ref LOG: Log = Log::new();
+    static ref LABELS: Mutex<Vec<&'static str>> = Mutex::new(vec![]);
+    static ref PANEL_KEYS: Mutex<Vec<ComponentKey>> = Mutex::new(vec![]);
+}
+
+static SWAPPED: AtomicBool = AtomicBool::new(false);
+static GATE_RENDERS: AtomicUsize = AtomicUsize::new(0);
+static TICK: AtomicUsize = AtomicUsize::new(0);
+
+fn set_labels(labels: &[&'static str]) {
+    *LABELS.lock().unwrap() = labels.to_vec();
+}
+
+#[derive(Props)]
+struct Item {
+    #[props] label: String
+}
+
+impl Component for Item {
+    fn new(_key: ComponentKey) -> Item {
+        Item { label: String::new() }
+    }
+
+    fn component_did_mount(&mut self) {
+        LOG.push(format!("mount {}", self.label));
+    }
+
+    fn component_will_unmount(&mut self) {
+        LOG.push(format!("unmount {}", self.label));
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <Text styles=["row"]>{text!("{}", self.label)}</Text>
+        })
+    }
+}
+
+#[derive(Props)]
+struct Panel {
+    #[props] label: String
+}
+
+impl Component for Panel {
+    fn new(key: ComponentKey) -> Panel {
+        PANEL_KEYS.lock().unwrap().push(key);
+        Panel { label: String::new() }
+    }
+
+    fn component_will_unmount(&mut self) {
+        LOG.push(format!("unmount panel {}", self.label));
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <View styles=["panel"]>
+                <Item label={self.label.clone()} />
+            </View>
+        })
+    }
+}
+
+fn items() -> RSX {
+    let labels = LABELS.lock().unwrap().clone();
+
+    rsx! {
+        <View styles=["list"]>
+            {labels.iter().map(|label| rsx! {
+                <Item key={label} label={label.to_string()} />
+            })}
+        </View>
+    }
+}
+
+fn panels() -> RSX {
+    let labels = LABELS.lock().unwrap().clone();
+
+    rsx! {
+        <View styles=["list"]>
+            {labels.iter().map(|label| rsx! {
+                <Panel key={label} label={label.to_string()} />
+            })}
+        </View>
+    }
+}
+
+/// Only re-renders when its label changes, ignoring `tick`.
+#[derive(Props)]
+struct Gate {
+    #[props] label: String,
+    #[props] tick: usize
+}
+
+impl Component for Gate {
+    fn new(_key: ComponentKey) -> Gate {
+        Gate { label: String::new(), tick: 0 }
+    }
+
+    fn should_component_update(&self, next_props: Option<&Any>) -> bool {
+        let next = next_props.and_then(|props| props.downcast_ref::<GateProps>()).unwrap();
+        LOG.push(format!("should update {} -> {}", self.label, next.label));
+        next.label != self.label
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        GATE_RENDERS.fetch_add(1, Ordering::SeqCst);
+
+        Ok(rsx! {
+            <View styles=["panel"]>
+                <Item label={format!("{} {}", self.label, self.tick)} />
+            </View>
+        })
+    }
+}
+
+fn gated() -> RSX {
+    let label = LABELS.lock().unwrap()[0];
+    let tick = TICK.load(Ordering::SeqCst);
+
+    rsx! {
+        <View styles=["list"]>
+            <Gate label={label.to_string()} tick={tick} />
+        </View>
+    }
+}
+
+fn swap() -> RSX {
+    let middle = match SWAPPED.load(Ordering::SeqCst) {
+        true => rsx! { <Text styles=["box"]>"Swapped"</Text> },
+        false => rsx! { <View styles=["box"] /> }
+    };
+
+    rsx! {
+        <View styles=["list"]>
+            <Text styles=["row"]>"First"</Text>
+            {middle}
+            <Text styles=["row"]>"Last"</Text>
+        </View>
+    }
+}
+
+#[test]
+fn keyed_children_are_moved_rather_than_remounted() {
+    let _guard = setup();
+    LOG.take();
+    set_labels(&["A", "B", "C"]);
+    let window = open("keyed reorder", items);
+    let list = children(content_view("keyed reorder"))[0];
+    let before = children(list);
+    assert_eq!(texts(list), ["A", "B", "C"]);
+    assert_eq!(LOG.take(), ["mount A", "mount B", "mount C"]);
+
+    set_labels(&["C", "A", "B"]);
+    window.render();
+    assert_eq!(texts(list), ["C", "A", "B"]);
+    assert_eq!(children(list), [before[2], before[0], before[1]]);
+    assert_eq!(node(before[2]).frame.y, 0.);
+    assert_eq!(node(before[0]).frame.y, 20.);
+    assert_eq!(node(before[1]).frame.y, 40.);
+    assert!(LOG.take().is_empty());
+
+    set_labels(&["Z", "C", "A", "B"]);
+    window.render();
+    assert_eq!(texts(list), ["Z", "C", "A", "B"]);
+    assert_eq!(children(list)[1..], [before[2], before[0], before[1]]);
+    assert_eq!(LOG.take(), ["mount Z"]);
+}
+
+#[test]
+fn duplicate_keys_dont_leak_components() {
+    let _guard = setup();
+    LOG.take();
+    set_labels(&["A", "A", "B"]);
+    let window = open("duplicate keys", items);
+    let list = children(content_view("duplicate keys"))[0];
+    assert_eq!(texts(list), ["A", "A", "B"]);
+
+    set_labels(&["B", "A"]);
+    window.render();
+    assert_eq!(texts(list), ["B", "A"]);
+
+    set_labels(&[]);
+    window.render();
+    assert!(children(list).is_empty());
+
+    let log = LOG.take();
+    let mounts = log.iter().filter(|entry| entry.starts_with("mount")).count();
+    let unmounts = log.iter().filter(|entry| entry.starts_with("unmount")).count();
+    assert_eq!(mounts, unmounts);
+}
+
+#[test]
+fn should_component_update_sees_the_incoming_props() {
+    let _guard = setup();
+    set_labels(&["A"]);
+    TICK.store(0, Ordering::SeqCst);
+    GATE_RENDERS.store(0, Ordering::SeqCst);
+    let window = open("gated", gated);
+    let panel = children(children(content_view("gated"))[0])[0];
+    let row = children(panel);
+    assert_eq!(texts(panel), ["A 0"]);
+    assert_eq!(LOG.take(), ["mount A 0"]);
+
+    // Returning false skips the render, and leaves everything underneath alone.
+    TICK.store(1, Ordering::SeqCst);
+    window.render();
+    assert_eq!(GATE_RENDERS.load(Ordering::SeqCst), 1);
+    assert_eq!(children(panel), row);
+    assert_eq!(texts(panel), ["A 0"]);
+    assert_eq!(LOG.take(), ["should update A -> A"]);
+
+    // The props were still set, so they show up on the next render.
+    set_labels(&["B"]);
+    TICK.store(2, Ordering::SeqCst);
+    window.render();
+    assert_eq!(GATE_RENDERS.load(Ordering::SeqCst), 2);
+    assert_eq!(children(panel), row);
+    assert_eq!(texts(panel), ["B 2"]);
+    assert_eq!(LOG.take(), ["should update A -> B"]);
+}
+
+#[test]
+fn changing_tag_replaces_the_node_in_place() {
+    let _guard = setup();
+    SWAPPED.store(false, Ordering::SeqCst);
+    let window = open("tag replace", swap);
+    let list = children(content_view("tag replace"))[0];
+    let view = children(list)[1];
+    assert_eq!(node(view).kind, headless::NodeKind::View);
+
+    SWAPPED.store(true, Ordering::SeqCst);
+    window.render();
+    let nodes = children(list);
+    assert_eq!(nodes.len(), 3);
+    assert!(headless::node(view).is_none());
+
+    let text = node(nodes[1]);
+    assert_eq!(text.kind, headless::NodeKind::Text);
+    assert_eq!(text.text, "Swapped");
+    assert_eq!(text.frame, headless::Frame { x: 0., y: 20., width: 100., height: 50. });
+    assert_eq!(node(nodes[2]).frame.y, 70.);
+
+    SWAPPED.store(false, Ordering::SeqCst);
+    window.render();
+    let nodes = children(list);
+    assert_eq!(node(nodes[1]).kind, headless::NodeKind::View);
+    assert_eq!(node(nodes[1]).frame, headless::Frame { x: 0., y: 20., width: 100., height: 50. });
+    assert_eq!(texts(list), ["First", "", "Last"]);
+}
+
+#[test]
+fn unmounting_removes_nodes_and_runs_cleanup() {
+    let _guard = setup();
+    PANEL_KEYS.lock().unwrap().clear();
+    set_labels(&["P", "Q"]);
+    let window = open("unmount", panels);
+    let list = children(content_view("unmount"))[0];
+    let before = children(list);
+    let removed = vec![before[0], children(before[0])[0]];
+    LOG.take();
+
+    set_labels(&["Q"]);
+    window.render();
+    assert_eq!(children(list), [before[1]]);
+    assert!(removed.iter().all(|id| headless::node(*id).is_none()));
+    assert_eq!(node(before[1]).frame.y, 0.);
+
+    let mut log = LOG.take();
+    log.sort();
+    assert_eq!(log, ["unmount P", "unmount panel P"]);
+
+    // Updates queued for a component that's gone are dropped.
+    let key = PANEL_KEYS.lock().unwrap()[0];
+    RENDER_ENGINE.set_state(key, |panel: &mut Panel| { panel.label = "R".into(); });
+    flush();
+    assert_eq!(children(list), [before[1]]);
+    assert!(LOG.take().is_empty());
+}
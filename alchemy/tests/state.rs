@@ -0,0 +1,195 @@
+//! Tests for `RenderEngine::set_state()` and `force_update()`: updates are batched until the
+//! runloop flushes them, and only the affected subtree re-renders and re-lays out. Restyling, as
+//! happens when themes are reloaded, leaves state alone entirely.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use alchemy::{headless, lazy_static, rsx, styles, text, Component, ComponentKey, Error, Props, RSX, Text, View, RENDER_ENGINE};
+
+use common::{children, content_view, flush, node, open, setup, texts, Log};
+
+lazy_static! {
+    static ref LOG: Log = Log::new();
+    static ref KEYS: Mutex<Vec<ComponentKey>> = Mutex::new(vec![]);
+}
+
+static RENDERS: AtomicUsize = AtomicUsize::new(0);
+static EXTERNAL: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Props)]
+struct Counter {
+    count: usize
+}
+
+impl Component for Counter {
+    fn new(key: ComponentKey) -> Counter {
+        KEYS.lock().unwrap().push(key);
+        Counter { count: 0 }
+    }
+
+    fn component_did_update(&mut self) {
+        LOG.push(format!("did update {}", self.count));
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        RENDERS.fetch_add(1, Ordering::SeqCst);
+        let rows: Vec<usize> = (0..self.count).collect();
+
+        Ok(rsx! {
+            <View styles=["counter"]>
+                {rows.iter().map(|row| rsx! {
+                    <Text key={row} styles=["row"]>{text!("row {}", row)}</Text>
+                })}
+            </View>
+        })
+    }
+}
+
+#[derive(Props)]
+struct Reader;
+
+impl Component for Reader {
+    fn new(key: ComponentKey) -> Reader {
+        KEYS.lock().unwrap().push(key);
+        Reader
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <Text styles=["row"]>{text!("{}", EXTERNAL.load(Ordering::SeqCst))}</Text>
+        })
+    }
+}
+
+fn reset() {
+    KEYS.lock().unwrap().clear();
+    RENDERS.store(0, Ordering::SeqCst);
+    LOG.take();
+}
+
+#[test]
+fn updates_are_batched_until_the_runloop_flushes() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("batching", || rsx! {
+        <View styles=["list"]>
+            <Text styles=["row"]>"Above"</Text>
+            <Counter />
+            <Text styles=["row"]>"Below"</Text>
+        </View>
+    });
+
+    let list = children(content_view("batching"))[0];
+    let counter = children(list)[1];
+    let key = KEYS.lock().unwrap()[0];
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+
+    RENDER_ENGINE.set_state(key, |counter: &mut Counter| { counter.count += 1; });
+    RENDER_ENGINE.set_state(key, |counter: &mut Counter| { counter.count += 1; });
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 1);
+    assert!(children(counter).is_empty());
+
+    flush();
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 2);
+    assert_eq!(LOG.take(), ["did update 2"]);
+    assert_eq!(texts(counter), ["row 0", "row 1"]);
+
+    // The counter grew, so its siblings had to be laid out again too.
+    assert_eq!(node(counter).frame.height, 40.);
+    assert_eq!(node(children(counter)[1]).frame.y, 20.);
+    assert_eq!(node(children(list)[2]).frame.y, 60.);
+}
+
+#[test]
+fn updates_inside_a_fixed_size_node_only_relayout_that_node() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("partial relayout", || rsx! {
+        <View styles=["list"]>
+            <Text styles=["row"]>"Above"</Text>
+            <View styles=["fixed"]>
+                <Counter />
+            </View>
+            <Text styles=["row"]>"Below"</Text>
+        </View>
+    });
+
+    let list = children(content_view("partial relayout"))[0];
+    let fixed = children(list)[1];
+    let counter = children(fixed)[0];
+    let key = KEYS.lock().unwrap()[0];
+
+    RENDER_ENGINE.set_state(key, |counter: &mut Counter| { counter.count = 3; });
+    flush();
+    assert_eq!(texts(counter), ["row 0", "row 1", "row 2"]);
+    assert_eq!(node(counter).frame.height, 60.);
+    assert_eq!(node(children(counter)[2]).frame.y, 40.);
+
+    // Nothing outside of the fixed-size node moved.
+    assert_eq!(node(fixed).frame, headless::Frame { x: 0., y: 20., width: 200., height: 100. });
+    assert_eq!(node(children(list)[2]).frame.y, 120.);
+}
+
+#[test]
+fn force_update_rerenders_without_changing_state() {
+    let _guard = setup();
+    reset();
+    EXTERNAL.store(1, Ordering::SeqCst);
+
+    let _window = open("force update", || rsx! {
+        <View styles=["list"]>
+            <Reader />
+        </View>
+    });
+
+    let list = children(content_view("force update"))[0];
+    assert_eq!(texts(list), ["1"]);
+
+    EXTERNAL.store(2, Ordering::SeqCst);
+    let key = KEYS.lock().unwrap()[0];
+    RENDER_ENGINE.force_update(key);
+    assert_eq!(texts(list), ["1"]);
+
+    flush();
+    assert_eq!(texts(list), ["2"]);
+}
+
+#[test]
+fn restyling_keeps_component_state_without_re_rendering() {
+    let _guard = setup();
+    reset();
+
+    let _window = open("restyle", || rsx! {
+        <View styles=["list"]>
+            <Counter />
+        </View>
+    });
+
+    let key = KEYS.lock().unwrap()[0];
+    RENDER_ENGINE.set_state(key, |counter: &mut Counter| { counter.count = 2; });
+    flush();
+    RENDERS.store(0, Ordering::SeqCst);
+
+    // This is how reloaded theme files are swapped in, too.
+    alchemy::shared_app().replace_styles("default", "restyle", styles! {
+        row { height: 30px; }
+    });
+
+    let counter = children(children(content_view("restyle"))[0])[0];
+    let rows = children(counter);
+    assert_eq!(texts(counter), ["row 0", "row 1"]);
+    assert_eq!((node(rows[0]).frame.height, node(rows[1]).frame.y), (30., 30.));
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 0);
+
+    alchemy::shared_app().unregister_styles("default", "restyle");
+    assert_eq!(children(counter), rows);
+    assert_eq!((node(rows[0]).frame.height, node(rows[1]).frame.y), (20., 20.));
+    assert_eq!(RENDERS.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,482 @@
+//! Tests for how stylesheets end up applied to rendered nodes: what the rules resolve to (and what
+//! nodes inherit from their ancestors), which
+//! of them win (within a stylesheet, and between stylesheets layered on the same theme), and
+//! which `@media` queries they're under. Also covers themes, whether picked for the whole app or
+//! for a subtree.
+
+#![recursion_limit="256"]
+
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use alchemy::{headless, rsx, styles, ColorScheme, Component, ComponentKey, Error, Props, RSX, Text, View};
+
+use common::{background, children, content_view, node, open, setup};
+
+const RED: (u8, u8, u8) = (255, 0, 0);
+const GREEN: (u8, u8, u8) = (0, 255, 0);
+const BLUE: (u8, u8, u8) = (0, 0, 255);
+const YELLOW: (u8, u8, u8) = (255, 255, 0);
+
+static SHOW_NESTED: AtomicBool = AtomicBool::new(false);
+
+/// A composite component, so that the natively backed nodes it renders sit a level below it. It
+/// renders a `panel` with a single node styled with `look` in it.
+#[derive(Props)]
+struct Nested {
+    #[props] look: String
+}
+
+impl Component for Nested {
+    fn new(_key: ComponentKey) -> Nested {
+        Nested { look: String::new() }
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <View styles=["panel"]>
+                <View styles={self.look.as_str()} />
+            </View>
+        })
+    }
+}
+
+/// A composite component that renders some text, for checking what it inherits.
+#[derive(Props)]
+struct Caption;
+
+impl Component for Caption {
+    fn new(_key: ComponentKey) -> Caption {
+        Caption
+    }
+
+    fn render(&self, _children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(rsx! {
+            <Text styles=["row"]>"Caption"</Text>
+        })
+    }
+}
+
+/// Returns the text color of the node at `id`, as `(red, green, blue)`.
+fn text_color(id: headless::NodeId) -> (u8, u8, u8) {
+    let color = node(id).text_color;
+    (color.red, color.green, color.blue)
+}
+
+#[test]
+fn rules_apply_in_order_of_specificity_then_source_order() {
+    let _guard = setup();
+
+    let _window = open("specificity", || rsx! {
+        <View styles=["list", "palette"]>
+            <View styles=["swatch"] />
+            <View styles=["nested"] />
+            <View styles=["panel"]>
+                <View styles=["nested"] />
+            </View>
+            <Text styles=["label"]>"Label"</Text>
+            <View styles=["first", "second"] />
+        </View>
+    });
+
+    let palette = children(content_view("specificity"))[0];
+    let nodes = children(palette);
+    assert_eq!(background(nodes[0]), RED);
+    assert_eq!(background(nodes[1]), GREEN);
+    assert_eq!(background(children(nodes[2])[0]), BLUE);
+    assert_eq!(background(nodes[3]), YELLOW);
+    assert_eq!(background(nodes[4]), (0x22, 0x22, 0x22));
+}
+
+#[test]
+fn selectors_match_subtrees_mounted_after_the_first_render() {
+    let _guard = setup();
+    SHOW_NESTED.store(false, Ordering::SeqCst);
+
+    let window = open("late swatches", || {
+        let swatches = match SHOW_NESTED.load(Ordering::SeqCst) {
+            true => rsx! { <Nested look="swatch" /> },
+            false => RSX::None
+        };
+
+        rsx! {
+            <View styles=["list", "palette"]>
+                {swatches}
+            </View>
+        }
+    });
+
+    let palette = children(content_view("late swatches"))[0];
+    assert!(children(palette).is_empty());
+
+    SHOW_NESTED.store(true, Ordering::SeqCst);
+    window.render();
+    let panel = children(palette)[0];
+    assert_eq!(background(children(panel)[0]), RED);
+}
+
+#[test]
+fn later_stylesheets_win_regardless_of_specificity() {
+    let _guard = setup();
+    let app = alchemy::shared_app();
+
+    let _window = open("layering", || rsx! {
+        <View styles=["list", "palette"]>
+            <View styles=["swatch"] />
+        </View>
+    });
+
+    let swatch = || children(children(content_view("layering"))[0])[0];
+    assert_eq!(background(swatch()), RED);
+
+    app.replace_styles("default", "base", styles! {
+        swatch { background-color: #ffff00; }
+    });
+    assert_eq!(background(swatch()), YELLOW);
+
+    app.replace_styles("default", "overrides", styles! {
+        swatch { background-color: #00ff00; }
+    });
+    assert_eq!(background(swatch()), GREEN);
+
+    // Replacing a stylesheet keeps its place, underneath anything registered after it.
+    app.replace_styles("default", "base", styles! {
+        swatch { width: 30px; background-color: #0000ff; }
+    });
+    assert_eq!(background(swatch()), GREEN);
+    assert_eq!(node(swatch()).frame.width, 30.);
+
+    app.unregister_styles("default", "overrides");
+    assert_eq!(background(swatch()), BLUE);
+
+    app.unregister_styles("default", "base");
+    assert_eq!(background(swatch()), RED);
+    assert_eq!(node(swatch()).frame.width, 50.);
+}
+
+/// Registers a `night` theme, which only overrides the color of swatches.
+fn register_night_theme() {
+    alchemy::shared_app().replace_styles("night", "night", styles! {
+        swatch { background-color: #00ff00; }
+    });
+}
+
+#[test]
+fn switching_themes_restyles_open_windows() {
+    let _guard = setup();
+    let app = alchemy::shared_app();
+    register_night_theme();
+
+    let _window = open("switching", || rsx! {
+        <View styles=["list", "palette"]>
+            <View styles=["swatch"] />
+        </View>
+    });
+
+    let swatch = || children(children(content_view("switching"))[0])[0];
+    assert_eq!(background(swatch()), RED);
+
+    // Anything the theme doesn't set falls back to `default`.
+    app.set_theme("night");
+    assert_eq!(app.theme(), "night");
+    assert_eq!(background(swatch()), GREEN);
+    assert_eq!(node(swatch()).frame.width, 50.);
+
+    app.set_theme("default");
+    assert_eq!(background(swatch()), RED);
+    app.unregister_styles("night", "night");
+}
+
+#[test]
+fn subtrees_can_pick_their_own_theme() {
+    let _guard = setup();
+    register_night_theme();
+    SHOW_NESTED.store(false, Ordering::SeqCst);
+
+    let window = open("scoped themes", || {
+        let nested = match SHOW_NESTED.load(Ordering::SeqCst) {
+            true => rsx! { <Nested look="swatch" /> },
+            false => RSX::None
+        };
+
+        rsx! {
+            <View styles=["list", "palette"]>
+                <View styles=["swatch"] />
+                <View styles=["panel"] theme="night">
+                    <View styles=["swatch"] />
+                    {nested}
+                </View>
+            </View>
+        }
+    });
+
+    let palette = children(content_view("scoped themes"))[0];
+    let nodes = children(palette);
+    assert_eq!(background(nodes[0]), RED);
+    assert_eq!(background(children(nodes[1])[0]), GREEN);
+    assert_eq!(node(children(nodes[1])[0]).frame.width, 50.);
+
+    // Whatever mounts under the themed subtree later on picks its theme up, too.
+    SHOW_NESTED.store(true, Ordering::SeqCst);
+    window.render();
+    let panel = children(nodes[1])[1];
+    assert_eq!(background(children(panel)[0]), GREEN);
+
+    alchemy::shared_app().unregister_styles("night", "night");
+}
+
+#[test]
+fn custom_properties_are_inherited_and_overridden_by_subtrees() {
+    let _guard = setup();
+
+    let _window = open("variables", || rsx! {
+        <View styles=["list"]>
+            <View styles=["list", "tokens"]>
+                <View styles=["accented"] />
+                <View styles=["panel", "retint"]>
+                    <View styles=["accented"] />
+                </View>
+                <View styles=["panel", "chained"]>
+                    <View styles=["accented"] />
+                </View>
+                <View styles=["unresolved"] />
+            </View>
+            <View styles=["accented"] />
+        </View>
+    });
+
+    let list = children(content_view("variables"))[0];
+    let tokens = children(list)[0];
+    let nodes = children(tokens);
+    assert_eq!(background(nodes[0]), RED);
+    assert_eq!((node(nodes[0]).frame.width, node(nodes[0]).frame.height), (50., 50.));
+    assert_eq!(background(children(nodes[1])[0]), GREEN);
+    assert_eq!(background(children(nodes[2])[0]), YELLOW);
+
+    // A reference that can't be resolved drops the declaration, rather than what came before it.
+    assert_eq!(background(nodes[3]), (0x22, 0x22, 0x22));
+
+    // Outside of `.tokens`, only the fallbacks are left.
+    let outside = children(list)[1];
+    assert_eq!(background(outside), BLUE);
+    assert_eq!((node(outside).frame.width, node(outside).frame.height), (10., 10.));
+}
+
+#[test]
+fn custom_properties_resolve_for_subtrees_mounted_after_the_first_render() {
+    let _guard = setup();
+    SHOW_NESTED.store(false, Ordering::SeqCst);
+
+    let window = open("late variables", || {
+        let nested = match SHOW_NESTED.load(Ordering::SeqCst) {
+            true => rsx! { <Nested look="accented" /> },
+            false => RSX::None
+        };
+
+        rsx! {
+            <View styles=["list", "tokens"]>
+                {nested}
+            </View>
+        }
+    });
+
+    SHOW_NESTED.store(true, Ordering::SeqCst);
+    window.render();
+    let panel = children(children(content_view("late variables"))[0])[0];
+    assert_eq!(background(children(panel)[0]), RED);
+}
+
+#[test]
+fn media_queries_follow_the_window_and_color_scheme() {
+    let _guard = setup();
+
+    let mut window = open("media", || rsx! {
+        <View styles=["list"]>
+            <View styles=["responsive"] />
+        </View>
+    });
+
+    let responsive = || children(children(content_view("media"))[0])[0];
+    assert_eq!(background(responsive()), BLUE);
+
+    window.set_dimensions(0., 0., 400., 600.);
+    window.render();
+    assert_eq!(background(responsive()), RED);
+
+    window.set_dimensions(0., 0., 600., 600.);
+    headless::set_color_scheme(ColorScheme::Dark);
+    window.render();
+    headless::set_color_scheme(ColorScheme::Light);
+    assert_eq!(background(responsive()), GREEN);
+
+    window.render();
+    assert_eq!(background(responsive()), BLUE);
+}
+
+#[test]
+fn media_queries_apply_to_subtrees_mounted_after_the_first_render() {
+    let _guard = setup();
+    SHOW_NESTED.store(false, Ordering::SeqCst);
+
+    let mut window = open("late media", || {
+        let responsive = match SHOW_NESTED.load(Ordering::SeqCst) {
+            true => rsx! { <Nested look="responsive" /> },
+            false => RSX::None
+        };
+
+        rsx! {
+            <View styles=["list"]>
+                {responsive}
+            </View>
+        }
+    });
+
+    // The window's 600px wide, so `max-width: 500px` shouldn't match.
+    SHOW_NESTED.store(true, Ordering::SeqCst);
+    window.render();
+    let responsive = || children(children(children(content_view("late media"))[0])[0])[0];
+    assert_eq!(background(responsive()), BLUE);
+
+    window.set_dimensions(0., 0., 400., 600.);
+    window.render();
+    assert_eq!(background(responsive()), RED);
+}
+
+#[test]
+fn text_properties_are_inherited_through_views_and_composites() {
+    let _guard = setup();
+
+    let _window = open("inheritance", || rsx! {
+        <View styles=["list", "ink"]>
+            <Text styles=["row"]>"Direct"</Text>
+            <View styles=["panel"]>
+                <Text styles=["row"]>"Nested"</Text>
+            </View>
+            <Caption />
+            <Text styles=["row", "plain"]>"Initial"</Text>
+            <Text styles=["row", "recolored", "inherit"]>"Inherit"</Text>
+            <Nested look="measure" />
+        </View>
+    });
+
+    let list = children(content_view("inheritance"))[0];
+    let nodes = children(list);
+    assert_eq!(text_color(nodes[0]), RED);
+    assert_eq!(text_color(children(nodes[1])[0]), RED);
+    assert_eq!(text_color(nodes[2]), RED);
+    assert_eq!(node(nodes[3]).text_color.alpha, 0);
+    assert_eq!(text_color(nodes[4]), RED);
+
+    // `font-size` comes through too, which is what `em` lengths go off of.
+    assert_eq!(node(children(nodes[5])[0]).frame.width, 80.);
+}
+
+#[test]
+fn text_properties_are_inherited_by_subtrees_mounted_after_the_first_render() {
+    let _guard = setup();
+    SHOW_NESTED.store(false, Ordering::SeqCst);
+
+    let window = open("late inheritance", || {
+        let caption = match SHOW_NESTED.load(Ordering::SeqCst) {
+            true => rsx! { <Caption /> },
+            false => RSX::None
+        };
+
+        rsx! {
+            <View styles=["list", "ink"]>
+                <View styles=["panel"]>
+                    {caption}
+                </View>
+            </View>
+        }
+    });
+
+    SHOW_NESTED.store(true, Ordering::SeqCst);
+    window.render();
+    let panel = children(children(content_view("late inheritance"))[0])[0];
+    assert_eq!(text_color(children(panel)[0]), RED);
+}
+
+#[test]
+fn relative_font_sizes_compound_through_ancestors() {
+    let _guard = setup();
+
+    let _window = open("font sizes", || rsx! {
+        <View styles=["list", "type"]>
+            <View styles=["measure"] />
+            <View styles=["half", "measure"] />
+            <View styles=["half"]>
+                <View styles=["half", "measure"] />
+            </View>
+            <View styles=["double", "measure"] />
+            <View styles=["double"]>
+                <View styles=["half", "measure"] />
+            </View>
+        </View>
+    });
+
+    let nodes = children(children(content_view("font sizes"))[0]);
+    assert_eq!(node(nodes[0]).frame.width, 80.);
+    assert_eq!(node(nodes[1]).frame.width, 40.);
+    assert_eq!(node(children(nodes[2])[0]).frame.width, 20.);
+    assert_eq!(node(nodes[3]).frame.width, 160.);
+    assert_eq!(node(children(nodes[4])[0]).frame.width, 80.);
+}
+
+#[test]
+fn em_lengths_resolve_for_margin_padding_size_and_flex_basis() {
+    let _guard = setup();
+
+    let _window = open("em lengths", || rsx! {
+        <View styles=["list"]>
+            <View styles=["em-box"]>
+                <View styles=["dot"] />
+            </View>
+            <View styles=["em-row"]>
+                <View styles=["em-basis"] />
+            </View>
+        </View>
+    });
+
+    let nodes = children(children(content_view("em lengths"))[0]);
+    let em_box = node(nodes[0]);
+    assert_eq!((em_box.frame.x, em_box.frame.width, em_box.frame.height), (10., 30., 50.));
+    assert_eq!(node(children(nodes[0])[0]).frame.y, 20.);
+    assert_eq!(node(children(nodes[1])[0]).frame.width, 40.);
+}
+
+#[test]
+fn viewport_lengths_follow_the_window_size() {
+    let _guard = setup();
+
+    let mut window = open("viewport lengths", || rsx! {
+        <View styles=["list"]>
+            <View styles=["viewport"] />
+        </View>
+    });
+
+    let viewport = || node(children(children(content_view("viewport lengths"))[0])[0]).frame;
+    assert_eq!((viewport().width, viewport().height), (300., 60.));
+
+    window.set_dimensions(0., 0., 400., 500.);
+    window.render();
+    assert_eq!((viewport().width, viewport().height), (200., 50.));
+}
+
+#[test]
+fn border_widths_take_up_space_in_layout() {
+    let _guard = setup();
+
+    let _window = open("borders", || rsx! {
+        <View styles=["list"]>
+            <View styles=["bordered"]>
+                <View styles=["dot"] />
+            </View>
+        </View>
+    });
+
+    let bordered = children(children(content_view("borders"))[0])[0];
+    let dot = node(children(bordered)[0]).frame;
+    assert_eq!((dot.x, dot.y), (6., 4.));
+}
@@ -11,6 +11,7 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{msg_send, class, sel, sel_impl};
 
+use alchemy_lifecycle::native::AppHandle;
 use alchemy_lifecycle::traits::AppDelegate;
 
 static ALCHEMY_APP_PTR: &str = "alchemyParentAppPtr";
@@ -27,7 +28,7 @@ impl App {
     /// Creates an NSAutoReleasePool, configures various NSApplication properties (e.g, activation
     /// policies), injects an `NSObject` delegate wrapper, and retains everything on the
     /// Objective-C side of things.
-    pub fn new<T: AppDelegate>(parent_app_ptr: *const T) -> Self {
+    pub fn new(parent_app: AppHandle) -> Self {
         let inner = unsafe {
             let _pool = cocoa::foundation::NSAutoreleasePool::new(nil);
             let app = cocoa::appkit::NSApp();
@@ -36,9 +37,11 @@ impl App {
         };
         
         let delegate = unsafe {
-            let delegate_class = register_app_delegate_class::<T>();
+            // The handle is boxed for the delegate to point at, and freed when this is dropped.
+            let app_ptr = Box::into_raw(Box::new(parent_app));
+            let delegate_class = register_app_delegate_class();
             let delegate: id = msg_send![delegate_class, new];
-            (&mut *delegate).set_ivar(ALCHEMY_APP_PTR, parent_app_ptr as usize);
+            (&mut *delegate).set_ivar(ALCHEMY_APP_PTR, app_ptr as usize);
             msg_send![&*inner, setDelegate:delegate];
             Id::from_ptr(delegate)
         };
@@ -60,72 +63,65 @@ impl App {
     }
 }
 
-/// Fires when the Application Delegate receives a `applicationWillFinishLaunching` notification.
-extern fn will_finish_launching<T: AppDelegate>(this: &Object, _: Sel, _: id) {
+impl Drop for App {
+    /// Breaks the delegate link on the Objective-C side, and frees the `AppHandle` the
+    /// delegate was holding.
+    fn drop(&mut self) {
+        unsafe {
+            msg_send![&*self.inner, setDelegate:nil];
+
+            let app_ptr: usize = *self.delegate.get_ivar(ALCHEMY_APP_PTR);
+            Box::from_raw(app_ptr as *mut AppHandle);
+        }
+    }
+}
+
+/// Retrieves (a copy of) the `AppHandle` stored on the Application Delegate.
+fn app_handle(this: &Object) -> AppHandle {
     unsafe {
         let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).will_finish_launching();
-    };
+        *(app_ptr as *const AppHandle)
+    }
+}
+
+/// Fires when the Application Delegate receives a `applicationWillFinishLaunching` notification.
+extern fn will_finish_launching(this: &Object, _: Sel, _: id) {
+    app_handle(this).will_finish_launching();
 }
 
 /// Fires when the Application Delegate receives a `applicationDidFinishLaunching` notification.
-extern fn did_finish_launching<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).did_finish_launching();
-    };
+extern fn did_finish_launching(this: &Object, _: Sel, _: id) {
+    app_handle(this).did_finish_launching();
 }
 
 /// Fires when the Application Delegate receives a `applicationWillBecomeActive` notification.
-extern fn will_become_active<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).will_become_active();
-    };
+extern fn will_become_active(this: &Object, _: Sel, _: id) {
+    app_handle(this).will_become_active();
 }
 
 /// Fires when the Application Delegate receives a `applicationDidBecomeActive` notification.
-extern fn did_become_active<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).did_become_active();
-    };
+extern fn did_become_active(this: &Object, _: Sel, _: id) {
+    app_handle(this).did_become_active();
 }
 
 /// Fires when the Application Delegate receives a `applicationWillResignActive` notification.
-extern fn will_resign_active<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).will_resign_active();
-    };
+extern fn will_resign_active(this: &Object, _: Sel, _: id) {
+    app_handle(this).will_resign_active();
 }
 
 /// Fires when the Application Delegate receives a `applicationDidResignActive` notification.
-extern fn did_resign_active<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).did_resign_active();
-    };
+extern fn did_resign_active(this: &Object, _: Sel, _: id) {
+    app_handle(this).did_resign_active();
 }
 
 /// Fires when the Application Delegate receives a `applicationWillTerminate` notification.
-extern fn will_terminate<T: AppDelegate>(this: &Object, _: Sel, _: id) {
-    unsafe {
-        let app_ptr: usize = *this.get_ivar(ALCHEMY_APP_PTR);
-        let app = app_ptr as *mut T;
-        (*app).will_terminate();
-    };
+extern fn will_terminate(this: &Object, _: Sel, _: id) {
+    app_handle(this).will_terminate();
 }
 
 /// Registers an `NSObject` application delegate, and configures it for the various callbacks and
 /// pointers we need to have.
-fn register_app_delegate_class<T: AppDelegate>() -> *const Class {
+fn register_app_delegate_class() -> *const Class {
     static mut DELEGATE_CLASS: *const Class = 0 as *const Class;
     static INIT: Once = ONCE_INIT;
 
@@ -136,13 +132,13 @@ fn register_app_delegate_class<T: AppDelegate>() -> *const Class {
         decl.add_ivar::<usize>(ALCHEMY_APP_PTR);
 
         // Add callback methods
-        decl.add_method(sel!(applicationWillFinishLaunching:), will_finish_launching::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationDidFinishLaunching:), did_finish_launching::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationWillBecomeActive:), will_become_active::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationDidBecomeActive:), did_become_active::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationWillResignActive:), will_resign_active::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationDidResignActive:), did_resign_active::<T> as extern fn(&Object, _, _));
-        decl.add_method(sel!(applicationWillTerminate:), will_terminate::<T> as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationWillFinishLaunching:), will_finish_launching as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationDidFinishLaunching:), did_finish_launching as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationWillBecomeActive:), will_become_active as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationDidBecomeActive:), did_become_active as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationWillResignActive:), will_resign_active as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationDidResignActive:), did_resign_active as extern fn(&Object, _, _));
+        decl.add_method(sel!(applicationWillTerminate:), will_terminate as extern fn(&Object, _, _));
 
         DELEGATE_CLASS = decl.register();
     });
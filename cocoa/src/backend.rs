@@ -0,0 +1,178 @@
+//! Implements `Backend` for Cocoa. Native nodes are `NativeNode`s wrapping the `View`,
+//! `Text` and `Window` types found in this crate, which in turn retain the underlying
+//! Objective-C objects.
+
+use std::ffi::CStr;
+use std::sync::Mutex;
+
+use cocoa::base::{id, nil, NO};
+use cocoa::foundation::NSString;
+
+use objc_id::ShareId;
+use objc::runtime::{Object, BOOL};
+use objc::{msg_send, class, sel, sel_impl};
+
+use alchemy_styles::{Appearance, ColorScheme, Layout};
+
+use alchemy_lifecycle::native::{AppHandle, NativeNode};
+use alchemy_lifecycle::traits::{Backend, PlatformSpecificNodeType};
+
+use crate::app::App;
+use crate::text::Text;
+use crate::view::View;
+use crate::window::Window;
+
+/// A `Backend` that renders to AppKit.
+#[derive(Debug, Default)]
+pub struct CocoaBackend;
+
+impl CocoaBackend {
+    /// Creates a new `CocoaBackend`.
+    pub fn new() -> CocoaBackend {
+        CocoaBackend {}
+    }
+}
+
+/// Returns a retained pointer to the `NSView` (or subclass) behind a node, regardless of
+/// whether it's a `View` or a `Text`.
+fn objc_view(node: &PlatformSpecificNodeType) -> Option<ShareId<Object>> {
+    if let Some(view) = node.downcast_ref::<Mutex<View>>() {
+        return Some(view.lock().unwrap().borrow_native_backing_node());
+    }
+
+    if let Some(text) = node.downcast_ref::<Mutex<Text>>() {
+        return Some(text.lock().unwrap().borrow_native_backing_node());
+    }
+
+    None
+}
+
+impl Backend for CocoaBackend {
+    fn create_view(&self) -> PlatformSpecificNodeType {
+        NativeNode::new(Mutex::new(View::new()))
+    }
+
+    fn create_text(&self) -> PlatformSpecificNodeType {
+        NativeNode::new(Mutex::new(Text::new()))
+    }
+
+    fn create_window(
+        &self,
+        window_id: usize,
+        content_view: &PlatformSpecificNodeType,
+        app: AppHandle
+    ) -> PlatformSpecificNodeType {
+        let content_view = objc_view(content_view).expect("Cocoa windows need a Cocoa content view!");
+        NativeNode::new(Mutex::new(Window::new(window_id, content_view, app)))
+    }
+
+    fn append_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType) {
+        if let (Some(parent), Some(child)) = (parent.downcast_ref::<Mutex<View>>(), objc_view(child)) {
+            parent.lock().unwrap().append_child(&child);
+        }
+    }
+
+    fn insert_child(&self, parent: &PlatformSpecificNodeType, index: usize, child: &PlatformSpecificNodeType) {
+        if let (Some(parent), Some(child)) = (parent.downcast_ref::<Mutex<View>>(), objc_view(child)) {
+            parent.lock().unwrap().insert_child(index, &child);
+        }
+    }
+
+    fn replace_child(
+        &self,
+        parent: &PlatformSpecificNodeType,
+        old_child: &PlatformSpecificNodeType,
+        new_child: &PlatformSpecificNodeType
+    ) {
+        if let (Some(parent), Some(old_child), Some(new_child)) = (
+            parent.downcast_ref::<Mutex<View>>(),
+            objc_view(old_child),
+            objc_view(new_child)
+        ) {
+            parent.lock().unwrap().replace_child(&old_child, &new_child);
+        }
+    }
+
+    fn remove_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType) {
+        if let (Some(parent), Some(child)) = (parent.downcast_ref::<Mutex<View>>(), objc_view(child)) {
+            parent.lock().unwrap().remove_child(&child);
+        }
+    }
+
+    fn apply_styles(&self, node: &PlatformSpecificNodeType, appearance: &Appearance, layout: &Layout) {
+        if let Some(view) = node.downcast_ref::<Mutex<View>>() {
+            view.lock().unwrap().apply_styles(appearance, layout);
+        } else if let Some(text) = node.downcast_ref::<Mutex<Text>>() {
+            text.lock().unwrap().apply_styles(appearance, layout);
+        }
+    }
+
+    fn set_text(&self, node: &PlatformSpecificNodeType, text: &str) {
+        if let Some(node) = node.downcast_ref::<Mutex<Text>>() {
+            let mut node = node.lock().unwrap();
+            node.set_text(text.into());
+            node.render();
+        }
+    }
+
+    fn set_window_title(&self, window: &PlatformSpecificNodeType, title: &str) {
+        if let Some(window) = window.downcast_ref::<Mutex<Window>>() {
+            window.lock().unwrap().set_title(title);
+        }
+    }
+
+    fn set_window_dimensions(&self, window: &PlatformSpecificNodeType, x: f64, y: f64, width: f64, height: f64) {
+        if let Some(window) = window.downcast_ref::<Mutex<Window>>() {
+            window.lock().unwrap().set_dimensions(x, y, width, height);
+        }
+    }
+
+    /// On macOS we choose not to do anything here, and just have the content view handle the
+    /// background color, as calling window setBackgroundColor causes some notable lag on resizing.
+    fn apply_window_styles(&self, _window: &PlatformSpecificNodeType, _appearance: &Appearance) {}
+
+    fn show_window(&self, window: &PlatformSpecificNodeType) {
+        if let Some(window) = window.downcast_ref::<Mutex<Window>>() {
+            window.lock().unwrap().show();
+        }
+    }
+
+    /// `windowWillClose:` fires on the delegate as part of this, which loops back to the app.
+    fn close_window(&self, window: &PlatformSpecificNodeType) {
+        if let Some(window) = window.downcast_ref::<Mutex<Window>>() {
+            window.lock().unwrap().close();
+        }
+    }
+
+    /// Dark mode arrived in 10.14, along with `effectiveAppearance` on `NSApplication`; anything
+    /// older is always light.
+    fn color_scheme(&self) -> ColorScheme {
+        unsafe {
+            let shared_app: id = msg_send![class!(NSApplication), sharedApplication];
+            let supported: BOOL = msg_send![shared_app, respondsToSelector:sel!(effectiveAppearance)];
+            if supported == NO {
+                return ColorScheme::Light;
+            }
+
+            let appearance: id = msg_send![shared_app, effectiveAppearance];
+            let name: id = msg_send![appearance, name];
+            if name == nil {
+                return ColorScheme::Light;
+            }
+
+            match CStr::from_ptr(name.UTF8String()).to_string_lossy().contains("Dark") {
+                true => ColorScheme::Dark,
+                false => ColorScheme::Light
+            }
+        }
+    }
+
+    fn dispatch(&self, handler: Box<FnOnce() + Send + 'static>) {
+        dispatch::Queue::main().r#async(handler);
+    }
+
+    fn run(&self, app: AppHandle) {
+        let app = App::new(app);
+        app.run();
+    }
+}
@@ -19,6 +19,9 @@
 
 pub mod color;
 pub mod app;
+pub mod backend;
 pub mod text;
 pub mod view;
 pub mod window;
+
+pub use backend::CocoaBackend;
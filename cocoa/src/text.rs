@@ -15,8 +15,6 @@ use crate::color::IntoNSColor;
 
 use alchemy_styles::{Color, Layout, Appearance};
 
-use alchemy_lifecycle::traits::PlatformSpecificNodeType;
-
 static ALCHEMY_DELEGATE: &str = "alchemyDelegate";
 
 /// A wrapper for `NSText`. This holds retained pointers for the Objective-C 
@@ -60,17 +58,10 @@ impl Text {
 
     /// Returns a pointer to the underlying Objective-C view. The pointer is not mutable; however,
     /// you can send messages to it (unsafely).
-    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+    pub fn borrow_native_backing_node(&self) -> ShareId<Object> {
         self.inner_share.clone()
     }
 
-    /// Appends a child NSText (or subclassed type) to this view.
-    pub fn append_child(&mut self, child: PlatformSpecificNodeType) {
-        unsafe {
-            msg_send![&*self.inner_mut, addSubview:child];
-        }
-    }
-
     /// Given a `&Style`, will set the frame, background color, borders and so forth. It then
     /// calls `setNeedsDisplay:YES` on the Objective-C side, so that Cocoa will re-render this
     /// view.
@@ -15,8 +15,6 @@ use crate::color::IntoNSColor;
 
 use alchemy_styles::{Appearance, Color, Layout};
 
-use alchemy_lifecycle::traits::PlatformSpecificNodeType;
-
 static ALCHEMY_DELEGATE: &str = "alchemyDelegate";
 static BACKGROUND_COLOR: &str = "alchemyBackgroundColor";
 
@@ -54,17 +52,56 @@ impl View {
 
     /// Returns a pointer to the underlying Objective-C view. The pointer is not mutable; however,
     /// you can send messages to it (unsafely).
-    pub fn borrow_native_backing_node(&self) -> PlatformSpecificNodeType {
+    pub fn borrow_native_backing_node(&self) -> ShareId<Object> {
         self.inner_share.clone()
     }
 
     /// Appends a child NSView (or subclassed type) to this view.
-    pub fn append_child(&mut self, child: PlatformSpecificNodeType) {
+    pub fn append_child(&mut self, child: &Object) {
         unsafe {
             msg_send![&*self.inner_mut, addSubview:child];
         }
     }
 
+    /// Inserts a child NSView (or subclassed type) into this view's subviews at `index`. If
+    /// `index` is past the end, this just appends.
+    pub fn insert_child(&mut self, index: usize, child: &Object) {
+        unsafe {
+            let subviews: id = msg_send![&*self.inner_mut, subviews];
+            let count: usize = msg_send![subviews, count];
+
+            if index >= count {
+                msg_send![&*self.inner_mut, addSubview:child];
+                return;
+            }
+
+            // NSWindowBelow; slots the child in directly underneath the subview it's replacing
+            // at that index, which pushes that subview (and everything after it) down one.
+            let sibling: id = msg_send![subviews, objectAtIndex:index];
+            msg_send![&*self.inner_mut, addSubview:child positioned:-1isize relativeTo:sibling];
+        }
+    }
+
+    /// Swaps `new_child` into this view's subviews in place of `old_child`.
+    pub fn replace_child(&mut self, old_child: &Object, new_child: &Object) {
+        unsafe {
+            let superview: id = msg_send![old_child, superview];
+            if superview == &*self.inner_mut as *const Object as id {
+                msg_send![&*self.inner_mut, replaceSubview:old_child with:new_child];
+            }
+        }
+    }
+
+    /// Removes a child NSView (or subclassed type) from this view, if it's attached here.
+    pub fn remove_child(&mut self, child: &Object) {
+        unsafe {
+            let superview: id = msg_send![child, superview];
+            if superview == &*self.inner_mut as *const Object as id {
+                msg_send![child, removeFromSuperview];
+            }
+        }
+    }
+
     /// Given a `&Style`, will set the frame, background color, borders and so forth. It then
     /// calls `setNeedsDisplay:YES` on the Objective-C side, so that Cocoa will re-render this
     /// view.
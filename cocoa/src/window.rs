@@ -13,8 +13,8 @@ use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{msg_send, sel, sel_impl};
 
-use alchemy_lifecycle::traits::{AppDelegate, Component};
-use alchemy_styles::Appearance;
+use alchemy_lifecycle::native::AppHandle;
+use alchemy_lifecycle::traits::AppDelegate;
 
 static APP_PTR: &str = "alchemyAppPtr";
 static WINDOW_MANAGER_ID: &str = "alchemyWindowManagerID";
@@ -30,7 +30,7 @@ impl Window {
     /// Creates a new `NSWindow` instance, configures it appropriately (e.g, titlebar appearance),
     /// injects an `NSObject` delegate wrapper, and retains the necessary Objective-C runtime
     /// pointers.
-    pub fn new<T: AppDelegate>(window_id: usize, content_view: ShareId<Object>, app_ptr: *const T) -> Window {
+    pub fn new(window_id: usize, content_view: ShareId<Object>, app: AppHandle) -> Window {
         let dimensions = NSRect::new(NSPoint::new(0., 0.), NSSize::new(0., 0.));
 
         let style = NSWindowStyleMask::NSResizableWindowMask |
@@ -61,7 +61,8 @@ impl Window {
         };
         
         let delegate = unsafe {
-            let delegate_class = register_window_class::<T>();
+            let app_ptr = Box::into_raw(Box::new(app));
+            let delegate_class = register_window_class();
             let delegate: id = msg_send![delegate_class, new];
             (&mut *delegate).set_ivar(APP_PTR, app_ptr as usize);
             (&mut *delegate).set_ivar(WINDOW_MANAGER_ID, window_id);
@@ -75,14 +76,14 @@ impl Window {
         }
     }
 
-    pub fn set_title(&mut self, title: &str) {
+    pub fn set_title(&self, title: &str) {
         unsafe {
             let title = NSString::alloc(nil).init_str(title);
             msg_send![&*self.inner, setTitle:title];
         }
     }
 
-    pub fn set_dimensions(&mut self, x: f64, y: f64, width: f64, height: f64) {
+    pub fn set_dimensions(&self, x: f64, y: f64, width: f64, height: f64) {
         unsafe {
             let dimensions = NSRect::new(
                 NSPoint::new(x.into(), y.into()),
@@ -93,11 +94,6 @@ impl Window {
         }
     }
 
-    /// Normally used for setting platform-specific styles; on macOS we choose not to do this and
-    /// just have the content view handle the background color, as calling window
-    /// setBackgroundColor causes some notable lag on resizing.
-    pub fn apply_styles(&mut self, _appearance: &Appearance) { }
-
     /// On macOS, calling `show()` is equivalent to calling `makeKeyAndOrderFront`. This is the
     /// most common use case, hence why this method was chosen - if you want or need something
     /// else, feel free to open an issue to discuss.
@@ -125,29 +121,32 @@ impl Window {
 impl Drop for Window {
     /// When a Window is dropped on the Rust side, we want to ensure that we break the delegate
     /// link on the Objective-C side. While this shouldn't actually be an issue, I'd rather be
-    /// safer than sorry.
+    /// safer than sorry. The `AppHandle` the delegate was holding is freed here, too.
     fn drop(&mut self) {
         // This bridging link needs to be broken on Drop.
         unsafe { 
             msg_send![&*self.inner, setDelegate:nil];
+
+            let app_ptr: usize = *self.delegate.get_ivar(APP_PTR);
+            Box::from_raw(app_ptr as *mut AppHandle);
         }
     }
 }
 
 /// Called when a Window receives a `windowWillClose:` event. Loops back to the shared
 /// Alchemy app instance, so that our window manager can act appropriately.
-extern fn will_close<T: AppDelegate>(this: &Object, _: Sel, _: id) {
+extern fn will_close(this: &Object, _: Sel, _: id) {
     unsafe {
         let app_ptr: usize = *this.get_ivar(APP_PTR);
         let window_id: usize = *this.get_ivar(WINDOW_MANAGER_ID);
-        let app = app_ptr as *mut T;
+        let app = app_ptr as *const AppHandle;
         (*app)._window_will_close(window_id);
     };
 }
 
 /// Injects an `NSObject` delegate subclass, with some callback and pointer ivars for what we
 /// need to do.
-fn register_window_class<T: AppDelegate>() -> *const Class {
+fn register_window_class() -> *const Class {
     static mut DELEGATE_CLASS: *const Class = 0 as *const Class;
     static INIT: Once = ONCE_INIT;
 
@@ -158,7 +157,7 @@ fn register_window_class<T: AppDelegate>() -> *const Class {
         decl.add_ivar::<usize>(APP_PTR);
         decl.add_ivar::<usize>(WINDOW_MANAGER_ID);
         
-        decl.add_method(sel!(windowWillClose:), will_close::<T> as extern fn(&Object, _, _));
+        decl.add_method(sel!(windowWillClose:), will_close as extern fn(&Object, _, _));
         
         DELEGATE_CLASS = decl.register();
     });
@@ -24,9 +24,6 @@ impl AppDelegate for AppState {
     }
 }
 
-#[derive(Default)]
-struct BannerProps {}
-
 #[derive(Props)]
 struct Banner;
 
@@ -0,0 +1,84 @@
+//! Implements the headless runloop. There's no system runloop to hook into, so this
+//! runs a synchronous one instead: `run()` fires the launch lifecycle on your
+//! `AppDelegate`, then drains any work queued via `dispatch()` until the queue is empty
+//! or `terminate()` is called, and then fires the termination lifecycle and returns.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, RwLock};
+
+use alchemy_styles::{lazy_static, ColorScheme};
+
+use alchemy_lifecycle::native::AppHandle;
+use alchemy_lifecycle::traits::AppDelegate;
+
+lazy_static! {
+    /// Pending work for the headless runloop.
+    static ref EVENT_QUEUE: Mutex<VecDeque<Event>> = Mutex::new(VecDeque::new());
+
+    /// The system appearance that the headless backend reports.
+    static ref COLOR_SCHEME: RwLock<ColorScheme> = RwLock::new(ColorScheme::Light);
+}
+
+/// Work that the headless runloop can process.
+enum Event {
+    Dispatch(Box<FnOnce() + Send + 'static>),
+    Terminate
+}
+
+/// Queues a closure to be run on the headless runloop. If the runloop isn't running yet,
+/// it'll be run once `run()` is called and the app has finished launching.
+pub fn dispatch<F: FnOnce() + Send + 'static>(handler: F) {
+    let mut queue = EVENT_QUEUE.lock().unwrap();
+    queue.push_back(Event::Dispatch(Box::new(handler)));
+}
+
+/// Requests that the headless runloop stop, once everything queued before this call
+/// has been processed. The `AppDelegate` can veto this via `should_terminate()`.
+pub fn terminate() {
+    let mut queue = EVENT_QUEUE.lock().unwrap();
+    queue.push_back(Event::Terminate);
+}
+
+/// Sets the system appearance that the headless backend reports, for exercising
+/// `prefers-color-scheme` media queries. Windows pick this up the next time they render.
+pub fn set_color_scheme(color_scheme: ColorScheme) {
+    let mut lock = COLOR_SCHEME.write().unwrap();
+    *lock = color_scheme;
+}
+
+/// Returns the system appearance set via `set_color_scheme()`; light, by default.
+pub(crate) fn color_scheme() -> ColorScheme {
+    *COLOR_SCHEME.read().unwrap()
+}
+
+/// Pops the next event off of the queue. This is split out so that the queue isn't
+/// locked while a dispatched closure runs (which may very well queue more work).
+fn next_event() -> Option<Event> {
+    let mut queue = EVENT_QUEUE.lock().unwrap();
+    queue.pop_front()
+}
+
+/// The runloop itself. Fires the same lifecycle events, in the same order, that a native
+/// backend would. This blocks until the runloop is out of work, or has been told to
+/// terminate.
+pub(crate) fn run(mut app: AppHandle) {
+    app.will_finish_launching();
+    app.did_finish_launching();
+    app.will_become_active();
+    app.did_become_active();
+
+    while let Some(event) = next_event() {
+        match event {
+            Event::Dispatch(handler) => { handler(); },
+            Event::Terminate => {
+                if app.should_terminate() {
+                    break;
+                }
+            }
+        }
+    }
+
+    app.will_resign_active();
+    app.did_resign_active();
+    app.will_terminate();
+}
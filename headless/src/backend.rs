@@ -0,0 +1,127 @@
+//! Implements `Backend` for the headless tree. Nodes are `NativeNode`s wrapping handles
+//! into the in-memory tree, and windows wrap headless window records.
+
+use alchemy_styles::{Appearance, ColorScheme, Layout};
+
+use alchemy_lifecycle::native::{AppHandle, NativeNode};
+use alchemy_lifecycle::traits::{Backend, PlatformSpecificNodeType};
+
+use crate::app;
+use crate::tree::{self, node_id, Frame, NodeKind};
+use crate::window::Window;
+
+/// A `Backend` that records everything in memory, rather than drawing it.
+#[derive(Debug, Default)]
+pub struct HeadlessBackend;
+
+impl HeadlessBackend {
+    /// Creates a new `HeadlessBackend`.
+    pub fn new() -> HeadlessBackend {
+        HeadlessBackend {}
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn create_view(&self) -> PlatformSpecificNodeType {
+        NativeNode::new(tree::create(NodeKind::View))
+    }
+
+    fn create_text(&self) -> PlatformSpecificNodeType {
+        NativeNode::new(tree::create(NodeKind::Text))
+    }
+
+    fn create_window(
+        &self,
+        window_id: usize,
+        content_view: &PlatformSpecificNodeType,
+        app: AppHandle
+    ) -> PlatformSpecificNodeType {
+        let content_view = node_id(content_view).expect("Headless windows need a headless content view!");
+        NativeNode::new(Window::new(window_id, content_view, app))
+    }
+
+    fn append_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType) {
+        self.insert_child(parent, std::usize::MAX, child);
+    }
+
+    fn insert_child(&self, parent: &PlatformSpecificNodeType, index: usize, child: &PlatformSpecificNodeType) {
+        if let (Some(parent), Some(child)) = (node_id(parent), node_id(child)) {
+            tree::insert_child(parent, index, child);
+        }
+    }
+
+    fn replace_child(
+        &self,
+        parent: &PlatformSpecificNodeType,
+        old_child: &PlatformSpecificNodeType,
+        new_child: &PlatformSpecificNodeType
+    ) {
+        if let (Some(parent), Some(old_child), Some(new_child)) = (node_id(parent), node_id(old_child), node_id(new_child)) {
+            tree::replace_child(parent, old_child, new_child);
+        }
+    }
+
+    fn remove_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType) {
+        if let (Some(parent), Some(child)) = (node_id(parent), node_id(child)) {
+            tree::remove_child(parent, child);
+        }
+    }
+
+    fn apply_styles(&self, node: &PlatformSpecificNodeType, appearance: &Appearance, layout: &Layout) {
+        if let Some(id) = node_id(node) {
+            tree::update(id, |node| {
+                node.frame = Frame {
+                    x: layout.location.x,
+                    y: layout.location.y,
+                    width: layout.size.width,
+                    height: layout.size.height
+                };
+
+                node.background_color = appearance.background_color;
+                node.text_color = appearance.text_color;
+            });
+        }
+    }
+
+    fn set_text(&self, node: &PlatformSpecificNodeType, text: &str) {
+        if let Some(id) = node_id(node) {
+            tree::update(id, |node| node.text = text.into());
+        }
+    }
+
+    fn set_window_title(&self, window: &PlatformSpecificNodeType, title: &str) {
+        if let Some(window) = window.downcast_ref::<Window>() {
+            window.set_title(title);
+        }
+    }
+
+    fn set_window_dimensions(&self, window: &PlatformSpecificNodeType, x: f64, y: f64, width: f64, height: f64) {
+        if let Some(window) = window.downcast_ref::<Window>() {
+            window.set_dimensions(x, y, width, height);
+        }
+    }
+
+    fn show_window(&self, window: &PlatformSpecificNodeType) {
+        if let Some(window) = window.downcast_ref::<Window>() {
+            window.show();
+        }
+    }
+
+    fn close_window(&self, window: &PlatformSpecificNodeType) {
+        if let Some(window) = window.downcast_ref::<Window>() {
+            window.close();
+        }
+    }
+
+    fn color_scheme(&self) -> ColorScheme {
+        app::color_scheme()
+    }
+
+    fn dispatch(&self, handler: Box<FnOnce() + Send + 'static>) {
+        app::dispatch(handler);
+    }
+
+    fn run(&self, app: AppHandle) {
+        app::run(app);
+    }
+}
@@ -0,0 +1,32 @@
+//! This crate provides a headless backend for Alchemy, the Rust GUI framework.
+//! Rather than wrapping native widgets, it keeps an in-memory record of what a
+//! backend would be told to do - frames, text, colors, and the order of child
+//! nodes - and runs a synchronous event loop. This makes it possible to mount,
+//! lay out and inspect entire apps on platforms without a windowserver (e.g, CI).
+//!
+//! Every native node created by this backend is registered in a global tree, and
+//! can be inspected at any point via `node()` (`node_id()` maps a `NativeNode` handle
+//! to its id). Windows can be inspected via `window()` and `windows()`.
+//!
+//! # License
+//!
+//! Copyright 2018 Ryan McGrath. See the license files included in the root repository
+//! for more information, along with credit to applicable parties for who this project
+//! would not have happened.
+//!
+//! # Code of Conduct
+//!
+//! Please note that this project is released with a [Contributor Code of
+//! Conduct][coc]. By participating in this project you agree to abide by its terms.
+//!
+//! [coc]: https://www.contributor-covenant.org/version/1/4/code-of-conduct
+
+pub mod app;
+pub mod backend;
+pub mod tree;
+pub mod window;
+
+pub use app::{dispatch, set_color_scheme, terminate};
+pub use backend::HeadlessBackend;
+pub use tree::{node, node_id, Frame, Node, NodeId, NodeKind};
+pub use window::{window, windows, WindowRecord};
@@ -0,0 +1,198 @@
+//! Implements the in-memory node tree that the headless backend writes into. Each
+//! view or text node allocates an entry here, and every call a native backend
+//! would normally forward to the platform (appending children, setting frames and
+//! colors, and so on) is recorded on that entry instead.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use alchemy_styles::{lazy_static, Color};
+
+use alchemy_lifecycle::traits::PlatformSpecificNodeType;
+
+/// Nodes in the headless tree are identified by id.
+pub type NodeId = usize;
+
+lazy_static! {
+    /// Global storage for every headless node that's currently alive.
+    static ref NODE_TREE: Mutex<NodeTree> = Mutex::new(NodeTree::new());
+}
+
+/// Describes what kind of node was created.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum NodeKind {
+    View,
+    Text
+}
+
+/// A frame, as last applied from a computed `Layout`. Coordinates are relative to
+/// the parent node, as they would be on a native backend.
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Frame {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32
+}
+
+/// A recorded native node. You get copies of these back from `node()`, so holding
+/// one won't reflect later changes.
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub kind: NodeKind,
+    pub frame: Frame,
+    pub background_color: Color,
+    pub text_color: Color,
+    pub text: String,
+    pub parent: Option<NodeId>,
+    pub children: Vec<NodeId>
+}
+
+impl Node {
+    fn new(kind: NodeKind) -> Node {
+        Node {
+            kind: kind,
+            frame: Frame::default(),
+            background_color: Color::transparent(),
+            text_color: Color::transparent(),
+            text: "".into(),
+            parent: None,
+            children: vec![]
+        }
+    }
+}
+
+/// Backing storage for nodes, keyed by their id.
+struct NodeTree {
+    new_id: NodeId,
+    nodes: HashMap<NodeId, Node>
+}
+
+impl NodeTree {
+    fn new() -> NodeTree {
+        NodeTree { new_id: 1, nodes: HashMap::new() }
+    }
+
+    /// Detaches `child` from whatever parent it currently has, if any.
+    fn detach(&mut self, child: NodeId) {
+        let parent = match self.nodes.get_mut(&child) {
+            Some(node) => node.parent.take(),
+            None => None
+        };
+
+        if let Some(parent) = parent {
+            if let Some(parent) = self.nodes.get_mut(&parent) {
+                parent.children.retain(|c| *c != child);
+            }
+        }
+    }
+}
+
+/// What a headless `NativeNode` wraps. It owns an entry in the tree, which is removed when
+/// the last handle to it is dropped.
+#[derive(Debug)]
+pub(crate) struct NodeHandle(pub(crate) NodeId);
+
+impl Drop for NodeHandle {
+    /// Removes the backing entry from the in-memory tree.
+    fn drop(&mut self) {
+        remove(self.0);
+    }
+}
+
+/// Allocates a new node of the given kind, returning a handle that owns it.
+pub(crate) fn create(kind: NodeKind) -> NodeHandle {
+    let mut tree = NODE_TREE.lock().unwrap();
+    let id = tree.new_id;
+    tree.new_id += 1;
+    tree.nodes.insert(id, Node::new(kind));
+    NodeHandle(id)
+}
+
+/// Runs `handler` against the node found at `id`, if it exists.
+pub(crate) fn update<F: FnOnce(&mut Node)>(id: NodeId, handler: F) {
+    let mut tree = NODE_TREE.lock().unwrap();
+    if let Some(node) = tree.nodes.get_mut(&id) {
+        handler(node);
+    }
+}
+
+/// Inserts `child` into `parent` at `index`, appending if `index` is past the end. Like
+/// `addSubview:` on macOS, this moves the child if it's already attached elsewhere.
+pub(crate) fn insert_child(parent: NodeId, index: usize, child: NodeId) {
+    let mut tree = NODE_TREE.lock().unwrap();
+    if !tree.nodes.contains_key(&parent) || !tree.nodes.contains_key(&child) {
+        return;
+    }
+
+    tree.detach(child);
+    tree.nodes.get_mut(&child).unwrap().parent = Some(parent);
+
+    let children = &mut tree.nodes.get_mut(&parent).unwrap().children;
+    let index = std::cmp::min(index, children.len());
+    children.insert(index, child);
+}
+
+/// Swaps `new_child` into `parent` in place of `old_child`, if it's attached there. Like
+/// `replaceSubview:with:` on macOS, this moves `new_child` if it's already attached elsewhere.
+pub(crate) fn replace_child(parent: NodeId, old_child: NodeId, new_child: NodeId) {
+    let mut tree = NODE_TREE.lock().unwrap();
+    let is_attached = match tree.nodes.get(&old_child) {
+        Some(node) => node.parent == Some(parent),
+        None => false
+    };
+
+    if !is_attached || !tree.nodes.contains_key(&new_child) {
+        return;
+    }
+
+    tree.detach(new_child);
+    let index = tree.nodes[&parent].children.iter().position(|c| *c == old_child);
+
+    if let Some(index) = index {
+        tree.nodes.get_mut(&parent).unwrap().children[index] = new_child;
+        tree.nodes.get_mut(&new_child).unwrap().parent = Some(parent);
+
+        if let Some(old_child) = tree.nodes.get_mut(&old_child) {
+            old_child.parent = None;
+        }
+    }
+}
+
+/// Detaches `child` from `parent`, if it's attached there.
+pub(crate) fn remove_child(parent: NodeId, child: NodeId) {
+    let mut tree = NODE_TREE.lock().unwrap();
+    let is_attached = match tree.nodes.get(&child) {
+        Some(node) => node.parent == Some(parent),
+        None => false
+    };
+
+    if is_attached {
+        tree.detach(child);
+    }
+}
+
+/// Removes a node entirely, detaching it from its parent and orphaning its children.
+fn remove(id: NodeId) {
+    let mut tree = NODE_TREE.lock().unwrap();
+    tree.detach(id);
+
+    if let Some(node) = tree.nodes.remove(&id) {
+        for child in node.children {
+            if let Some(child) = tree.nodes.get_mut(&child) {
+                child.parent = None;
+            }
+        }
+    }
+}
+
+/// Returns the id of the node behind a `NativeNode` created by the headless backend.
+pub fn node_id(node: &PlatformSpecificNodeType) -> Option<NodeId> {
+    node.downcast_ref::<NodeHandle>().map(|handle| handle.0)
+}
+
+/// Returns a copy of the node found at `id`, if it's still alive.
+pub fn node(id: NodeId) -> Option<Node> {
+    let tree = NODE_TREE.lock().unwrap();
+    tree.nodes.get(&id).cloned()
+}
@@ -0,0 +1,106 @@
+//! Implements headless windows. Windows are recorded in a global registry
+//! so that their title, dimensions, visibility and content view can be inspected.
+//! Closing a window loops back to the shared app, just like a native backend's
+//! `windowWillClose:` notification would.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use alchemy_styles::lazy_static;
+
+use alchemy_lifecycle::native::AppHandle;
+use alchemy_lifecycle::traits::AppDelegate;
+
+use crate::tree::NodeId;
+
+lazy_static! {
+    /// Global storage for every headless window that's currently alive.
+    static ref WINDOWS: Mutex<HashMap<usize, WindowRecord>> = Mutex::new(HashMap::new());
+}
+
+/// A recorded window. You get copies of these back from `window()` and `windows()`,
+/// so holding one won't reflect later changes.
+#[derive(Clone, Debug)]
+pub struct WindowRecord {
+    pub id: usize,
+    pub title: String,
+    pub dimensions: (f64, f64, f64, f64),
+    pub visible: bool,
+    pub content_view: NodeId
+}
+
+/// A headless stand-in for a native window. This is what a headless `NativeNode` wraps
+/// for windows; the record is removed when it's dropped.
+pub(crate) struct Window {
+    id: usize,
+    app: AppHandle
+}
+
+impl Window {
+    /// Registers a new window record, with `content_view` as its root node. The `app` handle
+    /// is retained so that close events can loop back around.
+    pub fn new(window_id: usize, content_view: NodeId, app: AppHandle) -> Window {
+        let mut windows = WINDOWS.lock().unwrap();
+        windows.insert(window_id, WindowRecord {
+            id: window_id,
+            title: "".into(),
+            dimensions: (0., 0., 0., 0.),
+            visible: false,
+            content_view: content_view
+        });
+
+        Window {
+            id: window_id,
+            app: app
+        }
+    }
+
+    /// Runs `handler` against the record for this window.
+    fn update<F: FnOnce(&mut WindowRecord)>(&self, handler: F) {
+        let mut windows = WINDOWS.lock().unwrap();
+        if let Some(record) = windows.get_mut(&self.id) {
+            handler(record);
+        }
+    }
+
+    pub fn set_title(&self, title: &str) {
+        self.update(|record| record.title = title.into());
+    }
+
+    pub fn set_dimensions(&self, x: f64, y: f64, width: f64, height: f64) {
+        self.update(|record| record.dimensions = (x, y, width, height));
+    }
+
+    /// Marks the window as visible.
+    pub fn show(&self) {
+        self.update(|record| record.visible = true);
+    }
+
+    /// Marks the window as hidden, and notifies the app that the window will close.
+    pub fn close(&self) {
+        self.update(|record| record.visible = false);
+        self.app._window_will_close(self.id);
+    }
+}
+
+impl Drop for Window {
+    /// Removes the window record from the registry.
+    fn drop(&mut self) {
+        let mut windows = WINDOWS.lock().unwrap();
+        windows.remove(&self.id);
+    }
+}
+
+/// Returns a copy of the record for the window with the given id, if it's still alive.
+pub fn window(window_id: usize) -> Option<WindowRecord> {
+    let windows = WINDOWS.lock().unwrap();
+    windows.get(&window_id).cloned()
+}
+
+/// Returns copies of every window record that's currently alive, ordered by id.
+pub fn windows() -> Vec<WindowRecord> {
+    let windows = WINDOWS.lock().unwrap();
+    let mut records: Vec<WindowRecord> = windows.values().cloned().collect();
+    records.sort_by_key(|record| record.id);
+    records
+}
@@ -0,0 +1,215 @@
+//! Implements synthetic events. Whatever's sourcing events (typically a `Backend`) hands the
+//! `RenderEngine` an `Event`, along with the `ComponentKey` it's targeted at. From there, the
+//! event makes its way through the tree in two phases, much like the DOM: capturing down from
+//! the root to the target, then bubbling back up to the root.
+//!
+//! Handlers are set in `rsx! {}` via `on*` attributes - e.g, `onClick`, `onPointerDown`, or
+//! `onKeyDownCapture` for the capture phase.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::reconciler::key::ComponentKey;
+
+/// Details for pointer (mouse, touch, pen) events. Coordinates are relative to the window.
+#[derive(Clone, Debug, Default)]
+pub struct PointerEvent {
+    pub x: f64,
+    pub y: f64,
+
+    /// Which button triggered the event, following the DOM: `0` is the main button, `1` the
+    /// auxiliary (middle) button, and `2` the secondary button.
+    pub button: u8
+}
+
+/// Modifier keys that were held down when an event fired.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool
+}
+
+/// Details for keyboard events.
+#[derive(Clone, Debug, Default)]
+pub struct KeyEvent {
+    /// The value of the key pressed, e.g `"a"` or `"Enter"`.
+    pub key: String,
+    pub modifiers: Modifiers,
+
+    /// Whether this is a repeat, from the key being held down.
+    pub repeat: bool
+}
+
+/// Details for focus events. There aren't any, at the moment.
+#[derive(Clone, Debug, Default)]
+pub struct FocusEvent {}
+
+/// An event that can be dispatched through the `RenderEngine`.
+#[derive(Clone, Debug)]
+pub enum Event {
+    PointerDown(PointerEvent),
+    PointerUp(PointerEvent),
+    PointerMove(PointerEvent),
+    Click(PointerEvent),
+    KeyDown(KeyEvent),
+    KeyUp(KeyEvent),
+    Focus(FocusEvent),
+    Blur(FocusEvent)
+}
+
+/// Which phase of dispatch an event is in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventPhase {
+    Capturing,
+    AtTarget,
+    Bubbling
+}
+
+/// What handlers actually receive. Derefs to the event details (e.g, `PointerEvent`).
+#[derive(Debug)]
+pub struct SyntheticEvent<T> {
+    /// The `Component` the event was dispatched to.
+    pub target: ComponentKey,
+
+    /// The `Component` whose handler is currently running.
+    pub current_target: ComponentKey,
+
+    pub phase: EventPhase,
+    pub detail: T,
+    propagation_stopped: bool,
+    default_prevented: bool
+}
+
+impl<T> SyntheticEvent<T> {
+    /// Stops the event from going any further through the tree, once the current handler returns.
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    /// Tells whoever dispatched the event not to carry out the default action for it.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+}
+
+impl<T> Deref for SyntheticEvent<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.detail
+    }
+}
+
+/// A handler for events with details of type `T`. These are cheap to clone.
+pub struct EventHandler<T>(Arc<Fn(&mut SyntheticEvent<T>) + Send + Sync + 'static>);
+
+impl<T> EventHandler<T> {
+    pub fn new<F: Fn(&mut SyntheticEvent<T>) + Send + Sync + 'static>(handler: F) -> EventHandler<T> {
+        EventHandler(Arc::new(handler))
+    }
+
+    fn call(&self, event: &mut SyntheticEvent<T>) {
+        (self.0)(event)
+    }
+}
+
+impl<T> Clone for EventHandler<T> {
+    fn clone(&self) -> EventHandler<T> {
+        EventHandler(self.0.clone())
+    }
+}
+
+/// The event handlers set on a node. `rsx! {}` fills these in from `on*` attributes; e.g,
+/// `onPointerDown` sets `pointer_down`, and `onPointerDownCapture` sets `pointer_down_capture`.
+#[derive(Clone, Default)]
+pub struct EventHandlers {
+    pub pointer_down: Option<EventHandler<PointerEvent>>,
+    pub pointer_down_capture: Option<EventHandler<PointerEvent>>,
+    pub pointer_up: Option<EventHandler<PointerEvent>>,
+    pub pointer_up_capture: Option<EventHandler<PointerEvent>>,
+    pub pointer_move: Option<EventHandler<PointerEvent>>,
+    pub pointer_move_capture: Option<EventHandler<PointerEvent>>,
+    pub click: Option<EventHandler<PointerEvent>>,
+    pub click_capture: Option<EventHandler<PointerEvent>>,
+    pub key_down: Option<EventHandler<KeyEvent>>,
+    pub key_down_capture: Option<EventHandler<KeyEvent>>,
+    pub key_up: Option<EventHandler<KeyEvent>>,
+    pub key_up_capture: Option<EventHandler<KeyEvent>>,
+    pub focus: Option<EventHandler<FocusEvent>>,
+    pub focus_capture: Option<EventHandler<FocusEvent>>,
+    pub blur: Option<EventHandler<FocusEvent>>,
+    pub blur_capture: Option<EventHandler<FocusEvent>>
+}
+
+impl Event {
+    /// Runs the event through `path` (the target's ancestors and then the target, ordered from
+    /// the root down), capturing and then bubbling. Returns whether the default action was
+    /// prevented.
+    pub(crate) fn dispatch(self, target: ComponentKey, path: &[(ComponentKey, EventHandlers)]) -> bool {
+        match self {
+            Event::PointerDown(e) => propagate(e, target, path, |h| &h.pointer_down_capture, |h| &h.pointer_down),
+            Event::PointerUp(e) => propagate(e, target, path, |h| &h.pointer_up_capture, |h| &h.pointer_up),
+            Event::PointerMove(e) => propagate(e, target, path, |h| &h.pointer_move_capture, |h| &h.pointer_move),
+            Event::Click(e) => propagate(e, target, path, |h| &h.click_capture, |h| &h.click),
+            Event::KeyDown(e) => propagate(e, target, path, |h| &h.key_down_capture, |h| &h.key_down),
+            Event::KeyUp(e) => propagate(e, target, path, |h| &h.key_up_capture, |h| &h.key_up),
+            Event::Focus(e) => propagate(e, target, path, |h| &h.focus_capture, |h| &h.focus),
+            Event::Blur(e) => propagate(e, target, path, |h| &h.blur_capture, |h| &h.blur)
+        }
+    }
+}
+
+/// Handles the actual capture and bubble phases for `Event::dispatch()`.
+fn propagate<T>(
+    detail: T,
+    target: ComponentKey,
+    path: &[(ComponentKey, EventHandlers)],
+    capture: fn(&EventHandlers) -> &Option<EventHandler<T>>,
+    bubble: fn(&EventHandlers) -> &Option<EventHandler<T>>
+) -> bool {
+    let mut event = SyntheticEvent {
+        target: target,
+        current_target: target,
+        phase: EventPhase::Capturing,
+        detail: detail,
+        propagation_stopped: false,
+        default_prevented: false
+    };
+
+    let phases: [(EventPhase, fn(&EventHandlers) -> &Option<EventHandler<T>>); 2] = [
+        (EventPhase::Capturing, capture),
+        (EventPhase::Bubbling, bubble)
+    ];
+
+    for &(phase, handler_for) in phases.iter() {
+        let nodes: Vec<&(ComponentKey, EventHandlers)> = match phase {
+            EventPhase::Capturing => path.iter().collect(),
+            _ => path.iter().rev().collect()
+        };
+
+        for (key, handlers) in nodes {
+            event.current_target = *key;
+            event.phase = if *key == target { EventPhase::AtTarget } else { phase };
+
+            if let Some(handler) = handler_for(handlers) {
+                handler.call(&mut event);
+            }
+
+            if event.propagation_stopped {
+                return event.default_prevented;
+            }
+        }
+    }
+
+    event.default_prevented
+}
@@ -16,6 +16,8 @@ pub use std::sync::Arc;
 use alchemy_styles::lazy_static;
 
 pub mod error;
+pub mod events;
+pub mod native;
 pub mod rsx;
 pub mod traits;
 
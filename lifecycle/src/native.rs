@@ -0,0 +1,77 @@
+//! Types that get handed between Alchemy and a `Backend`. Alchemy itself never needs to
+//! know what a native node actually is - it just holds on to opaque handles, and passes
+//! them back to the backend that created them when something needs to happen.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::traits::{AppDelegate, AppLifecycle};
+
+/// An opaque, reference counted handle to a node (or window) in a backend's native tree.
+/// Backends wrap whatever they need in here (e.g, a retained `NSView`), and downcast it
+/// back out when they're asked to work with it. Cloning this is cheap, and clones refer to
+/// the same underlying node.
+#[derive(Clone)]
+pub struct NativeNode(Arc<Any + Send + Sync>);
+
+impl NativeNode {
+    /// Wraps a backend-specific value in a `NativeNode`.
+    pub fn new<T: Any + Send + Sync>(inner: T) -> NativeNode {
+        NativeNode(Arc::new(inner))
+    }
+
+    /// Attempts to downcast back to the backend-specific value this was created with.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref::<T>()
+    }
+
+    /// Returns `true` if both handles refer to the same underlying node.
+    pub fn ptr_eq(&self, other: &NativeNode) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for NativeNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NativeNode({:p})", &*self.0)
+    }
+}
+
+/// A pointer back to the shared Alchemy application, handed to a `Backend` so that it can
+/// loop system-level events (launching, terminating, windows closing...) back around. It
+/// implements `AppDelegate` itself by forwarding to the application it points to, so
+/// backends can just call through to it. Handles are freely copied, so they only ever hand
+/// out shared references; the application guards its own state.
+#[derive(Copy, Clone)]
+pub struct AppHandle(*const AppLifecycle);
+
+// The application this points to is guarded internally, and lives for the duration of
+// the program.
+unsafe impl Send for AppHandle {}
+unsafe impl Sync for AppHandle {}
+
+impl AppHandle {
+    /// Wraps a pointer to the shared application. The application must outlive every use
+    /// of the handle; in practice, it lives for the lifetime of the program.
+    pub fn new(app: *const AppLifecycle) -> AppHandle {
+        AppHandle(app)
+    }
+
+    /// Dereferences the inner pointer.
+    fn app(&self) -> &AppLifecycle {
+        unsafe { &*self.0 }
+    }
+}
+
+impl AppDelegate for AppHandle {
+    fn will_finish_launching(&mut self) { self.app().will_finish_launching(); }
+    fn did_finish_launching(&mut self) { self.app().did_finish_launching(); }
+    fn will_become_active(&mut self) { self.app().will_become_active(); }
+    fn did_become_active(&mut self) { self.app().did_become_active(); }
+    fn will_resign_active(&mut self) { self.app().will_resign_active(); }
+    fn did_resign_active(&mut self) { self.app().did_resign_active(); }
+    fn should_terminate(&self) -> bool { self.app().should_terminate() }
+    fn will_terminate(&mut self) { self.app().will_terminate(); }
+    fn _window_will_close(&self, window_id: usize) { self.app().window_will_close(window_id); }
+}
@@ -8,15 +8,31 @@ use crate::reconciler::key::ComponentKey;
 pub enum RenderEngineError {
     InvalidKey,
     InvalidRootComponent,
-    InvalidComponentKey(ComponentKey)
+    InvalidComponentKey(ComponentKey),
+
+    /// A `Component` that should be natively backed (and so have a layout node) didn't.
+    MissingLayoutNode(ComponentKey),
+
+    /// A `Component` was used without setting some of its required props.
+    MissingRequiredProps(&'static str, Vec<&'static str>),
+
+    /// A `Component` returned an error from `render()`. These get passed up the tree to the
+    /// nearest error boundary.
+    RenderFailed(Box<std::error::Error>)
 }
 
 impl std::fmt::Display for RenderEngineError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             RenderEngineError::InvalidComponentKey(ref node) => write!(f, "Invalid component key {:?}", node),
+            RenderEngineError::MissingLayoutNode(ref node) => write!(f, "Component {:?} has no layout node", node),
             RenderEngineError::InvalidRootComponent => write!(f, "Invalid component type! Root nodes must be a natively backed node."),
-            RenderEngineError::InvalidKey => write!(f, "An invalid key was passed to the render engine.")
+            RenderEngineError::InvalidKey => write!(f, "An invalid key was passed to the render engine."),
+            RenderEngineError::MissingRequiredProps(tag, ref props) => {
+                write!(f, "<{}> is missing required props: {}", tag, props.join(", "))
+            },
+
+            RenderEngineError::RenderFailed(ref error) => write!(f, "Error rendering: {}", error)
         }
     }
 }
@@ -25,8 +41,11 @@ impl std::error::Error for RenderEngineError {
     fn description(&self) -> &str {
         match *self {
             RenderEngineError::InvalidComponentKey(_) => "The key is not part of the component storage instance",
+            RenderEngineError::MissingLayoutNode(_) => "A Component expected to be natively backed has no layout node.",
             RenderEngineError::InvalidRootComponent => "The root component must be a natively backed Component instance.",
-            RenderEngineError::InvalidKey => "An invalid key was passed to the render engine."
+            RenderEngineError::InvalidKey => "An invalid key was passed to the render engine.",
+            RenderEngineError::MissingRequiredProps(_, _) => "A component was used without setting some of its required props.",
+            RenderEngineError::RenderFailed(_) => "A component returned an error from render(), and no error boundary caught it."
         }
     }
 }
@@ -4,6 +4,8 @@
 use std::any::Any;
 
 use crate::ComponentKey;
+use crate::error::Error;
+use crate::rsx::RSX;
 use crate::traits::{Component, Props};
 
 #[derive(Default)]
@@ -21,11 +23,19 @@ impl GenericRootView {
 }
 
 impl Props for GenericRootView {
-    fn set_props(&mut self, _: &mut Any) {}    
+    fn set_props(&mut self, _: &Any) {}    
 }
 
 impl Component for GenericRootView {
     fn new(_: ComponentKey) -> GenericRootView {
         GenericRootView {}
     }
+
+    /// The reconciler hoists the children of anything tagged `Fragment`, which is all we
+    /// need here; the component behind the tag never gets created.
+    fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> {
+        Ok(RSX::node("Fragment", "".into(), |key| {
+            Box::new(GenericRootView::new(key))
+        }, GenericRootViewProps {}, children))
+    }
 }
@@ -1,15 +1,34 @@
 //! Internal struct used for tracking component instances and their 
 //! associated metadata (layout, appearance, etc).
 
-use alchemy_styles::{Appearance, StylesList};
+use alchemy_styles::{Appearance, MediaEnvironment, StylesList};
+use alchemy_styles::selector::ElementState;
 use alchemy_styles::stretch::node::{Node as LayoutNode};
 
+use crate::events::EventHandlers;
+use crate::rsx::RSX;
 use crate::traits::Component;
 
 pub(crate) struct Instance {
     pub(crate) tag: &'static str,
+    pub(crate) key: Option<String>,
+
+    /// The theme this `Component` (and its subtree) is styled with, if it picked one.
+    pub(crate) theme: Option<String>,
     pub(crate) style_keys: StylesList,
+
+    /// Interaction state (hovered, pressed, and so on), which pseudo-class selectors match on.
+    pub(crate) state: ElementState,
+    pub(crate) events: EventHandlers,
     pub(crate) component: Box<Component + 'static>,
+
+    /// The children this `Component` was last rendered with. State updates re-render
+    /// with these, as they don't have a parent render pass to supply new ones.
+    pub(crate) children: Vec<RSX>,
     pub(crate) appearance: Appearance,
-    pub(crate) layout: Option<LayoutNode>
+    pub(crate) layout: Option<LayoutNode>,
+
+    /// What `@media` rules are evaluated against. Only roots have this set; everything else
+    /// goes by its root's.
+    pub(crate) media: Option<MediaEnvironment>
 }
@@ -3,13 +3,19 @@
 //! their `ComponentKey` passed in their constructor if they want to update. Doing this 
 //! enables us to avoid re-scanning or diffing an entire tree.
 
-use std::sync::Mutex;
+use std::any::Any;
+use std::sync::{Mutex, RwLock};
 use std::error::Error;
+use std::collections::{HashMap, VecDeque};
+use std::collections::hash_map::Entry;
 
-use alchemy_styles::THEME_ENGINE;
-use alchemy_styles::styles::{Appearance, Dimension, Number, Size, Style};
+use alchemy_styles::{MediaEnvironment, StylesList, THEME_ENGINE};
+use alchemy_styles::selector::{ElementState, PseudoClass, StyledNode};
+use alchemy_styles::styles::{Appearance, Dimension, Display, Number, Overflow, Point, PositionType, Rect, Size, Style};
 use alchemy_styles::stretch::node::{Node as LayoutNode, Stretch as LayoutStore};
 
+use crate::RENDER_ENGINE;
+use crate::events::{Event, EventHandlers};
 use crate::rsx::{RSX, VirtualNode};
 use crate::traits::Component;
 
@@ -30,8 +36,21 @@ use generic_root_view_stub::{GenericRootView, GenericRootViewProps};
 
 struct GenericRootProps;
 
+/// A queued state update, which gets run against the `Component` it was queued for. `None`
+/// signifies a forced update, which re-renders without consulting `should_component_update()`.
+type StateUpdater = Option<Box<FnOnce(&mut Component) + Send + 'static>>;
+
+/// Schedules a closure to run on a later tick of the runloop.
+type Dispatcher = Box<Fn(Box<FnOnce() + Send + 'static>) + Send + Sync + 'static>;
+
 pub struct RenderEngine {
-    queued_state_updates: Mutex<Vec<i32>>,
+    queued_state_updates: Mutex<Vec<(ComponentKey, StateUpdater)>>,
+    dispatcher: RwLock<Option<Dispatcher>>,
+
+    /// The `Component`s that `dispatch_event()` last put in each interaction state, per root; the
+    /// next event only has to touch whatever's changed since.
+    interactions: Mutex<HashMap<(ComponentKey, PseudoClass), Vec<ComponentKey>>>,
+
     components: Mutex<ComponentStore>,
     layouts: Mutex<LayoutStore>
 }
@@ -40,13 +59,371 @@ impl RenderEngine {
     pub(crate) fn new() -> RenderEngine {
         RenderEngine {
             queued_state_updates: Mutex::new(vec![]),
+            dispatcher: RwLock::new(None),
+            interactions: Mutex::new(HashMap::new()),
             components: Mutex::new(ComponentStore::new()),
             layouts: Mutex::new(LayoutStore::new())
         }
     }
 
-    // pub fn queue_update_for(&self, component_ptr: usize, updater: Box<Fn() -> Component + Send + Sync + 'static>) {
-    // }
+    /// Sets the function used to schedule a flush of queued state updates on the next tick of
+    /// the runloop. Alchemy configures this for you, pointing it at the active `Backend`; if it's
+    /// not set, queued updates sit there until `flush_queued_updates()` is called.
+    pub fn set_dispatcher<F: Fn(Box<FnOnce() + Send + 'static>) + Send + Sync + 'static>(&self, dispatcher: F) {
+        let mut lock = self.dispatcher.write().unwrap();
+        *lock = Some(Box::new(dispatcher));
+    }
+
+    /// Queues `updater` to be run against the `Component` found at `key`, which should be of type
+    /// `C`. Updates are batched: everything queued in one tick of the runloop is applied together,
+    /// after which each affected component re-renders (once), layout is recomputed, and
+    /// `component_did_update()` fires.
+    ///
+    /// Components receive their `ComponentKey` in `Component::new()`, and should hold on to it
+    /// if they want to update themselves.
+    pub fn set_state<C, F>(&self, key: ComponentKey, updater: F)
+    where
+        C: Component + 'static,
+        F: FnOnce(&mut C) + Send + 'static
+    {
+        self.queue_update(key, Some(Box::new(move |component: &mut Component| {
+            match component.as_any_mut().downcast_mut::<C>() {
+                Some(component) => { updater(component); },
+                None => { eprintln!("set_state() called with the wrong Component type for {:?}", key); }
+            }
+        })));
+    }
+
+    /// Queues a re-render of the `Component` found at `key`, without changing anything. Useful
+    /// if `render()` depends on something outside of the component. This skips
+    /// `should_component_update()` for the component itself, but not for its children.
+    ///
+    /// Normally, you should try to avoid this, and only read from props and state in `render()`.
+    pub fn force_update(&self, key: ComponentKey) {
+        self.queue_update(key, None);
+    }
+
+    /// Pushes an update onto the queue. If it's the first update since the last flush, a flush
+    /// gets scheduled for the next tick.
+    fn queue_update(&self, key: ComponentKey, updater: StateUpdater) {
+        let should_schedule = {
+            let mut queue = self.queued_state_updates.lock().unwrap();
+            queue.push((key, updater));
+            queue.len() == 1
+        };
+
+        if should_schedule {
+            let dispatcher = self.dispatcher.read().unwrap();
+            if let Some(dispatch) = &*dispatcher {
+                dispatch(Box::new(|| {
+                    if let Err(e) = RENDER_ENGINE.flush_queued_updates() {
+                        eprintln!("Error flushing state updates: {}", e);
+                    }
+                }));
+            }
+        }
+    }
+
+    /// Applies every queued state update, then re-renders the affected subtrees. If a component
+    /// and one of its ancestors were both updated, and the ancestor re-renders, the descendant
+    /// is covered by that. Layout is then recomputed from the nearest layout root (see
+    /// `layout_root_of()`) of each re-rendered subtree.
+    ///
+    /// If a render error makes it all the way up the tree (or the bookkeeping for a component
+    /// can't be found), the rest of the batch still goes through; the first error is returned at
+    /// the end, and any others are logged.
+    pub fn flush_queued_updates(&self) -> Result<(), Box<Error>> {
+        let updates = {
+            let mut queue = self.queued_state_updates.lock().unwrap();
+            std::mem::replace(&mut *queue, vec![])
+        };
+
+        if updates.len() == 0 {
+            return Ok(());
+        }
+
+        let mut first_error = None;
+        let mut updated = vec![];
+        let mut forced = vec![];
+        for (key, updater) in updates {
+            match updater {
+                // Updaters can call back into the engine (e.g, `restyle()`), so the stores aren't
+                // locked while they run.
+                Some(updater) => {
+                    // The component may well have been unmounted since this was queued.
+                    let mut component = match self.take_component(key) {
+                        Some(component) => component,
+                        None => { continue; }
+                    };
+
+                    updater(&mut *component);
+                    self.return_component(key, component);
+                },
+
+                None => { forced.push(key); }
+            }
+
+            let component_store = self.components.lock().unwrap();
+            if component_store.get(key).is_ok() && !updated.iter().any(|&(_, updated_key)| updated_key == key) {
+                match depth_of(key, &component_store) {
+                    Ok(depth) => { updated.push((depth, key)); },
+                    Err(e) => { keep_first_error(&mut first_error, e); }
+                }
+            }
+        }
+
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+
+        // Ancestors go first, so that we know whether they've covered their descendants.
+        updated.sort_by_key(|&(depth, _)| depth);
+
+        let mut rerendered = vec![];
+        let mut failed = vec![];
+        for (_, key) in updated {
+            match has_ancestor_in(key, &rerendered, &component_store) {
+                Ok(true) => { continue; },
+                Ok(false) => {},
+                Err(e) => {
+                    keep_first_error(&mut first_error, e);
+                    continue;
+                }
+            }
+
+            // An earlier re-render in this batch could have unmounted this one.
+            if component_store.get(key).is_err() {
+                continue;
+            }
+
+            match update_component_tree(key, None, forced.contains(&key), &mut component_store, &mut layout_store) {
+                Ok(true) => { rerendered.push(key); },
+                Ok(false) => {},
+
+                // There's no render pass above this one to catch the error, so it's passed up
+                // the tree by hand.
+                Err(e) => {
+                    let mut error = e;
+                    let mut current = parent_or_log(key, &component_store, &mut first_error);
+
+                    loop {
+                        let ancestor = match current {
+                            Some(ancestor) => ancestor,
+
+                            // Whatever did get updated still needs laying out.
+                            None => {
+                                keep_first_error(&mut first_error, error);
+                                failed.push(key);
+                                break;
+                            }
+                        };
+
+                        match catch_render_error(ancestor, error, &mut component_store, &mut layout_store) {
+                            Ok(()) => {
+                                rerendered.push(ancestor);
+                                break;
+                            },
+
+                            Err(e) => {
+                                error = e;
+                                current = parent_or_log(ancestor, &component_store, &mut first_error);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // An error boundary further down the batch can have unmounted something re-rendered
+        // earlier on.
+        let mut layout_roots = vec![];
+        for &key in rerendered.iter().chain(failed.iter()) {
+            if component_store.get(key).is_ok() {
+                match layout_root_of(key, &component_store, &layout_store) {
+                    Ok(layout_root) => {
+                        if !layout_roots.contains(&layout_root) {
+                            layout_roots.push(layout_root);
+                        }
+                    },
+
+                    Err(e) => { keep_first_error(&mut first_error, e); }
+                }
+            }
+        }
+
+        for &layout_root in &layout_roots {
+            let result = has_ancestor_in(layout_root, &layout_roots, &component_store).and_then(|covered| {
+                if !covered {
+                    compute_layout_from(layout_root, &component_store, &mut layout_store)?;
+                    walk_and_apply_styles(layout_root, &mut component_store, &mut layout_store)?;
+                }
+
+                Ok(())
+            });
+
+            if let Err(e) = result {
+                keep_first_error(&mut first_error, e);
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(())
+        }
+    }
+
+    /// Takes the `Component` out of the instance found at `key` (if there is one), so that it can
+    /// be worked with while the stores are unlocked. A placeholder stands in for it until it's
+    /// handed back via `return_component()`.
+    fn take_component(&self, key: ComponentKey) -> Option<Box<Component>> {
+        let mut component_store = self.components.lock().unwrap();
+        component_store.get_mut(key).ok().map(|instance| {
+            std::mem::replace(&mut instance.component, Box::new(GenericRootView {}))
+        })
+    }
+
+    /// Puts a `Component` taken out via `take_component()` back in its instance. If the instance
+    /// was unmounted in the meantime, the placeholder got unmounted in its stead, so this
+    /// passes that on.
+    fn return_component(&self, key: ComponentKey, mut component: Box<Component>) {
+        let mut component_store = self.components.lock().unwrap();
+        match component_store.get_mut(key) {
+            Ok(instance) => { instance.component = component; },
+            Err(_) => { component.component_will_unmount(); }
+        }
+    }
+
+    /// Dispatches `event` to the `Component` found at `target`. Handlers set via `on*` attributes
+    /// run through the capture phase (from the root down to `target`), and then the bubble phase
+    /// (back up to the root); a handler can stop this early via `stop_propagation()`.
+    ///
+    /// Pointer and focus events also update the `:hover`, `:active` and `:focus` states of the
+    /// components involved, restyling anything that changes as a result.
+    ///
+    /// Returns whether a handler called `prevent_default()`, in which case whoever dispatched the
+    /// event should skip whatever it'd normally do in response.
+    pub fn dispatch_event(&self, target: ComponentKey, event: Event) -> Result<bool, Box<Error>> {
+        // Handlers are gathered up front, so that the stores aren't locked while they run; they
+        // can then go ahead and call `set_state()` or the like.
+        let path = {
+            let component_store = self.components.lock().unwrap();
+            let mut path = vec![];
+            let mut current = Some(target);
+
+            while let Some(key) = current {
+                path.push((key, component_store.get(key)?.events.clone()));
+                current = component_store.parent(key)?;
+            }
+
+            path.reverse();
+            path
+        };
+
+        let interaction = match &event {
+            Event::PointerMove(_) => Some((PseudoClass::Hover, true)),
+            Event::PointerDown(_) => Some((PseudoClass::Active, true)),
+            Event::PointerUp(_) => Some((PseudoClass::Active, false)),
+            Event::Focus(_) => Some((PseudoClass::Focus, true)),
+            Event::Blur(_) => Some((PseudoClass::Focus, false)),
+            _ => None
+        };
+
+        let default_prevented = event.dispatch(target, &path);
+
+        if let Some((pseudo_class, enabled)) = interaction {
+            let mut component_store = self.components.lock().unwrap();
+            let mut layout_store = self.layouts.lock().unwrap();
+
+            let root = root_of(target, &component_store)?;
+            let mut interactions = self.interactions.lock().unwrap();
+            let previous = interactions.remove(&(root, pseudo_class)).unwrap_or_default();
+
+            // Hovering and pressing apply to the target and all of its ancestors, much like the
+            // DOM; focus only ever applies to the target.
+            let current: Vec<ComponentKey> = match (pseudo_class, enabled) {
+                (PseudoClass::Focus, true) => vec![target],
+                (PseudoClass::Focus, false) => previous.iter().cloned().filter(|&key| key != target).collect(),
+                (_, true) => path.iter().map(|(key, _)| *key).collect(),
+                _ => vec![]
+            };
+
+            // Only what's changed since the last event is touched; anything since unmounted is
+            // skipped.
+            let mut changes = vec![];
+            for &key in previous.iter().filter(|key| !current.contains(key)) {
+                if component_store.get(key).is_ok() {
+                    changes.push((key, pseudo_class, false));
+                }
+            }
+
+            for &key in current.iter().filter(|key| !previous.contains(key)) {
+                changes.push((key, pseudo_class, true));
+            }
+
+            if pseudo_class == PseudoClass::Focus && !enabled {
+                changes.push((target, pseudo_class, false));
+            }
+
+            interactions.insert((root, pseudo_class), current);
+            apply_state_changes(changes, &mut component_store, &mut layout_store)?;
+        }
+
+        Ok(default_prevented)
+    }
+
+    /// Sets whether the `Component` found at `key` is in the interaction state for
+    /// `pseudo_class`, restyling it (and its subtree) if that changed. `dispatch_event()` takes
+    /// care of `:hover`, `:active` and `:focus` as pointer and focus events come through; this is
+    /// mostly useful for `:disabled`, or for sources of interaction that aren't events.
+    pub fn set_element_state(
+        &self,
+        key: ComponentKey,
+        pseudo_class: PseudoClass,
+        enabled: bool
+    ) -> Result<(), Box<Error>> {
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+        apply_state_changes(vec![(key, pseudo_class, enabled)], &mut component_store, &mut layout_store)
+    }
+
+    /// Recomputes styles for the `Component` found at `key` and everything underneath it,
+    /// against whatever the stylesheets currently say - e.g, after a theme has been reloaded.
+    /// Nothing re-renders, so component state is left alone; layout is recomputed (from the
+    /// root) only if a layout style changed.
+    pub fn restyle(&self, key: ComponentKey) -> Result<(), Box<Error>> {
+        let mut component_store = self.components.lock().unwrap();
+        let mut layout_store = self.layouts.lock().unwrap();
+
+        if restyle_component_tree(key, &mut component_store, &mut layout_store)? {
+            let root = root_of(key, &component_store)?;
+            compute_root_layout(root, &component_store, &mut layout_store)?;
+            walk_and_apply_styles(root, &mut component_store, &mut layout_store)
+        } else {
+            walk_and_apply_styles(key, &mut component_store, &mut layout_store)
+        }
+    }
+
+    /// Finds the `Component` under `point` in the tree found at `root_key`, going off of the last
+    /// computed layout. `point` is relative to the root (typically, the window).
+    ///
+    /// Nodes set to `display: none` are skipped, and nodes with `overflow: hidden` clip their
+    /// children. Where siblings overlap, later ones are painted over earlier ones, and so win.
+    ///
+    /// Returns the key of the deepest `Component` hit, along with the path of its ancestors
+    /// (ordered from the root down) - or `None`, if nothing was hit.
+    pub fn hit_test(
+        &self,
+        root_key: ComponentKey,
+        point: Point<f32>
+    ) -> Result<Option<(ComponentKey, Vec<ComponentKey>)>, Box<Error>> {
+        let component_store = self.components.lock().unwrap();
+        let layout_store = self.layouts.lock().unwrap();
+        let origin = Point { x: 0., y: 0. };
+
+        Ok(hit_test_tree(root_key, point, origin, None, &component_store, &layout_store)?.map(|mut path| {
+            let key = path.pop().unwrap();
+            (key, path)
+        }))
+    }
 
     /// `Window`'s (or anything "root" in nature) need to register with the 
     /// reconciler for things like setState to work properly. When they do so,
@@ -66,10 +443,16 @@ impl RenderEngine {
         let component_key = component_store.new_key();
         component_store.insert(component_key, Instance {
             tag: "root",
+            key: None,
+            theme: None,
             style_keys: "root".into(),
+            state: ElementState::default(),
+            events: EventHandlers::default(),
             component: Box::new(component),
+            children: vec![],
             appearance: Appearance::default(),
-            layout: Some(layouts_store.new_node(Style::default(), vec![])?)
+            layout: Some(layouts_store.new_node(Style::default(), vec![])?),
+            media: Some(MediaEnvironment::default())
         })?;
 
         Ok(component_key)
@@ -80,18 +463,46 @@ impl RenderEngine {
     /// `Window`). Thus, for this one, we do some manual mucking with what we know is the
     /// root view (a `Window` or such root component would call this with it's registered
     /// `ComponentKey`), and then recurse based on the children.
+    ///
+    /// The root is sized to `media`, which `@media` rules in the tree are evaluated against; if
+    /// it's changed since the last render (e.g, the window was resized), the tree is restyled.
     pub fn diff_and_render_root(
         &self,
         key: ComponentKey,
-        dimensions: (f64, f64),
+        media: MediaEnvironment,
         child: RSX
     ) -> Result<(), Box<Error>> {
         let mut component_store = self.components.lock().unwrap();
         let mut layout_store = self.layouts.lock().unwrap();
 
+        let media_changed = {
+            let root_instance = component_store.get_mut(key)?;
+            let changed = root_instance.media != Some(media);
+            root_instance.media = Some(media);
+            changed
+        };
+
+        // The root is styled first, as everything underneath inherits from it.
+        let layout_node = {
+            let (mut style, appearance) = {
+                let root_instance = component_store.get(key)?;
+                compute_styles(root_instance.tag, &root_instance.style_keys, root_instance.state, None, None, &media, &component_store)?
+            };
+
+            let layout = layout_of(key, &component_store)?;
+            let root_instance = component_store.get_mut(key)?;
+            root_instance.appearance = appearance;
+            style.size = Size {
+                width: Dimension::Points(media.width),
+                height: Dimension::Points(media.height)
+            };
+            layout_store.set_style(layout, style);
+            layout
+        };
+
         let new_root_node = RSX::node("root", "root".into(), |_| {
             Box::new(GenericRootView {})
-        }, Box::new(GenericRootViewProps {}), match child {
+        }, GenericRootViewProps {}, match child {
             RSX::VirtualNode(node) => {
                 if node.tag == "Fragment" {
                     node.children
@@ -105,22 +516,13 @@ impl RenderEngine {
 
         recursively_diff_tree(key, new_root_node, &mut component_store, &mut layout_store)?;
 
-        let layout_node = {
-            let mut root_instance = component_store.get_mut(key)?;
-            let layout = root_instance.layout.unwrap();
-            let mut style = Style::default();
-            THEME_ENGINE.configure_styles_for_keys(&root_instance.style_keys, &mut style, &mut root_instance.appearance);
-            style.size = Size {
-                width: Dimension::Points(dimensions.0 as f32),
-                height: Dimension::Points(dimensions.1 as f32)
-            };
-            layout_store.set_style(layout, style);
-            layout
-        };
+        if media_changed {
+            restyle_component_tree(key, &mut component_store, &mut layout_store)?;
+        }
 
         layout_store.compute_layout(layout_node, Size {
-            width: Number::Defined(dimensions.0 as f32),
-            height: Number::Defined(dimensions.1 as f32)
+            width: Number::Defined(media.width),
+            height: Number::Defined(media.height)
         })?;
         
         walk_and_apply_styles(key, &mut component_store, &mut layout_store)?;
@@ -129,16 +531,32 @@ impl RenderEngine {
     }
 }
 
+/// Unmounts every child of the `Component` found at `key`.
+fn unmount_children(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    for child in component_store.children(key)? {
+        unmount_component_tree(child, component_store, layout_store)?;
+    }
+
+    Ok(())
+}
+
 /// Given two trees, will diff them to see if we need to replace or update. Depending on the
 /// result, we'll either recurse down a level, or tear down and build up a new tree. The final
 /// parameter on this method, `is_root_entity_view`, should only be passed for `Window` or other
 /// such instances, as it instructs us to skip the first level since these ones act different.
+///
+/// Returns the key of the `Component` that now occupies this slot in the tree; this is `key`
+/// itself, unless the node was replaced.
 fn recursively_diff_tree(
     key: ComponentKey,
     new_tree: RSX,
     component_store: &mut ComponentStore,
     layout_store: &mut LayoutStore
-) -> Result<(), Box<Error>> {
+) -> Result<ComponentKey, Box<Error>> {
     // First we need to determine if this node is being replaced or updated. A replace happens if 
     // two nodes are different types - in this case, we check their tag values. This is also a case
     // where, for instance, if the RSX tag is `::None` or `::VirtualText`, we'll treat it as
@@ -157,9 +575,11 @@ fn recursively_diff_tree(
     };
 
     if is_replace {
-        unmount_component_tree(key, component_store, layout_store)?;
-        //mount_component_tree(
-        return Ok(());
+        if let RSX::VirtualNode(new_tree) = new_tree {
+            return replace_component_tree(key, new_tree, component_store, layout_store);
+        }
+
+        return Ok(key);
     }
 
     // At this point, we know it's an update pass. Now we need to do a few things:
@@ -167,163 +587,1040 @@ fn recursively_diff_tree(
     // - Diff our `props` and figure out what actions we can take or shortcut.
     // - Let the `Component` instance determine what it should render.
     // - Recurse into the child trees if necessary.
-    let mut old_children = component_store.children(key)?;
-    old_children.reverse();
-
-    if let RSX::VirtualNode(mut child) = new_tree {
-        for new_child_tree in child.children {
-            match old_children.pop() {
-                // If there's a key in the old children for this position, it's 
-                // something we need to update, so let's recurse right back into it.
-                Some(old_child_key) => {
-                    recursively_diff_tree(
-                        old_child_key,
-                        new_child_tree,
-                        component_store,
-                        layout_store
-                    )?;
-                },
+    if let RSX::VirtualNode(new_tree) = new_tree {
+        update_component_tree(key, Some(new_tree), false, component_store, layout_store)?;
+    }
 
-                // If there's no matching old key in this position, then we've got a 
-                // new component instance to mount. This part now diverts into the Mount
-                // phase.
-                None => {
-                    if let RSX::VirtualNode(tr33amimustfeelohlol) = new_child_tree {
-                        let new_child_key = mount_component_tree(
-                            tr33amimustfeelohlol,
-                            component_store,
-                            layout_store
-                        )?;
-
-                        component_store.add_child(key, new_child_key)?;
-                        link_layout_nodess(key, new_child_key, component_store, layout_store)?;
-                    }
-                }
+    Ok(key)
+}
+
+/// Runs the update lifecycle for the `Component` found at `key`. If `new_tree` is passed (i.e,
+/// the parent re-rendered), its props, styles and children are handed to the instance first;
+/// otherwise (i.e, a state update), it re-renders with what it already has. Unless `force` is
+/// set, `should_component_update()` gets a say in whether it re-renders at all.
+///
+/// Returns whether the `Component` re-rendered.
+fn update_component_tree(
+    key: ComponentKey,
+    new_tree: Option<VirtualNode>,
+    force: bool,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<bool, Box<Error>> {
+    if let Some(new_tree) = &new_tree {
+        check_required_props(new_tree)?;
+    }
+
+    let (should_update, restyle) = {
+        let instance = component_store.get_mut(key)?;
+        let mut restyle = false;
+
+        // This gets a look at the incoming props before they replace the current ones.
+        let should_update = force || instance.component.should_component_update(
+            new_tree.as_ref().map(|new_tree| &*new_tree.props as &Any)
+        );
+
+        if let Some(new_tree) = new_tree {
+            instance.component.set_props(&*new_tree.props);
+            instance.children = new_tree.children;
+            instance.events = new_tree.events;
+
+            if instance.style_keys != new_tree.styles {
+                instance.style_keys = new_tree.styles;
+                restyle = true;
+            }
+
+            if instance.theme != new_tree.theme {
+                instance.theme = new_tree.theme;
+                restyle = true;
             }
         }
+
+        (should_update, restyle)
+    };
+
+    // Selectors can depend on ancestors' style keys (and themes are inherited), so everything
+    // underneath gets restyled, too.
+    if restyle {
+        restyle_component_tree(key, component_store, layout_store)?;
     }
 
-    // Trim the fat. If we still have child nodes after diffing in the new child trees,
-    // then they're ones that simply need to be unmounted and dropped.
-    if old_children.len() > 0 {
-        for child in old_children {
-            unmount_component_tree(child, component_store, layout_store)?;
+    let rendered = {
+        let instance = component_store.get_mut(key)?;
+        instance.component.get_derived_state_from_props();
+
+        if !should_update {
+            return Ok(false);
         }
+
+        instance.component.render(instance.children.clone())
+    };
+
+    let child = match rendered {
+        Ok(child) => child,
+        Err(e) => { return Err(Box::new(RenderEngineError::RenderFailed(e))); }
+    };
+
+    component_store.get(key)?.component.get_snapshot_before_update();
+    if let Err(e) = diff_children(key, rendered_children(child), component_store, layout_store) {
+        catch_render_error(key, e, component_store, layout_store)?;
     }
 
-    Ok(())
+    component_store.get_mut(key)?.component.component_did_update();
+    Ok(true)
 }
 
-/// Given a new `RSX` tree, a `ComponentStore`, and a `LayoutStore`, will recursively construct the
-/// tree, emitting required lifecycle events and persisting values. This happens in an inward-out
-/// fashion, which helps avoid unnecessary reflow in environments where it can get tricky.
-///
-/// This method returns a Result, the `Ok` variant containing a tuple of Vecs. These are the child 
-/// Component instances and Layout instances that need to be set in the stores.
-fn mount_component_tree(
-    tree: VirtualNode,
+/// Diffs the `Component` children found at `key` against `new_children`, updating, moving,
+/// mounting and unmounting as necessary. Old children are matched up with new ones by their
+/// `key` attribute if they have one, and by position (amongst the other unkeyed children) if
+/// they don't.
+fn diff_children(
+    key: ComponentKey,
+    new_children: Vec<RSX>,
     component_store: &mut ComponentStore,
     layout_store: &mut LayoutStore
-) -> Result<ComponentKey, Box<Error>> {
-    let key = component_store.new_key();
-    let component = (tree.create_component_fn)(key);
-    let is_native_backed = component.has_native_backing_node();
-    
-    // let state = get_derived_state_from_props()
-    let mut instance = Instance {
-        tag: tree.tag,
-        style_keys: tree.styles,
-        component: component,
-        appearance: Appearance::default(),
-        layout: None
-    };
+) -> Result<(), Box<Error>> {
+    let mut keyed_children = HashMap::new();
+    let mut unkeyed_children = VecDeque::new();
+    let mut duplicate_children = vec![];
+    for old_child_key in component_store.children(key)? {
+        match &component_store.get(old_child_key)?.key {
+            Some(rsx_key) => match keyed_children.entry(rsx_key.clone()) {
+                Entry::Vacant(entry) => { entry.insert(old_child_key); },
 
-    if is_native_backed {
-        let mut style = Style::default();
-        THEME_ENGINE.configure_styles_for_keys(&instance.style_keys, &mut style, &mut instance.appearance);
-        instance.layout = Some(layout_store.new_node(style, vec![])?);
+                // Keys need to be unique among siblings. Only the first with a given key can be
+                // matched up; any others are unmounted, and remounted if they're still around.
+                Entry::Occupied(_) => {
+                    eprintln!("Duplicate key \"{}\" among the children of {:?}; keys should be unique among siblings.", rsx_key, key);
+                    duplicate_children.push(old_child_key);
+                }
+            },
+
+            None => { unkeyed_children.push_back(old_child_key); }
+        }
     }
-    
-    let rendered = instance.component.render(tree.children);
-    // instance.get_snapshot_before_update()
-    component_store.insert(key, instance)?;
 
-    match rendered {
-        Ok(child) => if let RSX::VirtualNode(child) = child {
-            // We want to support Components being able to return arbitrary iteratable
-            // elements, but... well, it's not quite that simple. Thus we'll offer a <Fragment>
-            // tag similar to what React does, which just hoists the children out of it and
-            // discards the rest.
-            if child.tag == "Fragment" {
-                for child_tree in child.children {
-                    if let RSX::VirtualNode(child_tree) = child_tree {
-                        let child_key = mount_component_tree(child_tree, component_store, layout_store)?;
-                        
-                        component_store.add_child(key, child_key)?;
-                        if is_native_backed {
-                            link_layout_nodess(key, child_key, component_store, layout_store)?;
-                        }
-                    }
-                }
-            } else {
-                let child_key = mount_component_tree(child, component_store, layout_store)?;
-                
-                component_store.add_child(key, child_key)?;
-                if is_native_backed {
-                    link_layout_nodess(key, child_key, component_store, layout_store)?;
+    let mut children = vec![];
+    let mut mounted = vec![];
+
+    for new_child_tree in new_children {
+        // Text and empty values are handled by the `Component` they're passed to, and never
+        // get an instance of their own.
+        let new_child_tree = match new_child_tree {
+            RSX::VirtualNode(node) => node,
+            _ => { continue; }
+        };
+
+        let old_child_key = match &new_child_tree.key {
+            Some(rsx_key) => keyed_children.remove(rsx_key),
+            None => unkeyed_children.pop_front()
+        };
+
+        let child = match old_child_key {
+            // If there's a matching old child, it's something we need to update (or has
+            // just moved), so let's recurse right back into it.
+            Some(old_child_key) => recursively_diff_tree(
+                old_child_key,
+                RSX::VirtualNode(new_child_tree),
+                component_store,
+                layout_store
+            ),
+
+            // If there's no matching old child, then we've got a new component instance to
+            // mount. This part now diverts into the Mount phase; it gets linked in below,
+            // once we know where everything is going.
+            None => mount_component_tree(new_child_tree, key, component_store, layout_store).map(|child| {
+                mounted.push(child);
+                child
+            })
+        };
+
+        match child {
+            Ok(child) => { children.push(child); },
+
+            // Anything mounted so far in this pass isn't linked in anywhere yet, so it has to be
+            // cleaned up here; the old children are still in place, for whoever handles this.
+            Err(e) => {
+                for child in mounted {
+                    unmount_component_tree(child, component_store, layout_store)?;
                 }
-            }
-        },
 
-        Err(e) => {
-            // return an RSX::VirtualNode(ErrorComponentView) or something?
-            /* instance.get_derived_state_from_error(e) */
-            // render error state or something I guess?
-            /* instance.component_did_catch(e, info) */
-            eprintln!("Error rendering: {}", e);
+                return Err(e);
+            }
         }
     }
 
-    let instance_lol = component_store.get_mut(key)?;
-    instance_lol.component.component_did_mount();
+    // Trim the fat. If we still have old child nodes after diffing in the new child trees,
+    // then they're ones that simply need to be unmounted and dropped.
+    let old_children = keyed_children.into_iter().map(|(_, k)| k).chain(unkeyed_children.into_iter());
+    for old_child_key in old_children.chain(duplicate_children.into_iter()) {
+        unmount_component_tree(old_child_key, component_store, layout_store)?;
+    }
 
-    Ok(key)
+    // Children might have been added or moved, so we set them in their new order; the layout and
+    // native trees are brought in line below.
+    component_store.set_children(key, children)?;
+    let native_parent = nearest_native_ancestor(key, component_store)?;
+
+    if let Some(native_parent) = native_parent {
+        sync_native_children(native_parent, component_store, layout_store)?;
+    }
+
+    Ok(())
 }
 
-/// Given a `ComponentKey`, a `ComponentStore`, and a `LayoutStore`, will recursively walk the tree found at
-/// said key, emitting required lifecycle events and dropping values. This happens in an inward-out
-/// fashion, so deepest nodes/components get destroyed first to ensure that the backing widget tree
-/// doesn't get some weird dangling issue.
-fn unmount_component_tree(
+/// Mounts `children` under the `Component` found at `key`, linking them into the layout and
+/// native trees if it's natively backed.
+fn mount_children(
     key: ComponentKey,
+    children: Vec<RSX>,
+    is_native_backed: bool,
     component_store: &mut ComponentStore,
     layout_store: &mut LayoutStore
-) -> Result<Vec<LayoutNode>, Box<Error>> {
-    let mut instance = component_store.remove(key)?;
-    instance.component.component_will_unmount();
-    
-    let mut layout_nodes = vec![];
-    
-    let children = component_store.children(key)?;
+) -> Result<(), Box<Error>> {
     for child in children {
-        match unmount_component_tree(child, component_store, layout_store) {
-            Ok(mut child_layout_nodes) => {
-                if let Some(parent_layout_node) = instance.layout {
-                    for node in child_layout_nodes {
-                        layout_store.remove_child(parent_layout_node, node)?;
-                    }
-                } else {
-                    layout_nodes.append(&mut child_layout_nodes);
-                }
-            },
+        if let RSX::VirtualNode(child) = child {
+            let child_key = mount_component_tree(child, key, component_store, layout_store)?;
 
-            Err(e) => { eprintln!("Error unmounting a component tree: {}", e); }
+            component_store.add_child(key, child_key)?;
+            if is_native_backed {
+                link_layout_nodess(key, child_key, component_store, layout_store)?;
+            }
         }
     }
 
-    // remove node from backing tree
+    Ok(())
+}
+
+/// Gives the `Component` found at `key` a chance to catch `error`, which was returned while
+/// rendering one of its descendants. If it's an error boundary, its children are unmounted and
+/// it re-renders with its fallback UI; otherwise, `error` is handed back, to be passed further
+/// up the tree.
+fn catch_render_error(
+    key: ComponentKey,
+    error: Box<Error>,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let is_render_error = match error.downcast_ref::<RenderEngineError>() {
+        Some(RenderEngineError::RenderFailed(_)) => true,
+        _ => false
+    };
+
+    if !is_render_error || !component_store.get(key)?.component.is_error_boundary() {
+        return Err(error);
+    }
 
-    Ok(layout_nodes)
+    let error = match error.downcast::<RenderEngineError>() {
+        Ok(error) => match *error {
+            RenderEngineError::RenderFailed(error) => error,
+            error => { return Err(Box::new(error)); }
+        },
+
+        Err(error) => { return Err(error); }
+    };
+
+    unmount_children(key, component_store, layout_store)?;
+
+    let rendered = {
+        let instance = component_store.get_mut(key)?;
+        instance.component.get_derived_state_from_error(&error);
+        instance.component.render(instance.children.clone())
+    };
+
+    match rendered {
+        Ok(child) => { diff_children(key, rendered_children(child), component_store, layout_store)?; },
+        Err(e) => { return Err(Box::new(RenderEngineError::RenderFailed(e))); }
+    }
+
+    component_store.get_mut(key)?.component.component_did_catch(&error);
+    Ok(())
+}
+
+/// Fails if `tree` is missing any of its `Component`'s required props. This counts as a render
+/// error, so error boundaries above it get a chance to catch it.
+fn check_required_props(tree: &VirtualNode) -> Result<(), Box<Error>> {
+    if tree.missing_props.is_empty() {
+        return Ok(());
+    }
+
+    let error = RenderEngineError::MissingRequiredProps(tree.tag, tree.missing_props.clone());
+    Err(Box::new(RenderEngineError::RenderFailed(Box::new(error))))
+}
+
+/// Flattens what a `Component` rendered into the list of children it should have. A
+/// `<Fragment>` gets hoisted, similar to what React does.
+fn rendered_children(rendered: RSX) -> Vec<RSX> {
+    match rendered {
+        RSX::VirtualNode(node) => {
+            if node.tag == "Fragment" {
+                node.children
+            } else {
+                vec![RSX::VirtualNode(node)]
+            }
+        },
+
+        _ => vec![]
+    }
+}
+
+/// Returns `true` if any ancestor of the `Component` found at `key` is in `keys`.
+fn has_ancestor_in(
+    key: ComponentKey,
+    keys: &[ComponentKey],
+    components: &ComponentStore
+) -> Result<bool, Box<Error>> {
+    let mut current = components.parent(key)?;
+
+    while let Some(parent) = current {
+        if keys.contains(&parent) {
+            return Ok(true);
+        }
+
+        current = components.parent(parent)?;
+    }
+
+    Ok(false)
+}
+
+/// Returns how many ancestors the `Component` found at `key` has.
+fn depth_of(key: ComponentKey, components: &ComponentStore) -> Result<usize, Box<Error>> {
+    let mut depth = 0;
+    let mut current = components.parent(key)?;
+
+    while let Some(parent) = current {
+        depth += 1;
+        current = components.parent(parent)?;
+    }
+
+    Ok(depth)
+}
+
+/// Walks up from the `Component` found at `key`, returning the key of the root of its tree.
+fn root_of(key: ComponentKey, components: &ComponentStore) -> Result<ComponentKey, Box<Error>> {
+    let mut key = key;
+
+    while let Some(parent) = components.parent(key)? {
+        key = parent;
+    }
+
+    Ok(key)
+}
+
+/// Recomputes layout for the tree found at `root`, whose size was pinned when it was last
+/// rendered (e.g, to its window's size). Only nodes marked dirty since the last pass get
+/// laid out again; the rest come from the layout cache.
+fn compute_root_layout(
+    root: ComponentKey,
+    components: &ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let layout_node = match components.get(root)?.layout {
+        Some(layout_node) => layout_node,
+        None => { return Ok(()); }
+    };
+
+    let size = layouts.style(layout_node)?.size;
+    layouts.compute_layout(layout_node, Size {
+        width: match size.width { Dimension::Points(w) => Number::Defined(w), _ => Number::Undefined },
+        height: match size.height { Dimension::Points(h) => Number::Defined(h), _ => Number::Undefined }
+    })?;
+
+    Ok(())
+}
+
+/// Finds where layout needs recomputing from, when things under the `Component` found at `key`
+/// have changed: the nearest natively backed `Component` at or above it whose size can't depend
+/// on what's inside of it (see `has_fixed_size()`), or failing that, the root.
+fn layout_root_of(
+    key: ComponentKey,
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<ComponentKey, Box<Error>> {
+    let mut layout_root = None;
+    let mut current = Some(key);
+
+    while let Some(key) = current {
+        let parent = components.parent(key)?;
+
+        if let Some(layout) = components.get(key)?.layout {
+            let style = layouts.style(layout)?;
+
+            // Hidden subtrees are laid out as zero-sized, which only happens from the root.
+            if style.display == Display::None {
+                return root_of(key, components);
+            }
+
+            if layout_root.is_none() && (parent.is_none() || has_fixed_size(style)) {
+                layout_root = Some(key);
+            }
+        }
+
+        current = parent;
+    }
+
+    match layout_root {
+        Some(layout_root) => Ok(layout_root),
+        None => root_of(key, components)
+    }
+}
+
+/// Recomputes layout from `layout_root`, as found by `layout_root_of()`. Unless it's the root of
+/// its tree, that's done in place, keeping its own size and location.
+fn compute_layout_from(
+    layout_root: ComponentKey,
+    components: &ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    match components.parent(layout_root)? {
+        Some(_) => {
+            layouts.compute_layout_in_place(layout_of(layout_root, components)?)?;
+            Ok(())
+        },
+
+        None => compute_root_layout(layout_root, components, layouts)
+    }
+}
+
+/// Whether a node with `style` ends up the same size no matter what's inside of it: it has a
+/// fixed width and height, and doesn't get flexed to fit in alongside its siblings.
+fn has_fixed_size(style: &Style) -> bool {
+    let is_fixed = |dimension: Dimension| match dimension {
+        Dimension::Points(_) => true,
+        _ => false
+    };
+
+    let is_flexed = style.position_type != PositionType::Absolute && (style.flex_grow != 0. || style.flex_shrink != 0.);
+    is_fixed(style.size.width) && is_fixed(style.size.height) && !is_flexed
+}
+
+/// Applies interaction state changes, then restyles whatever they affect. Selectors can match on
+/// an ancestor's state, so each changed `Component` has its subtree restyled. If that leaves
+/// layout untouched (e.g, a `:hover` rule that only changes colors), the new appearance is
+/// simply applied to the restyled subtrees; otherwise, layout is recomputed from the nearest
+/// layout root above each of them, and the results applied from there.
+fn apply_state_changes(
+    changes: Vec<(ComponentKey, PseudoClass, bool)>,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let mut changed = vec![];
+    for (key, pseudo_class, enabled) in changes {
+        if components.get_mut(key)?.state.set(pseudo_class, enabled) && !changed.contains(&key) {
+            changed.push(key);
+        }
+    }
+
+    let mut restyled = vec![];
+    let mut layout_roots = vec![];
+    for &key in &changed {
+        if has_ancestor_in(key, &changed, components)? {
+            continue;
+        }
+
+        restyled.push(key);
+        if restyle_component_tree(key, components, layouts)? {
+            // Its own size might be what changed, so layout has to be redone from above it.
+            let layout_root = match components.parent(key)? {
+                Some(parent) => layout_root_of(parent, components, layouts)?,
+                None => key
+            };
+
+            if !layout_roots.contains(&layout_root) {
+                layout_roots.push(layout_root);
+            }
+        }
+    }
+
+    for &layout_root in &layout_roots {
+        if !has_ancestor_in(layout_root, &layout_roots, components)? {
+            compute_layout_from(layout_root, components, layouts)?;
+            walk_and_apply_styles(layout_root, components, layouts)?;
+        }
+    }
+
+    for key in restyled {
+        if !layout_roots.contains(&key) && !has_ancestor_in(key, &layout_roots, components)? {
+            walk_and_apply_styles(key, components, layouts)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns what `@media` rules should be evaluated against for the `Component` found at `key`,
+/// which is whatever its root was last rendered with.
+fn media_environment(key: ComponentKey, components: &ComponentStore) -> Result<MediaEnvironment, Box<Error>> {
+    let root = root_of(key, components)?;
+    Ok(components.get(root)?.media.unwrap_or_default())
+}
+
+/// Works out the `Style` and `Appearance` for a `Component` with the given tag, style keys,
+/// interaction state and theme, mounted under `parent`. Stylesheet selectors are matched against
+/// its ancestry, which is read from the `ComponentStore`, and `@media` rules against `media`.
+///
+/// If neither it nor any of its ancestors picked a theme, the active one is used. Inherited
+/// properties (`color`, fonts, and so on) start out as whatever the nearest natively backed
+/// ancestor ended up with, so that ancestor needs to have been styled already.
+fn compute_styles(
+    tag: &str,
+    style_keys: &StylesList,
+    state: ElementState,
+    theme: Option<&str>,
+    parent: Option<ComponentKey>,
+    media: &MediaEnvironment,
+    components: &ComponentStore
+) -> Result<(Style, Appearance), Box<Error>> {
+    let mut path = vec![StyledNode { tag: tag, style_keys: style_keys, state: state }];
+    let mut theme = theme;
+    let mut inherited = None;
+    let mut current = parent;
+
+    while let Some(key) = current {
+        let instance = components.get(key)?;
+        path.push(StyledNode { tag: instance.tag, style_keys: &instance.style_keys, state: instance.state });
+        theme = theme.or(instance.theme.as_ref().map(String::as_str));
+
+        // Only natively backed `Component`s are styled, so they're what's inherited from.
+        if inherited.is_none() && instance.layout.is_some() {
+            inherited = Some(&instance.appearance);
+        }
+
+        current = components.parent(key)?;
+    }
+
+    path.reverse();
+
+    let mut style = Style::default();
+    let mut appearance = Appearance::default();
+    if let Some(parent_appearance) = inherited {
+        appearance.inherit_from(parent_appearance);
+    }
+
+    match theme {
+        Some(theme) => THEME_ENGINE.configure_styles_for_path_in_theme(theme, &path, media, &mut style, &mut appearance),
+        None => THEME_ENGINE.configure_styles_for_path(&path, media, &mut style, &mut appearance)
+    }
+
+    Ok((style, appearance))
+}
+
+/// Recomputes styles for the `Component` found at `key`, and everything underneath it. Layout
+/// nodes are only touched (and so marked dirty) where their `Style` actually changed.
+///
+/// Returns whether any layout `Style` changed, i.e whether layout needs recomputing.
+fn restyle_component_tree(
+    key: ComponentKey,
+    components: &mut ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<bool, Box<Error>> {
+    let instance = components.get(key)?;
+    let mut needs_layout = false;
+
+    if let Some(layout) = instance.layout {
+        let parent = components.parent(key)?;
+        let (mut style, appearance) = compute_styles(
+            instance.tag,
+            &instance.style_keys,
+            instance.state,
+            instance.theme.as_ref().map(String::as_str),
+            parent,
+            &media_environment(key, components)?,
+            components
+        )?;
+
+        // A root's size is pinned to whatever it's rendered into (e.g, its window).
+        if parent.is_none() {
+            style.size = layouts.style(layout)?.size;
+        }
+
+        components.get_mut(key)?.appearance = appearance;
+
+        if *layouts.style(layout)? != style {
+            layouts.set_style(layout, style)?;
+            needs_layout = true;
+        }
+    }
+
+    for child in components.children(key)? {
+        needs_layout = restyle_component_tree(child, components, layouts)? || needs_layout;
+    }
+
+    Ok(needs_layout)
+}
+
+/// Recursively hit tests the tree found at `key`, for `RenderEngine::hit_test()`. `origin` is the
+/// absolute position of the nearest native-backed ancestor, which layout locations are relative
+/// to, and `clip` is the area any `overflow: hidden` ancestors leave visible.
+///
+/// Returns the path (from `key` down) to the deepest `Component` hit, if any.
+fn hit_test_tree(
+    key: ComponentKey,
+    point: Point<f32>,
+    origin: Point<f32>,
+    clip: Option<Rect<f32>>,
+    components: &ComponentStore,
+    layouts: &LayoutStore
+) -> Result<Option<Vec<ComponentKey>>, Box<Error>> {
+    let instance = components.get(key)?;
+    let mut origin = origin;
+    let mut clip = clip;
+    let mut frame = None;
+
+    // Components without a native backing node have no frame of their own; they're just
+    // whatever their children are.
+    if let Some(layout_node) = instance.layout {
+        let style = layouts.style(layout_node)?;
+        if style.display == Display::None {
+            return Ok(None);
+        }
+
+        let layout = layouts.layout(layout_node)?;
+        origin = Point {
+            x: origin.x + layout.location.x,
+            y: origin.y + layout.location.y
+        };
+
+        let bounds = Rect {
+            start: origin.x,
+            end: origin.x + layout.size.width,
+            top: origin.y,
+            bottom: origin.y + layout.size.height
+        };
+
+        if style.overflow == Overflow::Hidden {
+            clip = Some(match clip {
+                Some(clip) => Rect {
+                    start: clip.start.max(bounds.start),
+                    end: clip.end.min(bounds.end),
+                    top: clip.top.max(bounds.top),
+                    bottom: clip.bottom.min(bounds.bottom)
+                },
+                None => bounds
+            });
+        }
+
+        frame = Some(bounds);
+    }
+
+    // A clipped-out point can't hit anything further down, either.
+    if let Some(clip) = clip {
+        if !rect_contains(&clip, point) {
+            return Ok(None);
+        }
+    }
+
+    // Children can sit outside of their parent's frame (short of it clipping them), so they
+    // always get checked - topmost (i.e, last) first.
+    for child in components.children(key)?.into_iter().rev() {
+        if let Some(mut path) = hit_test_tree(child, point, origin, clip, components, layouts)? {
+            path.insert(0, key);
+            return Ok(Some(path));
+        }
+    }
+
+    match frame {
+        Some(ref frame) if rect_contains(frame, point) => Ok(Some(vec![key])),
+        _ => Ok(None)
+    }
+}
+
+/// Whether `point` falls within `rect`. Edges on the far sides are treated as outside, so that
+/// adjacent frames don't both claim the point between them.
+fn rect_contains(rect: &Rect<f32>, point: Point<f32>) -> bool {
+    point.x >= rect.start && point.x < rect.end && point.y >= rect.top && point.y < rect.bottom
+}
+
+/// Given a new `RSX` tree, a `ComponentStore`, and a `LayoutStore`, will recursively construct the
+/// tree, emitting required lifecycle events and persisting values. This happens in an inward-out
+/// fashion, which helps avoid unnecessary reflow in environments where it can get tricky.
+///
+/// This method returns a Result, the `Ok` variant containing a tuple of Vecs. These are the child 
+/// Component instances and Layout instances that need to be set in the stores.
+fn mount_component_tree(
+    tree: VirtualNode,
+    parent: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<ComponentKey, Box<Error>> {
+    check_required_props(&tree)?;
+
+    let key = component_store.new_key();
+    let mut component = (tree.create_component_fn)(key);
+    let is_native_backed = component.has_native_backing_node();
+
+    component.set_props(&*tree.props);
+    component.get_derived_state_from_props();
+
+    let mut instance = Instance {
+        tag: tree.tag,
+        key: tree.key,
+        theme: tree.theme,
+        style_keys: tree.styles,
+        state: ElementState::default(),
+        events: tree.events,
+        component: component,
+        children: tree.children.clone(),
+        appearance: Appearance::default(),
+        layout: None,
+        media: None
+    };
+
+    if is_native_backed {
+        let media = media_environment(parent, component_store)?;
+        let theme = instance.theme.as_ref().map(String::as_str);
+        let (style, appearance) = compute_styles(instance.tag, &instance.style_keys, instance.state, theme, Some(parent), &media, component_store)?;
+        instance.appearance = appearance;
+        instance.layout = Some(layout_store.new_node(style, vec![])?);
+    }
+    
+    let rendered = instance.component.render(tree.children);
+    component_store.insert(key, instance)?;
+
+    // It's placed among its siblings once it's mounted, but everything underneath needs to see
+    // where it sits in the tree before then, to be styled.
+    component_store.set_parent(key, parent)?;
+
+    // A `Component` can't catch its own errors; they're passed up to whoever's mounting it.
+    let child = match rendered {
+        Ok(child) => child,
+        Err(e) => {
+            discard_failed_mount(key, component_store, layout_store)?;
+            return Err(Box::new(RenderEngineError::RenderFailed(e)));
+        }
+    };
+
+    if let Err(e) = mount_children(key, rendered_children(child), is_native_backed, component_store, layout_store) {
+        if let Err(e) = catch_render_error(key, e, component_store, layout_store) {
+            discard_failed_mount(key, component_store, layout_store)?;
+            return Err(e);
+        }
+    }
+
+    let instance_lol = component_store.get_mut(key)?;
+    instance_lol.component.component_did_mount();
+
+    Ok(key)
+}
+
+/// Given a `ComponentKey` and a new `RSX` tree, will mount the new tree into the same child slot
+/// the old one occupied in its parent, and then unmount the old one. Since not every `Component`
+/// is natively backed, the old and new trees can each contribute any number of layout (and
+/// native) nodes to the nearest natively backed ancestor; these get paired off and swapped in
+/// place, with any leftovers being removed or inserted at the correct index.
+fn replace_component_tree(
+    key: ComponentKey,
+    tree: VirtualNode,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<ComponentKey, Box<Error>> {
+    let parent = match component_store.parent(key)? {
+        Some(parent) => parent,
+        None => { return Err(Box::new(RenderEngineError::InvalidComponentKey(key))); }
+    };
+
+    let index = match component_store.children(parent)?.iter().position(|child| *child == key) {
+        Some(index) => index,
+        None => { return Err(Box::new(RenderEngineError::InvalidComponentKey(key))); }
+    };
+
+    let mut old_roots = vec![];
+    collect_native_roots(key, component_store, &mut old_roots)?;
+
+    let new_key = mount_component_tree(tree, parent, component_store, layout_store)?;
+    component_store.replace_child_at_index(parent, index, new_key)?;
+    
+    let mut new_roots = vec![];
+    collect_native_roots(new_key, component_store, &mut new_roots)?;
+
+    if let Some(native_parent) = nearest_native_ancestor(parent, component_store)? {
+        let parent_instance = component_store.get(native_parent)?;
+        let parent_layout = layout_of(native_parent, component_store)?;
+
+        for i in 0..std::cmp::max(old_roots.len(), new_roots.len()) {
+            match (old_roots.get(i), new_roots.get(i)) {
+                (Some(old), Some(new)) => {
+                    let (old_layout, new_layout) = (layout_of(*old, component_store)?, layout_of(*new, component_store)?);
+                    let (old, new) = (component_store.get(*old)?, component_store.get(*new)?);
+                    
+                    let position = layout_store.children(parent_layout)?.iter().position(|n| *n == old_layout);
+                    match position {
+                        Some(position) => {
+                            layout_store.replace_child_at_index(parent_layout, position, new_layout)?;
+
+                            if let (Some(old_node), Some(new_node)) = (
+                                old.component.borrow_native_backing_node(),
+                                new.component.borrow_native_backing_node()
+                            ) {
+                                parent_instance.component.replace_child_node(old_node, new_node);
+                            }
+                        },
+
+                        // The old node was never linked in, so there's nothing to swap; the new
+                        // one is inserted wherever it falls, as below.
+                        None => {
+                            if let Some(old_node) = old.component.borrow_native_backing_node() {
+                                parent_instance.component.remove_child_node(old_node);
+                            }
+
+                            insert_native_root(native_parent, new_roots[i], component_store, layout_store)?;
+                        }
+                    }
+                },
+
+                (Some(old), None) => {
+                    layout_store.remove_child(parent_layout, layout_of(*old, component_store)?)?;
+
+                    if let Some(old_node) = component_store.get(*old)?.component.borrow_native_backing_node() {
+                        parent_instance.component.remove_child_node(old_node);
+                    }
+                },
+
+                (None, Some(new)) => { insert_native_root(native_parent, *new, component_store, layout_store)?; },
+                (None, None) => {}
+            }
+        }
+    }
+
+    unmount_component_tree(key, component_store, layout_store)?;
+
+    Ok(new_key)
+}
+
+/// Given a `ComponentKey`, collects the keys of the top-most natively backed `Component`s in the
+/// tree found at said key (including itself). These are the nodes that get linked into the nearest
+/// natively backed ancestor, in order.
+fn collect_native_roots(
+    key: ComponentKey,
+    components: &ComponentStore,
+    roots: &mut Vec<ComponentKey>
+) -> Result<(), Box<Error>> {
+    if components.get(key)?.layout.is_some() {
+        roots.push(key);
+        return Ok(());
+    }
+
+    for child in components.children(key)? {
+        collect_native_roots(child, components, roots)?;
+    }
+
+    Ok(())
+}
+
+/// Holds on to the first error hit while flushing a batch of updates, so that it can be returned
+/// once the rest of the batch has gone through. Any after it are logged.
+fn keep_first_error(first_error: &mut Option<Box<Error>>, error: Box<Error>) {
+    match first_error {
+        Some(_) => { eprintln!("Error flushing state updates: {}", error); },
+        None => { *first_error = Some(error); }
+    }
+}
+
+/// Returns the parent of the `Component` found at `key`. If that can't be looked up, the error is
+/// kept (see `keep_first_error()`) and the component is treated as a root.
+fn parent_or_log(
+    key: ComponentKey,
+    components: &ComponentStore,
+    first_error: &mut Option<Box<Error>>
+) -> Option<ComponentKey> {
+    match components.parent(key) {
+        Ok(parent) => parent,
+        Err(e) => {
+            keep_first_error(first_error, Box::new(e));
+            None
+        }
+    }
+}
+
+/// Removes the native and layout nodes at the top of the tree found at `key` from
+/// `native_parent`. This is used before unmounting a tree that's being dropped.
+fn detach_native_roots(
+    native_parent: ComponentKey,
+    key: ComponentKey,
+    components: &ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let parent_instance = components.get(native_parent)?;
+    let parent_layout = layout_of(native_parent, components)?;
+
+    let mut roots = vec![];
+    collect_native_roots(key, components, &mut roots)?;
+
+    for root in roots {
+        let instance = components.get(root)?;
+        let layout = layout_of(root, components)?;
+
+        if layouts.children(parent_layout)?.contains(&layout) {
+            layouts.remove_child(parent_layout, layout)?;
+        }
+
+        if let Some(node) = instance.component.borrow_native_backing_node() {
+            parent_instance.component.remove_child_node(node);
+        }
+    }
+
+    Ok(())
+}
+
+/// Brings the layout and native children of `native_parent` in line with the order of the
+/// natively backed `Component`s beneath it, inserting any that aren't linked yet and moving any
+/// that are out of place. Anything that's going away should be detached beforehand.
+fn sync_native_children(
+    native_parent: ComponentKey,
+    components: &ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let parent_instance = components.get(native_parent)?;
+    let parent_layout = layout_of(native_parent, components)?;
+
+    let mut roots = vec![];
+    for child in components.children(native_parent)? {
+        collect_native_roots(child, components, &mut roots)?;
+    }
+
+    for (index, root) in roots.into_iter().enumerate() {
+        let instance = components.get(root)?;
+        let layout = layout_of(root, components)?;
+
+        let current_index = layouts.children(parent_layout)?.iter().position(|node| *node == layout);
+        if current_index == Some(index) {
+            continue;
+        }
+
+        if let Some(current_index) = current_index {
+            layouts.remove_child_at_index(parent_layout, current_index)?;
+        }
+
+        layouts.insert_child_at_index(parent_layout, index, layout)?;
+
+        // Backends move a node that's already attached, so this covers both cases.
+        if let Some(node) = instance.component.borrow_native_backing_node() {
+            parent_instance.component.insert_child_node(index, node);
+        }
+    }
+
+    Ok(())
+}
+
+/// Links the natively backed `Component` found at `key` into the layout and native trees of
+/// `native_parent`. Since everything else under the native parent is already linked in order,
+/// where it falls in the flattened list of native roots is where it needs to be inserted.
+fn insert_native_root(
+    native_parent: ComponentKey,
+    key: ComponentKey,
+    components: &ComponentStore,
+    layouts: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let mut siblings = vec![];
+    for child in components.children(native_parent)? {
+        collect_native_roots(child, components, &mut siblings)?;
+    }
+
+    let position = match siblings.iter().position(|sibling| *sibling == key) {
+        Some(position) => position,
+        None => { return Err(Box::new(RenderEngineError::InvalidComponentKey(key))); }
+    };
+
+    layouts.insert_child_at_index(layout_of(native_parent, components)?, position, layout_of(key, components)?)?;
+
+    if let Some(node) = components.get(key)?.component.borrow_native_backing_node() {
+        components.get(native_parent)?.component.insert_child_node(position, node);
+    }
+
+    Ok(())
+}
+
+/// Returns the layout node for the `Component` found at `key`, which should be natively backed.
+fn layout_of(key: ComponentKey, components: &ComponentStore) -> Result<LayoutNode, Box<Error>> {
+    match components.get(key)?.layout {
+        Some(layout) => Ok(layout),
+        None => Err(Box::new(RenderEngineError::MissingLayoutNode(key)))
+    }
+}
+
+/// Walks up from (and including) the `Component` found at `key`, returning the first one that is
+/// natively backed, if any.
+fn nearest_native_ancestor(
+    key: ComponentKey,
+    components: &ComponentStore
+) -> Result<Option<ComponentKey>, Box<Error>> {
+    let mut current = Some(key);
+
+    while let Some(key) = current {
+        if components.get(key)?.layout.is_some() {
+            return Ok(Some(key));
+        }
+
+        current = components.parent(key)?;
+    }
+
+    Ok(None)
+}
+
+/// Unmounts the `Component` found at `key`, along with everything under it. Its native nodes are
+/// detached from the nearest native ancestor, it's removed from its parent's children, and the
+/// layout nodes for the entire tree are freed.
+fn unmount_component_tree(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    // Something that's been mounted but not yet placed among its siblings has a parent, but
+    // isn't linked in anywhere.
+    if let Some(parent) = component_store.parent(key)? {
+        if component_store.children(parent)?.contains(&key) {
+            if let Some(native_parent) = nearest_native_ancestor(parent, component_store)? {
+                detach_native_roots(native_parent, key, component_store, layout_store)?;
+            }
+
+            component_store.remove_child(parent, key)?;
+        }
+    }
+
+    remove_component_tree(key, component_store, layout_store)
+}
+
+/// Removes the `Component` found at `key` from the stores after it failed to mount. It never got
+/// `component_did_mount()`, so it doesn't get `component_will_unmount()` either - but any children
+/// it finished mounting do. It isn't among its parent's children yet, so there's nothing to detach.
+fn discard_failed_mount(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    for child in component_store.children(key)? {
+        remove_component_tree(child, component_store, layout_store)?;
+    }
+
+    let instance = component_store.remove(key)?;
+    if let Some(layout) = instance.layout {
+        layout_store.remove(layout)?;
+    }
+
+    Ok(())
+}
+
+/// Removes the `Component` found at `key` and everything under it from the stores, calling
+/// `component_will_unmount()` on the way down. Native nodes further down the tree go along with
+/// the native node they're attached to, so there's no need to detach them one by one.
+fn remove_component_tree(
+    key: ComponentKey,
+    component_store: &mut ComponentStore,
+    layout_store: &mut LayoutStore
+) -> Result<(), Box<Error>> {
+    let children = component_store.children(key)?;
+    let mut instance = component_store.remove(key)?;
+    instance.component.component_will_unmount();
+
+    for child in children {
+        remove_component_tree(child, component_store, layout_store)?;
+    }
+
+    if let Some(layout) = instance.layout {
+        layout_store.remove(layout)?;
+    }
+
+    Ok(())
 }
 
 /// Given a tree, will walk the branches until it finds the next root nodes to connect.
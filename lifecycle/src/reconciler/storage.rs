@@ -105,8 +105,16 @@ impl ComponentStore {
         self.components.remove(key)
     }
 
+    /// Points `key` at `parent`, without adding it to `parent`'s children. Components are
+    /// mounted (and styled) before they're placed among their siblings, but selectors, themes
+    /// and inherited styles need to see their ancestry in the meantime.
+    pub fn set_parent(&mut self, key: ComponentKey, parent: ComponentKey) -> Result<(), Error> {
+        add_parent(self.parents.get_mut(key)?, parent);
+        Ok(())
+    }
+
     pub fn add_child(&mut self, key: ComponentKey, child: ComponentKey) -> Result<(), Error> {
-        self.parents.get_mut(child)?.push(key);
+        add_parent(self.parents.get_mut(child)?, key);
         self.children.get_mut(key)?.push(child);
         Ok(())
     }
@@ -121,7 +129,7 @@ impl ComponentStore {
 
         // Build up relation node <-> child
         for child in children {
-            self.parents.get_mut(child)?.push(key);
+            add_parent(self.parents.get_mut(child)?, key);
             self.children.get_mut(key)?.push(child);
         }
 
@@ -142,12 +150,16 @@ impl ComponentStore {
     }
 
     pub fn replace_child_at_index(&mut self, key: ComponentKey, index: usize, child: ComponentKey) -> Result<ComponentKey, Error> {
-        self.parents.get_mut(child)?.push(key);
+        add_parent(self.parents.get_mut(child)?, key);
         let old_child = std::mem::replace(&mut self.children.get_mut(key)?[index], child);
         self.parents.get_mut(old_child)?.retain(|p| *p != key);
         Ok(old_child)
     }
 
+    pub fn parent(&self, key: ComponentKey) -> Result<Option<ComponentKey>, Error> {
+        self.parents.get(key).map(|parents| parents.first().cloned())
+    }
+
     pub fn children(&self, key: ComponentKey) -> Result<Vec<ComponentKey>, Error> {
         self.children.get(key).map(Clone::clone)
     }
@@ -164,3 +176,10 @@ impl ComponentStore {
         self.components.get_mut(key)
     }
 }
+
+/// Adds `parent` to a component's `parents`, unless it's already there (e.g, via `set_parent()`).
+fn add_parent(parents: &mut Vec<ComponentKey>, parent: ComponentKey) {
+    if !parents.contains(&parent) {
+        parents.push(parent);
+    }
+}
@@ -5,6 +5,7 @@
 
 use std::any::Any;
 use std::fmt::{Debug, Display};
+use std::sync::Arc;
 
 use alchemy_styles::StylesList;
 
@@ -14,11 +15,13 @@ pub use virtual_node::VirtualNode;
 mod virtual_text;
 pub use virtual_text::VirtualText;
 
+use crate::events::EventHandlers;
 use crate::reconciler::key::ComponentKey;
 use crate::traits::Component;
 
 /// An enum representing the types of nodes that the
 /// system can work with. `None`, `VirtualText`, or `VirtualNode`.
+#[derive(Clone)]
 pub enum RSX {
     None,
     VirtualText(VirtualText),
@@ -28,21 +31,55 @@ pub enum RSX {
 impl RSX {
     /// Shorthand method for creating a new `RSX::VirtualNode` instance. Rarely should you call
     /// this yourself; the `rsx! {}` macro handles this for you.
-    pub fn node<P: Any + 'static>(
+    pub fn node<P: Any + Send + Sync + 'static>(
         tag: &'static str,
         styles: StylesList,
         create_fn: fn(key: ComponentKey) -> Box<Component>,
         props: P,
         children: Vec<RSX>
+    ) -> RSX {
+        RSX::keyed_node(None, None, tag, styles, EventHandlers::default(), create_fn, props, children)
+    }
+
+    /// Shorthand method for creating a new `RSX::VirtualNode` instance that carries a `key`. Keys
+    /// let the reconciler match children up across renders, rather than going purely by
+    /// position. This also carries the theme and any event handlers for the node. The `rsx! {}`
+    /// macro calls this for you.
+    pub fn keyed_node<P: Any + Send + Sync + 'static>(
+        key: Option<String>,
+        theme: Option<String>,
+        tag: &'static str,
+        styles: StylesList,
+        events: EventHandlers,
+        create_fn: fn(key: ComponentKey) -> Box<Component>,
+        props: P,
+        children: Vec<RSX>
     ) -> RSX {
         RSX::VirtualNode(VirtualNode {
             tag: tag,
+            key: key,
+            theme: theme,
             create_component_fn: create_fn,
             styles: styles,
-            props: Box::new(props),
+            events: events,
+            props: Arc::new(props),
+            missing_props: vec![],
             children: children
         })
     }
+
+    /// Records required props that weren't set on this node, which the reconciler then refuses
+    /// to mount it without. The `rsx! {}` macro calls this for you.
+    pub fn with_missing_props(self, missing: Vec<&'static str>) -> RSX {
+        match self {
+            RSX::VirtualNode(mut node) => {
+                node.missing_props = missing;
+                RSX::VirtualNode(node)
+            },
+
+            rsx => rsx
+        }
+    }
     
     /// Shorthand method for creating a new `RSX::VirtualText` instance. Rarely should you call
     /// this yourself; the `rsx! {}` and `text!()` macros handle this for you. 
@@ -72,6 +72,7 @@ impl Props {
         match key {
             "children" => { None },
             "key" => { None },
+            "theme" => { None },
             "styles" => { None },
             _ => { None } //self.attributes.get(key) }
         }
@@ -3,23 +3,39 @@
 
 use std::any::Any;
 use std::fmt::{Display, Debug};
+use std::sync::Arc;
 
 use alchemy_styles::StylesList;
 
+use crate::events::EventHandlers;
 use crate::reconciler::key::ComponentKey;
 use crate::rsx::RSX;
 use crate::traits::Component;
 
 /// A VirtualNode is akin to an `Element` in React terms. Here, we provide a way
 /// for lazy `Component` instantiation, properties, children and so on.
+///
+/// Cloning a `VirtualNode` is fairly cheap, as props are shared rather than copied.
+#[derive(Clone)]
 pub struct VirtualNode {
     /// Used in debugging/printing/etc.
     pub tag: &'static str,
 
+    /// An optional key, set via the `key` attribute. Siblings with the same key are treated as
+    /// the same node across renders, even if they've moved around.
+    pub key: Option<String>,
+
+    /// An optional theme, set via the `theme` attribute, to style this node and everything
+    /// underneath it with. If it's not set, the node goes with its parent's.
+    pub theme: Option<String>,
+
     /// Used for determining which CSS styles should be applied to this node.
     /// This property is accessed often enough that it's separated out here.
     pub styles: StylesList,
 
+    /// Handlers for any events this node should respond to, set via `on*` attributes.
+    pub events: EventHandlers,
+
     /// `Component` instances are created on-demand, if the reconciler deems it be so. This
     /// is a closure that should return an instance of the correct type.
     pub create_component_fn: fn(key: ComponentKey) -> Box<Component>,
@@ -27,7 +43,11 @@ pub struct VirtualNode {
     /// When some RSX is returned, we scoop up the props inside a special block, and then shove
     /// them in here as an `Any` object. When you `derive(Props)` on a `Component` struct, it 
     /// creates a setter that specifically handles downcasting and persisting props for you.
-    pub props: Box<Any>,
+    pub props: Arc<Any + Send + Sync>,
+
+    /// Any required props that weren't set where this node was created. If there are any, the
+    /// node fails to mount (or update) with a render error, rather than going ahead without them.
+    pub missing_props: Vec<&'static str>,
 
     /// Child components for this node.
     pub children: Vec<RSX>
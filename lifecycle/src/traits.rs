@@ -3,20 +3,18 @@
 
 use std::any::Any;
 
+use alchemy_styles::ColorScheme;
 use alchemy_styles::styles::{Appearance, Layout};
 
 //use crate::RENDER_ENGINE;
 use crate::error::Error;
+use crate::native::{AppHandle, NativeNode};
 use crate::reconciler::key::ComponentKey;
 use crate::rsx::RSX;
 
-/// A per-platform wrapped Pointer type, used for attaching views/widgets.
-#[cfg(feature = "cocoa")]
-pub type PlatformSpecificNodeType = objc_id::ShareId<objc::runtime::Object>;
-
-/// A per-platform wrapped Pointer type, used for attaching views/widgets.
-#[cfg(not(feature = "cocoa"))]
-pub type PlatformSpecificNodeType = ();
+/// A per-platform wrapped Pointer type, used for attaching views/widgets. This is an opaque
+/// handle created by (and only meaningful to) the active `Backend`.
+pub type PlatformSpecificNodeType = NativeNode;
 
 /*fn update<C: Component, F: Fn() -> Box<C> + Send + Sync + 'static>(component: &Component, updater: F) {
     let component_ptr = component as *const C as usize;
@@ -59,6 +57,115 @@ pub trait AppDelegate: Send + Sync {
     fn _window_will_close(&self, _window_id: usize) {}
 }
 
+/// The application side of an `AppHandle`. This mirrors `AppDelegate`, but takes `&self`
+/// throughout: a backend can hold any number of handles to the same application, so whatever
+/// implements this has to guard its own state (e.g, behind a `Mutex`).
+pub trait AppLifecycle: Send + Sync {
+    /// Fired when an Application is about to finish launching.
+    fn will_finish_launching(&self);
+
+    /// Fired when an Application has finished launching.
+    fn did_finish_launching(&self);
+
+    /// Fired when an Application will become active.
+    fn will_become_active(&self);
+
+    /// Fired when an Application became active.
+    fn did_become_active(&self);
+
+    /// Fired when an Application will resign active.
+    fn will_resign_active(&self);
+
+    /// Fired when an Application has resigned active.
+    fn did_resign_active(&self);
+
+    /// Fired when an Application is going to terminate; returning `false` vetoes it.
+    fn should_terminate(&self) -> bool;
+
+    /// Fired when the Application is about to terminate.
+    fn will_terminate(&self);
+
+    /// Fired when the window with the given id is about to close.
+    fn window_will_close(&self, window_id: usize);
+}
+
+/// A `Backend` is what actually puts pixels on the screen. Alchemy drives it: it asks for
+/// native view, text and window nodes, arranges them into a tree, and hands over computed
+/// `Appearance` and `Layout` values. Everything is passed around as opaque handles that only
+/// the backend knows how to interpret, so backends can live in their own crates.
+///
+/// Backends ship with Alchemy for `cocoa` and `headless`; you can provide your own via
+/// `App::set_backend()`.
+pub trait Backend: Send + Sync {
+    /// Creates a native view node (e.g, an `NSView`).
+    fn create_view(&self) -> PlatformSpecificNodeType;
+
+    /// Creates a native text node (e.g, a label).
+    fn create_text(&self) -> PlatformSpecificNodeType;
+
+    /// Creates a native window, with `content_view` as its root node. The `app` handle should be
+    /// retained so that window-level events (e.g, closing) can be looped back around.
+    fn create_window(
+        &self,
+        window_id: usize,
+        content_view: &PlatformSpecificNodeType,
+        app: AppHandle
+    ) -> PlatformSpecificNodeType;
+
+    /// Appends `child` to the end of `parent`'s children.
+    fn append_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType);
+
+    /// Inserts `child` into `parent`'s children at `index`. If `index` is past the end, this should
+    /// behave like `append_child`. If `child` is already attached, it should be moved.
+    fn insert_child(&self, parent: &PlatformSpecificNodeType, index: usize, child: &PlatformSpecificNodeType);
+
+    /// Replaces `old_child` with `new_child`, in the same position in `parent`'s children.
+    fn replace_child(
+        &self,
+        parent: &PlatformSpecificNodeType,
+        old_child: &PlatformSpecificNodeType,
+        new_child: &PlatformSpecificNodeType
+    );
+
+    /// Removes `child` from `parent`'s children.
+    fn remove_child(&self, parent: &PlatformSpecificNodeType, child: &PlatformSpecificNodeType);
+
+    /// Given a configured `appearance` and computed `layout`, updates the native node to match.
+    fn apply_styles(&self, node: &PlatformSpecificNodeType, appearance: &Appearance, layout: &Layout);
+
+    /// Sets the text displayed by a text node.
+    fn set_text(&self, node: &PlatformSpecificNodeType, text: &str);
+
+    /// Sets the title of a window.
+    fn set_window_title(&self, window: &PlatformSpecificNodeType, title: &str);
+
+    /// Sets the frame of a window.
+    fn set_window_dimensions(&self, window: &PlatformSpecificNodeType, x: f64, y: f64, width: f64, height: f64);
+
+    /// Applies window-level styles. Many platforms are better off letting the content view
+    /// handle this, so it does nothing by default.
+    fn apply_window_styles(&self, _window: &PlatformSpecificNodeType, _appearance: &Appearance) {}
+
+    /// Shows a window.
+    fn show_window(&self, window: &PlatformSpecificNodeType);
+
+    /// Closes a window. Backends should notify `app._window_will_close()` as part of this,
+    /// the same way a system-initiated close would.
+    fn close_window(&self, window: &PlatformSpecificNodeType);
+
+    /// Returns the system's current light or dark appearance, which `prefers-color-scheme` media
+    /// queries are evaluated against. Platforms without such a thing can leave this as light.
+    fn color_scheme(&self) -> ColorScheme { ColorScheme::Light }
+
+    /// Schedules `handler` to run on the main thread, on a later tick of the runloop. Alchemy uses
+    /// this to batch up state updates.
+    fn dispatch(&self, handler: Box<FnOnce() + Send + 'static>);
+
+    /// Runs the application. This is typically a blocking operation, and should fire the
+    /// `AppDelegate` lifecycle methods on `app` as the platform emits them.
+    fn run(&self, app: AppHandle);
+}
+
 /// Each platform has their own `Window` API, which Alchemy attempts to pair down to one consistent
 /// API. This also acts as the bootstrapping point for a `render` tree.
 pub trait WindowDelegate: Send + Sync {
@@ -72,8 +179,27 @@ pub trait WindowDelegate: Send + Sync {
     fn render(&self) -> Result<RSX, Error> { Ok(RSX::None) }
 }
 
+/// Handles passing props down to a `Component`. You generally want to `#[derive(Props)]` rather
+/// than implement this by hand.
 pub trait Props {
-    fn set_props(&mut self, new_props: &mut Any);
+    /// Called with new props before the component renders, both on mount and on update.
+    fn set_props(&mut self, new_props: &Any);
+
+    /// The names of any props that have to be set wherever this component is used. `rsx! {}`
+    /// checks these when building the props.
+    fn required_props() -> &'static [&'static str] where Self: Sized { &[] }
+}
+
+/// Allows a `Component` trait object to be downcast back to its concrete type, which is how
+/// `RenderEngine::set_state()` hands your updater the right type. This is implemented for you.
+pub trait AsAny {
+    fn as_any_mut(&mut self) -> &mut Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut Any {
+        self
+    }
 }
 
 /// The `Component` lifecycle, mostly inspired from React, with a few extra methods for views that
@@ -83,7 +209,7 @@ pub trait Props {
 /// Alchemy does not currently implement Hooks, and at the moment has no plans to do so (the API
 /// doesn't feel comfortable in Rust, in any way I tried). If you think you have an interesting
 /// proposal for this, feel free to open an issue!
-pub trait Component: Props + Send + Sync {
+pub trait Component: AsAny + Props + Send + Sync {
     fn new(key: ComponentKey) -> Self where Self: Sized;
 
     /// Indicates whether a Component instance carries a native backing node. If you return `true`
@@ -99,8 +225,14 @@ pub trait Component: Props + Send + Sync {
     fn append_child_node(&self, _component: PlatformSpecificNodeType) {}
 
     /// If you implement a Native-backed component, you'll need to implement this. Given a
-    /// `node`, you need to instruct the system how to replace it in the tree at your point.
-    fn replace_child_node(&self, _component: PlatformSpecificNodeType) {}
+    /// `node`, you need to instruct the system how to insert it into the tree at `index` at your
+    /// point, moving it if it's already there.
+    fn insert_child_node(&self, _index: usize, _component: PlatformSpecificNodeType) {}
+
+    /// If you implement a Native-backed component, you'll need to implement this. Given an `old`
+    /// and a `new` node, you need to instruct the system how to swap `new` into the tree in place
+    /// of `old` at your point.
+    fn replace_child_node(&self, _old: PlatformSpecificNodeType, _new: PlatformSpecificNodeType) {}
 
     /// If you implement a Native-backed component, you'll need to implement this. Given a
     /// `node`, you need to instruct the system how to remove it from the tree at your point.
@@ -111,9 +243,9 @@ pub trait Component: Props + Send + Sync {
     fn apply_styles(&self, _appearance: &Appearance, _layout: &Layout) {}
 
     /// Invoked right before calling the render method, both on the initial mount and on subsequent updates.
-    /// It should return an object to update the state, or null to update nothing.
+    /// Props have already been set by this point, so update any state that depends on them here.
     /// This method exists for rare use cases where the state depends on changes in props over time.
-    fn get_derived_state_from_props(&self) {}
+    fn get_derived_state_from_props(&mut self) {}
     
     /// Invoked right before the most recently rendered output is committed to the backing layer tree.
     /// It enables your component to capture some information from the tree (e.g. scroll position) before it's 
@@ -146,16 +278,28 @@ pub trait Component: Props + Send + Sync {
     /// Invoked after an error has been thrown by a descendant component. Called during the "commit" phase, 
     /// so side-effects are permitted. It should be used for things like logging errors (e.g,
     /// Sentry).
-    fn component_did_catch(&mut self /* error: */) {}
+    ///
+    /// This is only called if `is_error_boundary()` returns `true`.
+    fn component_did_catch(&mut self, _error: &Error) {}
+
+    /// Return `true` from this to make the component an error boundary: if a descendant returns an
+    /// error from `render()`, the failing subtree is unmounted, and this component re-renders (after
+    /// `get_derived_state_from_error()`) with whatever fallback UI it likes. Errors from the
+    /// component's own `render()` go to the next boundary up. Returns `false` by default.
+    fn is_error_boundary(&self) -> bool { false }
 
     /// Use this to let Alchemy know if a component’s output is not affected by the current change in state 
     /// or props. The default behavior is to re-render on every state change, and in the vast majority of 
     /// cases you should rely on the default behavior.
     ///
     /// This is invoked before rendering when new props or state are being received. Defaults to true. This 
-    /// method is not called for the initial render or when force_update() is used. This method only exists 
+    /// method is not called for the initial render or when `RenderEngine::force_update()` is used. This method only exists 
     /// as a performance optimization. Do not rely on it to “prevent” a rendering, as this can lead to bugs.
-    fn should_component_update(&self) -> bool { true }
+    ///
+    /// When the parent re-renders, `next_props` holds the incoming props (your `{Name}Props` struct, which
+    /// `downcast_ref()` gets you back to), and `self` still has the current ones to compare against; they're
+    /// set either way. For a state update, `next_props` is `None`, and the new state is already in place.
+    fn should_component_update(&self, _next_props: Option<&Any>) -> bool { true }
 
     /// The only required method for a `Component`. Should return a Result of RSX nodes, or an
     /// Error (in very rare cases, such as trying to get a key from a strange HashMap or
@@ -172,22 +316,10 @@ pub trait Component: Props + Send + Sync {
     fn render(&self, children: Vec<RSX>) -> Result<RSX, Error> { Ok(RSX::None) }
 
     /// This lifecycle is invoked after an error has been thrown by a descendant component. It receives 
-    /// the error that was thrown as a parameter and should return a value to update state.
+    /// the error that was thrown as a parameter, and should update state so that the next `render()`
+    /// shows a fallback UI.
     ///
     /// This is called during the "render" phase, so side-effects are not permitted. 
     /// For those use cases, use component_did_catch() instead.
-    fn get_derived_state_from_error(&self, _error: ()) {}
-
-    /// By default, when your component’s state or props change, your component will re-render. 
-    /// If your `render()` method depends on some other data, you can tell Alchemy that the component 
-    /// needs re-rendering by calling `force_update()`.
-    ///
-    /// Calling `force_update()` will cause `render()` to be called on the component, skipping 
-    /// `should_component_update()`. This will trigger the normal lifecycle methods for child components, 
-    /// including the `should_component_update()` method of each child. Alchemy will still only update the 
-    /// backing widget tree if the markup changes.
-    ///
-    /// Normally, you should try to avoid all uses of `force_update()` and only read from `this.props` 
-    /// and `this.state` in `render()`.
-    fn force_update(&self) {}
+    fn get_derived_state_from_error(&mut self, _error: &Error) {}
 }
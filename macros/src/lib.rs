@@ -4,7 +4,8 @@
 //! Implements macros used in Alchemy.
 //!
 //! - `rsx! {}`, which turns RSX tags into `RSX` node trees.
-//! - `styles! {}`, which turns CSS stylesheet strings into `Vec<Styles>`.
+//! - `styles! {}`, which turns CSS stylesheets into `StyleSheet`s.
+//! - `include_styles!()`, which does the same for a `.css` file.
 //!
 //! In general, you should prefer using these to constructing the above values manually.
 //!
@@ -14,22 +15,22 @@
 extern crate proc_macro;
 
 mod error;
+mod props;
 mod rsx;
 mod ident;
 mod lexer;
 mod map;
 mod parser;
 mod span;
+mod styles;
 
 use proc_macro::TokenStream;
-use proc_macro2::{Ident, TokenStream as TokenStream2, Literal, Span};
+use proc_macro2::{Ident, Literal, Span};
 use proc_macro_hack::proc_macro_hack;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{DeriveInput, parse_macro_input};
 
-use alchemy_styles::cssparser::{Parser, ParserInput, RuleListParser};
-use alchemy_styles::styles_parser::{Rule, RuleParser};
-
 /// Implements the `rsx! {}` macro, which turns RSX tags into `RSX` node trees.
 #[proc_macro_hack]
 pub fn rsx(input: TokenStream) -> TokenStream {
@@ -44,64 +45,131 @@ pub fn rsx(input: TokenStream) -> TokenStream {
     })
 }
 
-/// Implements the `styles! {}` macro, which turns CSS stylesheet strings into `Vec<Styles>`.
+/// Implements the `styles! {}` macro, which turns CSS stylesheet strings into a `StyleSheet`.
+/// Anything that fails to parse is a compile error. The stylesheet can also be given as a string
+/// literal, e.g `styles!(".a .b { ... }")`, which stable Rust needs for selectors like `.a .b`
+/// or `Text.label`.
 #[proc_macro_hack]
 pub fn styles(input: TokenStream) -> TokenStream {
-    let s = input.to_string().replace(" ", "");
-    let mut input = ParserInput::new(&s);
-    let mut parser = Parser::new(&mut input);
-        
-    let parsed: Vec<Rule> = RuleListParser::new_for_stylesheet(&mut parser, RuleParser {})
-        .collect::<Vec<_>>()
-        .into_iter()
-        .filter_map(|rule| {
-            rule.ok()
-        })
-        .collect();
-
-    let mut body = TokenStream2::new();
-    for rule in parsed {
-        let mut stream = TokenStream2::new();
-        for style in rule.styles {
-            stream.extend(quote!(#style,));
-        }
+    TokenStream::from(styles::expand_styles(input.into()))
+}
 
-        let key = Literal::string(&rule.key);
-        body.extend(quote!(styles.insert(#key, vec![#stream]);))
-    }
-        
-    quote!(alchemy::StyleSheet::new({
-        use alchemy::style_attributes::*;
-        use alchemy::Color;
-        let mut styles = std::collections::HashMap::new();
-        #body
-        styles
-    })).into()
+/// Implements the `include_styles!("path.css")` macro, which compiles a CSS file (relative to
+/// your crate's `Cargo.toml`) into a `StyleSheet`. Changes to the file trigger a rebuild.
+#[proc_macro_hack]
+pub fn include_styles(input: TokenStream) -> TokenStream {
+    TokenStream::from(styles::expand_include_styles(input.into()))
 }
 
 /// Implements a derive macro for automating props setting and conversion.
-#[proc_macro_derive(Props)]
+///
+/// Fields on your `Component` marked with `#[props]` are treated as props: a `{Name}Props` struct
+/// is generated with those fields, and they're copied over (field by field) whenever new props
+/// come in, just before `render()` is called. Any other fields are left alone, so they're free to
+/// be used as state.
+///
+/// - `#[props]` uses `Default::default()` if the prop isn't set.
+/// - `#[props(default = "expr")]` uses `expr` instead, e.g
+/// `#[props(default = "String::from(\"Untitled\")")]`. Other literals can be used as-is, e.g
+/// `#[props(default = 10)]`.
+/// - `#[props(required)]` must be set wherever the component is used. If it's not, mounting (or
+/// updating) the component fails with a render error, which error boundaries can catch.
+///
+/// Props need to implement `Clone`, and required ones `Default` (which is used as a
+/// placeholder). Generic components are supported, with the `{Name}Props` struct taking the same
+/// type parameters.
+#[proc_macro_derive(Props, attributes(props))]
 pub fn writable_props_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
+    let vis = &input.vis;
     let name_props = Ident::new(&format!("{}Props", name), Span::call_site());
-    let generics = input.generics;
+    let generics = &input.generics;
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
+    let props = match props::parse_fields(&input) {
+        Ok(props) => props,
+        Err(err) => { return err.to_compile_error().into(); }
+    };
+
+    let fields = props.iter().map(|prop| {
+        let (ident, ty) = (&prop.ident, &prop.ty);
+        quote!(pub #ident: #ty)
+    }).collect::<Vec<_>>();
+
+    // Type parameters that don't end up in any props still need using somewhere. This is a
+    // function pointer so that it doesn't affect whether the struct is `Send` or `Sync`.
+    let type_params = generics.type_params().map(|param| &param.ident).collect::<Vec<_>>();
+    let (marker, marker_default) = match type_params.len() {
+        0 => (quote!(), quote!()),
+        _ => (
+            quote!(#[doc(hidden)] pub __alchemy_marker: std::marker::PhantomData<fn() -> (#(#type_params,)*)>,),
+            quote!(__alchemy_marker: std::marker::PhantomData,)
+        )
+    };
+
+    let defaults = props.iter().map(|prop| {
+        let ident = &prop.ident;
+        match &prop.default {
+            Some(default) => quote!(#ident: #default),
+            None => quote!(#ident: std::default::Default::default())
+        }
+    });
+
+    // Props are cloned over from the `{Name}Props` struct; going through a function with a
+    // `Clone` bound means a prop that isn't `Clone` gets an error pointing at it, which says so.
+    let assignments = props.iter().map(|prop| {
+        let ident = &prop.ident;
+        quote_spanned!(prop.ty.span() => self.#ident = prop_must_implement_clone(&props.#ident);)
+    }).collect::<Vec<_>>();
+
+    let set_props = match assignments.len() {
+        0 => quote!(Some(_) => {}),
+        _ => quote!(Some(props) => {
+            fn prop_must_implement_clone<T: std::clone::Clone>(prop: &T) -> T {
+                std::clone::Clone::clone(prop)
+            }
+
+            #(#assignments)*
+        })
+    };
+
+    let required = props.iter().filter(|prop| prop.required).map(|prop| {
+        Literal::string(&prop.ident.to_string())
+    });
+
     TokenStream::from(quote! {
+        #vis struct #name_props #impl_generics #where_clause {
+            #marker
+            #(#fields),*
+        }
+
+        impl #impl_generics std::default::Default for #name_props #ty_generics #where_clause {
+            fn default() -> #name_props #ty_generics {
+                #name_props {
+                    #marker_default
+                    #(#defaults),*
+                }
+            }
+        }
+
         impl #impl_generics #name #ty_generics #where_clause {
-            pub fn default_props() -> #name_props {
-                #name_props::default()
+            pub fn default_props() -> #name_props #ty_generics {
+                std::default::Default::default()
             }
         }
 
         impl #impl_generics alchemy::ComponentProps for #name #ty_generics #where_clause {
-            fn set_props(&mut self, new_props: &mut std::any::Any) {
-                match new_props.downcast_ref::<#name_props>() {
-                    Some(props) => { },
+            fn set_props(&mut self, new_props: &std::any::Any) {
+                match new_props.downcast_ref::<#name_props #ty_generics>() {
+                    #set_props,
                     None => { panic!("Woah there, somehow the wrong props were being passed!"); }
                 }
             }
+
+            fn required_props() -> &'static [&'static str] {
+                &[#(#required),*]
+            }
         }
     })
 }
@@ -0,0 +1,93 @@
+//! Parses the `#[props]` attributes used by `#[derive(Props)]`.
+
+use proc_macro2::Span;
+use syn::{Data, DeriveInput, Error, Expr, Fields, GenericParam, Ident, Lit, Meta, NestedMeta, Type};
+
+/// A field on a `Component` that's been marked as a prop.
+pub struct Prop {
+    pub ident: Ident,
+    pub ty: Type,
+    pub default: Option<Expr>,
+    pub required: bool
+}
+
+/// Returns the fields on the given struct that are marked with `#[props]`, in the order they're
+/// declared. Anything else is left alone.
+pub fn parse_fields(input: &DeriveInput) -> Result<Vec<Prop>, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => vec![],
+            Fields::Unnamed(fields) => {
+                return Err(Error::new_spanned(fields, "#[derive(Props)] requires named fields"));
+            }
+        },
+
+        _ => {
+            return Err(Error::new(Span::call_site(), "#[derive(Props)] can only be used on structs"));
+        }
+    };
+
+    // Props get passed around as `Any`, so they can't borrow anything.
+    for param in &input.generics.params {
+        match param {
+            GenericParam::Type(_) => {},
+            param => {
+                return Err(Error::new_spanned(param, "#[derive(Props)] only supports type parameters, as props have to be 'static"));
+            }
+        }
+    }
+
+    let mut props = vec![];
+    for field in fields {
+        for attr in &field.attrs {
+            if attr.path.segments.len() != 1 || attr.path.segments[0].ident != "props" {
+                continue;
+            }
+
+            let mut prop = Prop {
+                ident: field.ident.clone().unwrap(),
+                ty: field.ty.clone(),
+                default: None,
+                required: false
+            };
+
+            match attr.parse_meta()? {
+                Meta::Word(_) => {},
+
+                Meta::List(list) => {
+                    for item in list.nested {
+                        match item {
+                            NestedMeta::Meta(Meta::Word(ref word)) if word == "required" => {
+                                prop.required = true;
+                            },
+
+                            NestedMeta::Meta(Meta::NameValue(ref pair)) if pair.ident == "default" => {
+                                prop.default = Some(match &pair.lit {
+                                    Lit::Str(s) => s.parse()?,
+                                    lit => syn::parse_quote!(#lit)
+                                });
+                            },
+
+                            item => {
+                                return Err(Error::new_spanned(item, "expected `default = ...` or `required`"));
+                            }
+                        }
+                    }
+                },
+
+                meta => {
+                    return Err(Error::new_spanned(meta, "expected #[props], #[props(default = ...)] or #[props(required)]"));
+                }
+            }
+
+            if prop.required && prop.default.is_some() {
+                return Err(Error::new_spanned(attr, "a prop can't be both required and have a default"));
+            }
+
+            props.push(prop);
+        }
+    }
+
+    Ok(props)
+}
@@ -1,4 +1,4 @@
-use proc_macro2::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Literal, TokenStream, TokenTree};
 use quote::{quote, quote_spanned};
 
 use crate::error::ParseError;
@@ -68,6 +68,19 @@ pub struct Element {
     pub children: Vec<Node>,
 }
 
+/// The events that can be handled via `on*` attributes, named as they are on `EventHandlers`.
+/// Each of these can also be handled in the capture phase, by adding a `Capture` suffix. The
+/// second item is the type of details handlers receive, which closures need spelled out for
+/// inference.
+const EVENTS: [(&str, &str); 8] = [
+    ("pointer_down", "PointerEvent"), ("pointer_up", "PointerEvent"),
+    ("pointer_move", "PointerEvent"), ("click", "PointerEvent"),
+    ("key_down", "KeyEvent"), ("key_up", "KeyEvent"),
+    ("focus", "FocusEvent"), ("blur", "FocusEvent")
+];
+
+/// Pulls `on*` attributes (e.g, `onClick`) out of `attrs`, returning them keyed by the matching
+/// field on `EventHandlers` (e.g, `click`).
 fn extract_event_handlers(
     attrs: &mut StringyMap<Ident, TokenTree>,
 ) -> StringyMap<Ident, TokenTree> {
@@ -76,15 +89,35 @@ fn extract_event_handlers(
     for key in keys {
         let key_name = key.to_string();
         let prefix = "on";
-        if key_name.starts_with(prefix) {
-            let event_name = &key_name[prefix.len()..];
+        let is_event = key_name.starts_with(prefix) &&
+            key_name[prefix.len()..].chars().next().map_or(false, char::is_uppercase);
+
+        if is_event {
+            let event_name = snake_case(&key_name[prefix.len()..]);
             let value = attrs.remove(&key).unwrap();
-            events.insert(ident::new_raw(event_name, key.span()), value);
+            events.insert(ident::new_raw(&event_name, key.span()), value);
         }
     }
     events
 }
 
+/// Converts `PointerDownCapture` to `pointer_down_capture`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
 fn process_value(value: &TokenTree) -> TokenStream {
     match value {
         TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => {
@@ -128,10 +161,29 @@ impl Element {
         }); 
        
         let mut attributes = TokenStream::new();
+        let mut provided_props = vec![];
+        let mut rsx_key = None;
+        let mut rsx_theme = None;
         let mut styles = TokenStream::new();
         styles.extend(quote!(alchemy::SpacedSet::new()));
 
         for (attr_str, key, value) in attrs {
+            if attr_str == "key" {
+                let value = process_value(value);
+                rsx_key = Some(quote!(std::string::ToString::to_string(&#value)));
+                continue;
+            }
+
+            if attr_str == "theme" {
+                let value = process_value(value);
+                rsx_theme = Some(quote!(std::string::ToString::to_string(&#value)));
+                continue;
+            }
+
+            if attr_str != "styles" {
+                provided_props.push(Literal::string(&attr_str));
+            }
+
             match value {
                 TokenTree::Literal(lit) if is_string_literal(lit) => {
                     let mut eprintln_msg = "ERROR: ".to_owned();
@@ -175,10 +227,6 @@ impl Element {
                         continue;
                     }
 
-                    if prop == "r#key" {
-                        continue;
-                    }
-
                     attributes.extend(quote!(
                         props.#key = std::convert::Into::into(#value);
                     ));
@@ -186,22 +234,23 @@ impl Element {
             }
         }
         
-        for (key, _value) in events.iter() {
-            if ty.is_none() {
-                let mut err = quote_spanned! { key.span() =>
-                    compile_error! { "when using event handlers, you must declare the output type inside the rsx! macro" }
-                };
-                let hint = quote_spanned! { Span::call_site() =>
-                    compile_error! { "for example: change rsx!(<div>...</div>) to rsx!(<div>...</div> : String)" }
-                };
-                err.extend(hint);
-                return Err(err);
-            }
-            //let key = TokenTree::Ident(key.clone());
-            //let value = process_value(value);
-            /*body.extend(quote!(
-                element.events.#key = Some(alchemy::dom::events::IntoEventHandler::into_event_handler(#value));
-            ));*/
+        let mut event_handlers = TokenStream::new();
+        for (key, value) in events.iter() {
+            let event = stringify_ident(key);
+            let detail = match EVENTS.iter().find(|(e, _)| *e == event || format!("{}_capture", e) == event) {
+                Some((_, detail)) => Ident::new(detail, key.span()),
+                None => {
+                    let error = format!("<{}> has no event handler for `{}`", name_str, event);
+                    return Err(quote_spanned! { key.span() =>
+                        compile_error! { #error }
+                    });
+                }
+            };
+
+            let value = process_value(value);
+            event_handlers.extend(quote!(
+                events.#key = Some(alchemy::EventHandler::<alchemy::#detail>::new(#value));
+            ));
         }
 
         /*let mut args = TokenStream::new();
@@ -218,19 +267,43 @@ impl Element {
 
         let component_name = Literal::string(&typename.to_string());
 
-        Ok(quote! {
-            alchemy::RSX::node(#component_name, #styles, |key| {
+        let rsx_key = match rsx_key {
+            Some(rsx_key) => quote!(Some(#rsx_key)),
+            None => quote!(None)
+        };
+
+        let rsx_theme = match rsx_theme {
+            Some(rsx_theme) => quote!(Some(#rsx_theme)),
+            None => quote!(None)
+        };
+
+        // Required props that weren't set are passed along with the node, which fails to mount if
+        // there are any; an error boundary can then deal with it.
+        Ok(quote! {{
+            let provided: &[&str] = &[#(#provided_props),*];
+            let missing = <#typename as alchemy::ComponentProps>::required_props().iter()
+                .filter(|prop| !provided.contains(prop))
+                .cloned()
+                .collect();
+
+            alchemy::RSX::keyed_node(#rsx_key, #rsx_theme, #component_name, #styles, {
+                #[allow(unused_mut)]
+                let mut events = alchemy::EventHandlers::default();
+                #event_handlers
+                events
+            }, |key| {
                 Box::new(<#typename as alchemy::Component>::new(key))
             }, {
-                let props = #typename::default_props();
+                #[allow(unused_mut)]
+                let mut props = #typename::default_props();
                 #attributes
-                Box::new(props)
+                props
             }, {
                 let mut children = vec![];
                 #children
                 children
-            })
-        })
+            }).with_missing_props(missing)
+        }})
     }
 }
 
@@ -0,0 +1,334 @@
+//! Implements the `styles! {}` and `include_styles!()` macros.
+//!
+//! By the time CSS reaches a macro, it's been tokenized as Rust - so the whitespace is gone, and
+//! with it the difference between e.g `LOL Text` and `LOLText`. The stylesheet is pieced back
+//! together from the tokens, putting whitespace back where CSS needs it, and keeping track of
+//! where each token ended up, so that anything that fails to parse can be reported as a compile
+//! error on the token at fault.
+//!
+//! On nightly, where each token sat in the source is known, so whitespace goes back exactly where
+//! it was. Elsewhere, it's inferred from the tokens themselves, which can't tell e.g `.a .b` from
+//! `.a.b`, or `Text .label` from `Text.label`. Rather than guess, a `.` straight after a name or a
+//! `)` is a compile error there; stylesheets that need one can be passed as a string literal
+//! instead (e.g `styles!(".a .b { ... }")`), or kept in a file for `include_styles!()`.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use proc_macro2::{Delimiter, Spacing, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+use syn::LitStr;
+
+use alchemy_styles::styles_parser::{CssError, Rule, parse_rules};
+
+/// Identifiers that can be followed by a parenthesized group without being a function call, e.g
+/// `@media screen and (max-width: 600px)`.
+static MEDIA_KEYWORDS: &[&str] = &["media", "and", "not", "only", "or"];
+
+/// Reported for a `.` that might or might not have had whitespace before it in the source.
+static AMBIGUOUS_DOT: &str = "can't tell whether there's whitespace before this `.` (e.g `.a .b` \
+    vs `.a.b`) outside of nightly; pass the stylesheet as a string literal instead, e.g \
+    `styles!(\".a .b { ... }\")`, or use `include_styles!()`";
+
+/// What the last token pushed was, which decides whether the next one needs whitespace before it.
+#[derive(Debug)]
+enum Previous {
+    Nothing,
+    Ident(String),
+    Literal,
+    Punct(char, Spacing),
+    Open,
+    Close
+}
+
+/// CSS rebuilt from tokens, along with the byte range each token occupies in it, and any `.`s
+/// where it can't be known whether there was whitespace before them.
+struct Source {
+    css: String,
+    tokens: Vec<(usize, usize, Span)>,
+    ambiguous: Vec<Span>,
+    previous: Previous,
+    previous_span: Option<Span>
+}
+
+impl Source {
+    fn new(input: TokenStream) -> Source {
+        let mut source = Source {
+            css: String::new(),
+            tokens: vec![],
+            ambiguous: vec![],
+            previous: Previous::Nothing,
+            previous_span: None
+        };
+
+        source.push_stream(input);
+        source
+    }
+
+    fn push_stream(&mut self, stream: TokenStream) {
+        for token in stream {
+            match token {
+                TokenTree::Group(group) => {
+                    let (open, close) = match group.delimiter() {
+                        Delimiter::Parenthesis => ("(", ")"),
+                        Delimiter::Brace => ("{", "}"),
+                        Delimiter::Bracket => ("[", "]"),
+                        Delimiter::None => ("", "")
+                    };
+
+                    let space = match &self.previous {
+                        Previous::Ident(ident) => group.delimiter() != Delimiter::Parenthesis || MEDIA_KEYWORDS.contains(&ident.as_str()),
+                        previous => needs_space(previous, None)
+                    };
+
+                    // A group only has the one span, which won't do for telling whether there's
+                    // whitespace just inside of it (not that it matters there).
+                    self.push(open, group.span(), space, Previous::Open);
+                    self.previous_span = None;
+                    self.push_stream(group.stream());
+                    self.previous_span = None;
+                    self.push(close, group.span(), false, Previous::Close);
+                },
+
+                TokenTree::Ident(ident) => {
+                    let space = needs_space(&self.previous, None);
+                    self.push(&ident.to_string(), ident.span(), space, Previous::Ident(ident.to_string()));
+                },
+
+                TokenTree::Literal(literal) => {
+                    let space = needs_space(&self.previous, None);
+                    self.push(&literal.to_string(), literal.span(), space, Previous::Literal);
+                },
+
+                TokenTree::Punct(punct) => {
+                    if punct.as_char() == '.' && self.is_ambiguous(punct.span()) {
+                        self.ambiguous.push(punct.span());
+                    }
+
+                    let space = needs_space(&self.previous, Some(punct.as_char()));
+                    self.push(&punct.as_char().to_string(), punct.span(), space, Previous::Punct(punct.as_char(), punct.spacing()));
+                }
+            }
+        }
+    }
+
+    /// Pushes a token, preceded by whitespace if `space` is set - unless it's known whether there
+    /// was any in the source, in which case that wins.
+    fn push(&mut self, text: &str, span: Span, space: bool, previous: Previous) {
+        let space = match self.previous_span {
+            Some(previous_span) => is_spaced(previous_span, span).unwrap_or(space),
+            None => space
+        };
+
+        if space {
+            self.css.push(' ');
+        }
+
+        let start = self.css.len();
+        self.css.push_str(text);
+        self.tokens.push((start, self.css.len(), span));
+        self.previous = previous;
+        self.previous_span = Some(span);
+    }
+
+    /// Whether a `.` at `span` might have had whitespace before it, with no way of knowing. After
+    /// a name or a `)`, it could be either a compound selector or a descendant one.
+    fn is_ambiguous(&self, span: Span) -> bool {
+        match (&self.previous, self.previous_span) {
+            (Previous::Ident(_), Some(previous_span)) | (Previous::Close, Some(previous_span)) => {
+                is_spaced(previous_span, span).is_none()
+            },
+
+            _ => false
+        }
+    }
+
+    /// Finds the span of the token that `error` is about. The rebuilt CSS is all on one line, so
+    /// only the column matters; cssparser counts these in UTF-16 code units, starting at 1.
+    fn span_for(&self, error: &CssError) -> Span {
+        let column = (error.column as usize).saturating_sub(1);
+        let mut units = 0;
+        let offset = self.css.char_indices().find(|(_, c)| {
+            units += c.len_utf16();
+            units > column
+        }).map_or(self.css.len(), |(offset, _)| offset);
+
+        self.tokens.iter()
+            .find(|(_, end, _)| offset < *end)
+            .or(self.tokens.last())
+            .map_or_else(Span::call_site, |(_, _, span)| *span)
+    }
+}
+
+/// Whether there's whitespace between two tokens in the source, if that can be known.
+#[cfg(can_show_location_of_runtime_parse_error)]
+fn is_spaced(previous: Span, next: Span) -> Option<bool> {
+    let (end, start) = (previous.unstable().end(), next.unstable().start());
+    Some(end.line != start.line || end.column != start.column)
+}
+
+#[cfg(not(can_show_location_of_runtime_parse_error))]
+fn is_spaced(_previous: Span, _next: Span) -> Option<bool> {
+    None
+}
+
+/// Whether whitespace is needed before a token, given what came before it. `next` is the
+/// character, if the token is punctuation.
+///
+/// CSS is sensitive to whitespace between words (e.g, descendant selectors, or values like
+/// `1px solid red`), but not around most punctuation - and some of it can't have any, e.g the
+/// `-` in `font-size`, or the `#` in `#307ace`.
+fn needs_space(previous: &Previous, next: Option<char>) -> bool {
+    match previous {
+        Previous::Nothing | Previous::Open => { return false; },
+        Previous::Punct(_, Spacing::Joint) => { return false; },
+        Previous::Punct(c, _) if "#.-:@!".contains(*c) => { return false; },
+        _ => {}
+    }
+
+    match next {
+        Some(c) => !".-:%,;".contains(c),
+        None => true
+    }
+}
+
+/// Expands `styles! {}` into a `StyleSheet`. Anything that fails to parse becomes a
+/// `compile_error!` on the offending token. The stylesheet can also be a string literal, in which
+/// case errors are reported on the literal, with the line and column.
+pub fn expand_styles(input: TokenStream) -> TokenStream {
+    if let Ok(literal) = syn::parse2::<LitStr>(input.clone()) {
+        let (parsed, errors) = parse_rules(&literal.value());
+
+        let errors = errors.iter().map(|error| {
+            let message = error.to_string();
+            quote_spanned!(literal.span() => compile_error!(#message);)
+        });
+
+        let stylesheet = stylesheet(parsed);
+        return quote!({
+            #(#errors)*
+            #stylesheet
+        });
+    }
+
+    let source = Source::new(input);
+    let (parsed, errors) = parse_rules(&source.css);
+
+    let errors = errors.iter().map(|error| {
+        let message = &error.message;
+        quote_spanned!(source.span_for(error) => compile_error!(#message);)
+    });
+
+    let ambiguous = source.ambiguous.iter().map(|span| {
+        quote_spanned!(*span => compile_error!(#AMBIGUOUS_DOT);)
+    });
+
+    let stylesheet = stylesheet(parsed);
+    quote!({
+        #(#ambiguous)*
+        #(#errors)*
+        #stylesheet
+    })
+}
+
+/// Expands `include_styles!("path.css")` into a `StyleSheet`, reading the file (relative to
+/// `CARGO_MANIFEST_DIR`) at compile time. Anything that fails to parse becomes a
+/// `compile_error!`, reporting the file, line and column.
+pub fn expand_include_styles(input: TokenStream) -> TokenStream {
+    let literal: LitStr = match syn::parse2(input) {
+        Ok(literal) => literal,
+        Err(e) => { return e.to_compile_error(); }
+    };
+
+    // This env var is set by Cargo, so it's always there when building.
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join(literal.value());
+    let css = match fs::read_to_string(&path) {
+        Ok(css) => css,
+        Err(e) => {
+            let message = format!("Unable to read {}: {}", path.display(), e);
+            return quote_spanned!(literal.span() => compile_error!(#message));
+        }
+    };
+
+    let (parsed, errors) = parse_rules(&css);
+
+    let errors = errors.iter().map(|error| {
+        let message = format!("{}:{}", path.display(), error);
+        quote_spanned!(literal.span() => compile_error!(#message);)
+    });
+
+    // Including the file is how Cargo knows to rebuild when it changes.
+    let path = path.to_string_lossy();
+    let stylesheet = stylesheet(parsed);
+    quote!({
+        let _ = include_str!(#path);
+        #(#errors)*
+        #stylesheet
+    })
+}
+
+/// Generates the code for a `StyleSheet` holding `parsed`.
+fn stylesheet(parsed: Vec<Rule>) -> TokenStream {
+    let rules = parsed.into_iter().map(|rule| {
+        let media = rule.media;
+        let selectors = rule.selectors;
+        let styles = rule.styles;
+
+        quote!(StyleRule {
+            media: vec![#(#media),*],
+            selectors: vec![#(#selectors),*],
+            styles: vec![#(#styles),*]
+        })
+    });
+
+    quote!(alchemy::StyleSheet::new({
+        use alchemy::style_attributes::*;
+        use alchemy::Color;
+        vec![#(#rules),*]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::TokenStream;
+    use quote::quote;
+
+    use super::{expand_styles, Source, AMBIGUOUS_DOT};
+
+    // Outside of a real macro expansion, `quote!` can't lex literals with suffixes (like `10px`),
+    // so these stick to percentages and keywords.
+    fn errors(input: TokenStream) -> usize {
+        expand_styles(input).to_string().matches("compile_error").count()
+    }
+
+    #[test]
+    fn whitespace_is_put_back_where_css_needs_it() {
+        let source = Source::new(quote!(.a > .b { border-style: solid; width: 50%; font-family: Helvetica Neue; }));
+        assert_eq!(source.css, ".a >.b {border-style:solid; width:50%; font-family:Helvetica Neue;}");
+        assert!(source.ambiguous.is_empty());
+    }
+
+    #[cfg(not(can_show_location_of_runtime_parse_error))]
+    #[test]
+    fn dots_after_names_are_ambiguous_without_span_locations() {
+        assert_eq!(Source::new(quote!(.a .b { width: 50%; })).ambiguous.len(), 1);
+        assert_eq!(Source::new(quote!(Text .label { width: 50%; })).ambiguous.len(), 1);
+        assert_eq!(Source::new(quote!(Text.label { width: 50%; })).ambiguous.len(), 1);
+        assert_eq!(Source::new(quote!(.a:hover, .b { width: 50%; })).ambiguous.len(), 0);
+
+        let expanded = expand_styles(quote!(.a .b { width: 50%; })).to_string();
+        assert!(expanded.contains(&format!("{:?}", AMBIGUOUS_DOT)));
+    }
+
+    #[test]
+    fn string_literals_keep_their_whitespace() {
+        assert_eq!(errors(quote!(".a .b { width: 50%; } Text.label { width: 50%; }")), 0);
+        assert_eq!(errors(quote!(".a .b { colour: red; }")), 1);
+    }
+
+    #[test]
+    fn parse_errors_become_compile_errors() {
+        assert_eq!(errors(quote!(a { colour: red; width: nope; height: 50%; })), 2);
+    }
+}
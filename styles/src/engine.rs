@@ -7,24 +7,63 @@
 //! your resulting binary. The hot-reloading functionality is not in release, 
 //! however it can be enabled if desired.
 //!
+//! The places scanned are the `dirs` listed under `[general]` in your `alchemy.toml`, or
+//! `themes/` next to it if there aren't any. In each of these, a theme is either a single
+//! `<theme-name>.css` file, or a `<theme-name>/` directory of `.css` files (loaded in
+//! alphabetical order).
+//!
+//! Hot-reloading works by polling those same places for changes, via `ThemeEngine::watch()`.
+//! To compile a stylesheet into your binary instead, use Alchemy's `include_styles!()` macro.
 
 use std::fs;
 use std::env;
 use std::sync::RwLock;
+use std::sync::atomic::AtomicBool;
 use std::path::PathBuf;
 use std::collections::HashMap;
 
+#[cfg(feature="parser")]
+use std::collections::BTreeMap;
+
+#[cfg(feature="parser")]
+use std::path::Path;
+
+#[cfg(feature="parser")]
+use std::sync::atomic::Ordering;
+
+#[cfg(feature="parser")]
+use std::thread;
+
+#[cfg(feature="parser")]
+use std::time::{Duration, SystemTime};
+
 use toml;
 use serde::Deserialize;
 
 use crate::stretch::style::Style;
 
 use crate::StylesList;
+use crate::media::MediaEnvironment;
+use crate::selector::{ElementState, StyledNode};
 use crate::styles::Appearance;
-use crate::stylesheet::StyleSheet;
+use crate::stylesheet::{StyleSheet, apply_layered_styles, resolve_font_relative_lengths};
+
+#[cfg(feature="parser")]
+use crate::styles_parser::parse_rules;
 
 static CONFIG_FILE_NAME: &str = "alchemy.toml";
 
+/// The theme that's active to begin with, and that other themes fall back to.
+static DEFAULT_THEME: &str = "default";
+
+/// The id that stylesheets loaded from theme files are registered under.
+#[cfg(feature="parser")]
+static THEME_FILES_ID: &str = "alchemy:theme-files";
+
+/// How often `ThemeEngine::watch()` checks theme files for changes.
+#[cfg(feature="parser")]
+static WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
 #[derive(Debug, Deserialize)]
 struct RawConfig<'d> {
     #[serde(borrow)]
@@ -37,12 +76,34 @@ struct General<'a> {
     dirs: Option<Vec<&'a str>>
 }
 
+/// The stylesheets registered for a theme, in the order they were registered (optionally with an
+/// id, so they can be replaced or unregistered later), along with the result of merging them.
+#[derive(Debug, Default)]
+pub struct Theme {
+    stylesheets: Vec<(Option<String>, StyleSheet)>,
+    merged: StyleSheet
+}
+
+impl Theme {
+    /// Rebuilds the merged stylesheet, for when a stylesheet was replaced or removed.
+    fn remerge(&mut self) {
+        let mut merged = StyleSheet::default();
+        for (_, stylesheet) in &self.stylesheets {
+            merged.merge(stylesheet.clone());
+        }
+
+        self.merged = merged;
+    }
+}
+
 /// The `ThemeEngine` controls loading themes and registering associated
 /// styles.
 #[derive(Debug)]
 pub struct ThemeEngine {
     pub dirs: Vec<PathBuf>,
-    pub themes: RwLock<HashMap<String, StyleSheet>>
+    pub themes: RwLock<HashMap<String, Theme>>,
+    active_theme: RwLock<String>,
+    watching: AtomicBool
 }
 
 impl ThemeEngine {
@@ -68,53 +129,279 @@ impl ThemeEngine {
             None => default_dirs
         };
 
-        ThemeEngine { dirs, themes: RwLock::new(HashMap::new()) }
+        ThemeEngine::with_dirs(dirs)
+    }
+
+    /// Creates a new `ThemeEngine` that loads themes from `dirs`, rather than wherever
+    /// `alchemy.toml` says to.
+    pub fn with_dirs(dirs: Vec<PathBuf>) -> ThemeEngine {
+        let engine = ThemeEngine {
+            dirs: dirs,
+            themes: RwLock::new(HashMap::new()),
+            active_theme: RwLock::new(DEFAULT_THEME.to_string()),
+            watching: AtomicBool::new(false)
+        };
+
+        #[cfg(feature="parser")]
+        engine.load_themes();
+
+        engine
+    }
+
+    /// Scans `dirs` for theme files, parses them, and registers a stylesheet for each theme
+    /// found. Calling this again reloads them: each theme's stylesheet is replaced in place (so
+    /// stylesheets registered in code still take precedence), and themes whose files are gone
+    /// are unregistered. Anything that fails to parse is reported (with the file, line and
+    /// column) and skipped, so one bad rule doesn't take the rest of a theme down with it.
+    #[cfg(feature="parser")]
+    pub fn load_themes(&self) {
+        let theme_files = self.theme_files();
+
+        let removed: Vec<String> = {
+            let themes = self.themes.read().unwrap();
+            themes.keys().filter(|theme| !theme_files.contains_key(*theme)).cloned().collect()
+        };
+
+        for theme in removed {
+            self.unregister_styles(&theme, THEME_FILES_ID);
+        }
+
+        for (theme, files) in theme_files {
+            let mut rules = vec![];
+
+            for file in files {
+                let css = match fs::read_to_string(&file) {
+                    Ok(css) => css,
+                    Err(e) => {
+                        eprintln!("Unable to read {}: {}", file.display(), e);
+                        continue;
+                    }
+                };
+
+                let (parsed, errors) = parse_rules(&css);
+                for error in errors {
+                    eprintln!("{}:{}", file.display(), error);
+                }
+
+                rules.extend(parsed.into_iter().map(|rule| rule.into()));
+            }
+
+            self.replace_styles(&theme, THEME_FILES_ID, StyleSheet::new(rules));
+        }
+    }
+
+    /// Starts watching theme files for changes, on a background thread. When any are added,
+    /// changed or removed, themes are reloaded, and `on_reload` is called - from that thread, so
+    /// it'll generally want to dispatch a restyle over to the main thread. Calling this more than
+    /// once does nothing.
+    ///
+    /// Alchemy calls this for you in debug builds.
+    #[cfg(feature="parser")]
+    pub fn watch<F: Fn() + Send + 'static>(&'static self, on_reload: F) {
+        if self.watching.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // This is taken before spawning, so that nothing changed after this returns is missed.
+        let mut last_seen = self.theme_file_times();
+
+        thread::spawn(move || {
+            loop {
+                thread::sleep(WATCH_INTERVAL);
+
+                let seen = self.theme_file_times();
+                if seen != last_seen {
+                    last_seen = seen;
+                    self.load_themes();
+                    on_reload();
+                }
+            }
+        });
+    }
+
+    /// Finds the theme files in `dirs`, keyed by theme name, in the order they should be loaded.
+    #[cfg(feature="parser")]
+    fn theme_files(&self) -> BTreeMap<String, Vec<PathBuf>> {
+        let mut themes: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+
+        for dir in &self.dirs {
+            for path in read_dir_sorted(dir) {
+                let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+                    Some(name) => name.to_string(),
+                    None => { continue; }
+                };
+
+                if path.is_dir() {
+                    let files = read_dir_sorted(&path).into_iter().filter(|file| is_css_file(file));
+                    themes.entry(name).or_insert_with(Vec::new).extend(files);
+                } else if is_css_file(&path) {
+                    themes.entry(name).or_insert_with(Vec::new).push(path);
+                }
+            }
+        }
+
+        themes
+    }
+
+    /// Returns every theme file, along with when it was last modified, for spotting changes.
+    #[cfg(feature="parser")]
+    fn theme_file_times(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        self.theme_files().into_iter().flat_map(|(_, files)| files).map(|file| {
+            let modified = fs::metadata(&file).and_then(|metadata| metadata.modified()).ok();
+            (file, modified)
+        }).collect()
     }
 
     /// Registers a stylesheet (typically created by the `styles! {}` macro) for a given
-    /// theme.
+    /// theme. If the theme already has stylesheets, this one is layered on top of them - so
+    /// where they set the same property, the later registration wins, regardless of specificity.
     pub fn register_styles(&self, key: &str, stylesheet: StyleSheet) {
         let mut themes = self.themes.write().unwrap();
-        if !themes.contains_key(key) {
-            themes.insert(key.to_string(), stylesheet);
-            return;
+        let theme = themes.entry(key.to_string()).or_insert_with(Theme::default);
+        theme.merged.merge(stylesheet.clone());
+        theme.stylesheets.push((None, stylesheet));
+    }
+
+    /// Registers a stylesheet for a given theme under `id`. If there's already a stylesheet
+    /// registered with that id, it's replaced, keeping its place in the order of precedence;
+    /// otherwise, this behaves like `register_styles()`.
+    pub fn replace_styles(&self, key: &str, id: &str, stylesheet: StyleSheet) {
+        let mut themes = self.themes.write().unwrap();
+        let theme = themes.entry(key.to_string()).or_insert_with(Theme::default);
+
+        match theme.stylesheets.iter().position(|(existing, _)| existing.as_ref().map(String::as_str) == Some(id)) {
+            Some(index) => {
+                theme.stylesheets[index].1 = stylesheet;
+                theme.remerge();
+            },
+
+            None => {
+                theme.merged.merge(stylesheet.clone());
+                theme.stylesheets.push((Some(id.to_string()), stylesheet));
+            }
         }
+    }
+
+    /// Unregisters the stylesheet registered under `id` for a given theme, returning whether
+    /// there was one. A theme left without any stylesheets is removed entirely.
+    pub fn unregister_styles(&self, key: &str, id: &str) -> bool {
+        let mut themes = self.themes.write().unwrap();
+
+        let is_empty = match themes.get_mut(key) {
+            Some(theme) => {
+                let count = theme.stylesheets.len();
+                theme.stylesheets.retain(|(existing, _)| existing.as_ref().map(String::as_str) != Some(id));
+
+                if theme.stylesheets.len() == count {
+                    return false;
+                }
+
+                theme.remerge();
+                theme.stylesheets.is_empty()
+            },
+
+            None => { return false; }
+        };
+
+        if is_empty {
+            themes.remove(key);
+        }
+
+        true
+    }
+
+    /// Returns the theme that nodes are styled with, unless they've picked one themselves.
+    pub fn active_theme(&self) -> String {
+        self.active_theme.read().unwrap().clone()
+    }
 
-        // if let Some(existing_stylesheet) = self.themes.get_mut(key) {
-        //    *existing_stylesheet.merge(stylesheet);
-        //}
+    /// Sets the theme that nodes are styled with, unless they've picked one themselves. This
+    /// doesn't restyle anything by itself.
+    pub fn set_active_theme(&self, theme: &str) {
+        let mut active_theme = self.active_theme.write().unwrap();
+        *active_theme = theme.to_string();
     }
 
-    /// Given a theme key, style keys, and a style, configures the style for layout
-    /// and appearance.
-    pub fn configure_style_for_keys_in_theme(
+    /// Given a theme key, a path of nodes (from the root of the tree down to the node being
+    /// styled), the environment to evaluate `@media` rules against, and a style, configures the
+    /// style for layout and appearance.
+    ///
+    /// Themes other than `default` are layered on top of it: anything the theme doesn't set falls
+    /// back to `default`, and where they conflict, the theme wins.
+    pub fn configure_styles_for_path_in_theme(
         &self,
         theme: &str,
-        keys: &StylesList,
+        path: &[StyledNode],
+        media: &MediaEnvironment,
         style: &mut Style,
         appearance: &mut Appearance
     ) {
         let themes = self.themes.read().unwrap();
 
-        match themes.get(theme) {
-            Some(theme) => {
-                for key in &keys.0 {
-                    theme.apply_styles(key, style, appearance);
-                }
-            },
+        let mut stylesheets = vec![];
+        if let Some(default) = themes.get(DEFAULT_THEME) {
+            stylesheets.push(&default.merged);
+        }
 
-            None => {
-                eprintln!("No styles for theme!");
+        if theme != DEFAULT_THEME {
+            if let Some(theme) = themes.get(theme) {
+                stylesheets.push(&theme.merged);
             }
         }
+
+        if stylesheets.is_empty() {
+            eprintln!("No styles for theme!");
+            return;
+        }
+
+        apply_layered_styles(&stylesheets, path, media, style, appearance);
+        resolve_font_relative_lengths(style, appearance);
+    }
+
+    /// The same logic as `configure_styles_for_path_in_theme`, but uses the active theme.
+    pub fn configure_styles_for_path(
+        &self,
+        path: &[StyledNode],
+        media: &MediaEnvironment,
+        style: &mut Style,
+        appearance: &mut Appearance
+    ) {
+        let theme = self.active_theme();
+        self.configure_styles_for_path_in_theme(&theme, path, media, style, appearance)
     }
 
-    /// The same logic as `configure_style_for_keys_in_theme`, but defaults to the default theme.
-    pub fn configure_styles_for_keys(&self, keys: &StylesList, style: &mut Style, appearance: &mut Appearance) {
-        self.configure_style_for_keys_in_theme("default", keys, style, appearance)
+    /// Configures a style for a lone node with the given style keys, and no tag or ancestors -
+    /// e.g, a `Window`.
+    pub fn configure_styles_for_keys(
+        &self,
+        keys: &StylesList,
+        media: &MediaEnvironment,
+        style: &mut Style,
+        appearance: &mut Appearance
+    ) {
+        let node = StyledNode { tag: "", style_keys: keys, state: ElementState::default() };
+        self.configure_styles_for_path(&[node], media, style, appearance)
     }
 }
 
+/// Returns the entries in `dir`, sorted so that themes load in a predictable order. A missing
+/// or unreadable directory just has no entries.
+#[cfg(feature="parser")]
+fn read_dir_sorted(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(_) => vec![]
+    };
+
+    paths.sort();
+    paths
+}
+
+#[cfg(feature="parser")]
+fn is_css_file(path: &Path) -> bool {
+    path.is_file() && path.extension().map_or(false, |extension| extension == "css")
+}
+
 /// Utility method for reading a config file from the `CARGO_MANIFEST_DIR`. Hat tip to 
 /// [askama](https://github.com/djc/askama) for this!
 pub fn read_config_file() -> String {
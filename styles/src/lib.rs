@@ -13,7 +13,7 @@ pub mod color;
 pub use color::Color;
 
 mod engine;
-use engine::ThemeEngine;
+pub use engine::ThemeEngine;
 
 mod spacedlist;
 pub use spacedlist::SpacedList;
@@ -21,6 +21,11 @@ pub use spacedlist::SpacedList;
 mod spacedset;
 pub use spacedset::SpacedSet;
 
+pub mod media;
+pub use media::{ColorScheme, MediaEnvironment};
+
+pub mod selector;
+
 pub mod stretch;
 pub use stretch::result::Layout;
 
@@ -32,7 +37,7 @@ pub mod styles;
 pub use styles::{Appearance, Styles, Style};
 
 pub mod stylesheet;
-pub use stylesheet::StyleSheet;
+pub use stylesheet::{StyleRule, StyleSheet};
 
 #[cfg(feature="parser")]
 pub mod styles_parser;
@@ -0,0 +1,132 @@
+//! Implements media queries, which let rules in a `StyleSheet` apply conditionally - e.g, only
+//! when the window is wide enough, or when the system is in dark mode:
+//!
+//! ```css
+//! @media (min-width: 600px) and (orientation: landscape), (prefers-color-scheme: dark) {
+//!     sidebar { width: 200; }
+//! }
+//! ```
+//!
+//! Queries are evaluated against a `MediaEnvironment`, which Alchemy builds from the window being
+//! rendered and the current system appearance.
+
+#[cfg(feature="tokenize")]
+use proc_macro2::TokenStream;
+
+#[cfg(feature="tokenize")]
+use quote::{quote, ToTokens};
+
+/// The system's light or dark appearance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorScheme {
+    Light,
+    Dark
+}
+
+impl Default for ColorScheme {
+    fn default() -> ColorScheme {
+        ColorScheme::Light
+    }
+}
+
+/// Whether a window is taller than it is wide, or the other way around.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    Portrait,
+    Landscape
+}
+
+/// What media queries get evaluated against.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MediaEnvironment {
+    pub width: f32,
+    pub height: f32,
+    pub color_scheme: ColorScheme
+}
+
+impl MediaEnvironment {
+    /// Square windows count as portrait, as they do in CSS.
+    pub fn orientation(&self) -> Orientation {
+        match self.width > self.height {
+            true => Orientation::Landscape,
+            false => Orientation::Portrait
+        }
+    }
+}
+
+/// A single `(feature: value)` test in a media query. Sizes are in points.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MediaFeature {
+    MinWidth(f32),
+    MaxWidth(f32),
+    MinHeight(f32),
+    MaxHeight(f32),
+    Orientation(Orientation),
+    PrefersColorScheme(ColorScheme)
+}
+
+impl MediaFeature {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        match *self {
+            MediaFeature::MinWidth(width) => environment.width >= width,
+            MediaFeature::MaxWidth(width) => environment.width <= width,
+            MediaFeature::MinHeight(height) => environment.height >= height,
+            MediaFeature::MaxHeight(height) => environment.height <= height,
+            MediaFeature::Orientation(orientation) => environment.orientation() == orientation,
+            MediaFeature::PrefersColorScheme(scheme) => environment.color_scheme == scheme
+        }
+    }
+}
+
+/// A media query, e.g `(min-width: 600px) and (orientation: landscape)`. Matches when all of its
+/// features do.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaQuery(pub Vec<MediaFeature>);
+
+impl MediaQuery {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.0.iter().all(|feature| feature.matches(environment))
+    }
+}
+
+/// The comma-separated media queries from an `@media` rule. Matches when any of them do.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaQueryList(pub Vec<MediaQuery>);
+
+impl MediaQueryList {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.0.iter().any(|query| query.matches(environment))
+    }
+}
+
+/// Converts a `MediaFeature` into its tokenized representation, for use in the `styles! {}`
+/// macro.
+#[cfg(feature="tokenize")]
+impl ToTokens for MediaFeature {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match *self {
+            MediaFeature::MinWidth(width) => quote!(MediaFeature::MinWidth(#width)),
+            MediaFeature::MaxWidth(width) => quote!(MediaFeature::MaxWidth(#width)),
+            MediaFeature::MinHeight(height) => quote!(MediaFeature::MinHeight(#height)),
+            MediaFeature::MaxHeight(height) => quote!(MediaFeature::MaxHeight(#height)),
+            MediaFeature::Orientation(Orientation::Portrait) => quote!(MediaFeature::Orientation(Orientation::Portrait)),
+            MediaFeature::Orientation(Orientation::Landscape) => quote!(MediaFeature::Orientation(Orientation::Landscape)),
+            MediaFeature::PrefersColorScheme(ColorScheme::Light) => quote!(MediaFeature::PrefersColorScheme(ColorScheme::Light)),
+            MediaFeature::PrefersColorScheme(ColorScheme::Dark) => quote!(MediaFeature::PrefersColorScheme(ColorScheme::Dark))
+        });
+    }
+}
+
+/// Converts a `MediaQueryList` into its tokenized representation, for use in the `styles! {}`
+/// macro.
+#[cfg(feature="tokenize")]
+impl ToTokens for MediaQueryList {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let queries = self.0.iter().map(|query| {
+            let features = &query.0;
+            quote!(MediaQuery(vec![#(#features),*]))
+        });
+
+        tokens.extend(quote!(MediaQueryList(vec![#(#queries),*])));
+    }
+}
@@ -0,0 +1,225 @@
+//! Implements selectors, which decide which nodes a rule in a `StyleSheet` applies to.
+//!
+//! A selector is made up of compound selectors, joined by combinators:
+//!
+//! - `name` matches a node whose tag (e.g, `Text`) or one of whose style keys is `name`. Bare
+//!   names have always matched style keys in Alchemy, so this keeps existing stylesheets working.
+//! - `.name` matches a node with `name` in its style keys, and nothing else.
+//! - `:hover`, `:active`, `:focus` and `:disabled` match a node in that interaction state.
+//! - Compound selectors like `Text.title.large` require everything in them to match.
+//! - `a b` matches a `b` anywhere inside of an `a`, and `a > b` a `b` directly inside an `a`.
+//!
+//! When more than one rule matches a node, they're applied in order of specificity (style keys
+//! and pseudo-classes outweigh names), and then in the order they appear in the stylesheet.
+
+#[cfg(feature="tokenize")]
+use proc_macro2::TokenStream;
+
+#[cfg(feature="tokenize")]
+use quote::{quote, ToTokens};
+
+use crate::StylesList;
+
+/// Interaction states that a node can be in, which pseudo-classes match against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PseudoClass {
+    Hover,
+    Active,
+    Focus,
+    Disabled
+}
+
+impl PseudoClass {
+    /// Returns the `PseudoClass` for a name as it appears in CSS (e.g, `hover`), if any.
+    pub fn from_name(name: &str) -> Option<PseudoClass> {
+        match name {
+            "hover" => Some(PseudoClass::Hover),
+            "active" => Some(PseudoClass::Active),
+            "focus" => Some(PseudoClass::Focus),
+            "disabled" => Some(PseudoClass::Disabled),
+            _ => None
+        }
+    }
+}
+
+/// The interaction state of a node, with a flag for each `PseudoClass`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ElementState {
+    pub hover: bool,
+    pub active: bool,
+    pub focus: bool,
+    pub disabled: bool
+}
+
+impl ElementState {
+    pub fn has(&self, pseudo_class: PseudoClass) -> bool {
+        match pseudo_class {
+            PseudoClass::Hover => self.hover,
+            PseudoClass::Active => self.active,
+            PseudoClass::Focus => self.focus,
+            PseudoClass::Disabled => self.disabled
+        }
+    }
+
+    /// Sets the flag for `pseudo_class`, returning whether it actually changed.
+    pub fn set(&mut self, pseudo_class: PseudoClass, enabled: bool) -> bool {
+        let flag = match pseudo_class {
+            PseudoClass::Hover => &mut self.hover,
+            PseudoClass::Active => &mut self.active,
+            PseudoClass::Focus => &mut self.focus,
+            PseudoClass::Disabled => &mut self.disabled
+        };
+
+        let changed = *flag != enabled;
+        *flag = enabled;
+        changed
+    }
+}
+
+/// A node that selectors can be matched against. The `RenderEngine` hands the `ThemeEngine` a
+/// path of these, from the root of the tree down to the node being styled.
+#[derive(Clone, Copy, Debug)]
+pub struct StyledNode<'a> {
+    pub tag: &'a str,
+    pub style_keys: &'a StylesList,
+    pub state: ElementState
+}
+
+impl<'a> StyledNode<'a> {
+    fn has_style_key(&self, key: &str) -> bool {
+        self.style_keys.0.iter().any(|style_key| &**style_key == key)
+    }
+}
+
+/// How a compound selector relates to the one after it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Combinator {
+    /// `a b`
+    Descendant,
+
+    /// `a > b`
+    Child
+}
+
+/// A run of simple selectors (e.g, `Text.title`), all of which have to match a given node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompoundSelector {
+    /// Bare names, which match either the tag or a style key.
+    pub names: Vec<String>,
+
+    /// `.`-prefixed names, which only match style keys.
+    pub keys: Vec<String>,
+
+    /// `:`-prefixed interaction states, e.g `:hover`.
+    pub pseudo_classes: Vec<PseudoClass>
+}
+
+impl CompoundSelector {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.keys.is_empty() && self.pseudo_classes.is_empty()
+    }
+
+    pub fn matches(&self, node: &StyledNode) -> bool {
+        self.names.iter().all(|name| node.tag == name || node.has_style_key(name)) &&
+            self.keys.iter().all(|key| node.has_style_key(key)) &&
+            self.pseudo_classes.iter().all(|pseudo_class| node.state.has(*pseudo_class))
+    }
+}
+
+/// How specific a selector is, used for ordering matching rules. Compares style keys and
+/// pseudo-classes first, then names.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity(pub usize, pub usize);
+
+/// A full selector, e.g `.sidebar > Text.title`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Selector {
+    /// The compound selector for the node actually being styled (the right-most one).
+    pub subject: CompoundSelector,
+
+    /// The rest of the compound selectors, nearest first, each along with how it relates to the
+    /// compound selector after it.
+    pub ancestors: Vec<(Combinator, CompoundSelector)>
+}
+
+impl Selector {
+    /// Whether this selector matches the last node in `path`, the rest of which should be its
+    /// ancestors, ordered from the root down.
+    pub fn matches(&self, path: &[StyledNode]) -> bool {
+        match path.split_last() {
+            Some((node, ancestors)) => {
+                self.subject.matches(node) && matches_ancestors(&self.ancestors, ancestors)
+            },
+
+            None => false
+        }
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        let compounds = Some(&self.subject).into_iter().chain(self.ancestors.iter().map(|(_, c)| c));
+
+        compounds.fold(Specificity::default(), |specificity, compound| {
+            let keys = compound.keys.len() + compound.pseudo_classes.len();
+            Specificity(specificity.0 + keys, specificity.1 + compound.names.len())
+        })
+    }
+}
+
+/// Handles matching the ancestor parts of a `Selector`, backtracking where a descendant
+/// combinator could match more than one ancestor.
+fn matches_ancestors(selectors: &[(Combinator, CompoundSelector)], path: &[StyledNode]) -> bool {
+    let ((combinator, compound), remaining) = match selectors.split_first() {
+        Some(selector) => selector,
+        None => { return true; }
+    };
+
+    match combinator {
+        Combinator::Child => match path.split_last() {
+            Some((node, rest)) => compound.matches(node) && matches_ancestors(remaining, rest),
+            None => false
+        },
+
+        Combinator::Descendant => (0..path.len()).rev().any(|i| {
+            compound.matches(&path[i]) && matches_ancestors(remaining, &path[..i])
+        })
+    }
+}
+
+/// Converts a `CompoundSelector` into its tokenized representation, for use in the `styles! {}`
+/// macro.
+#[cfg(feature="tokenize")]
+impl ToTokens for CompoundSelector {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let names = &self.names;
+        let keys = &self.keys;
+        let pseudo_classes = self.pseudo_classes.iter().map(|pseudo_class| match pseudo_class {
+            PseudoClass::Hover => quote!(PseudoClass::Hover),
+            PseudoClass::Active => quote!(PseudoClass::Active),
+            PseudoClass::Focus => quote!(PseudoClass::Focus),
+            PseudoClass::Disabled => quote!(PseudoClass::Disabled)
+        });
+
+        tokens.extend(quote!(CompoundSelector {
+            names: vec![#(String::from(#names)),*],
+            keys: vec![#(String::from(#keys)),*],
+            pseudo_classes: vec![#(#pseudo_classes),*]
+        }));
+    }
+}
+
+/// Converts a `Selector` into its tokenized representation, for use in the `styles! {}` macro.
+#[cfg(feature="tokenize")]
+impl ToTokens for Selector {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let subject = &self.subject;
+        let ancestors = self.ancestors.iter().map(|(combinator, compound)| match combinator {
+            Combinator::Descendant => quote!((Combinator::Descendant, #compound)),
+            Combinator::Child => quote!((Combinator::Child, #compound))
+        });
+
+        tokens.extend(quote!(Selector {
+            subject: #subject,
+            ancestors: vec![#(#ancestors),*]
+        }));
+    }
+}
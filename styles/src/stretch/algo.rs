@@ -55,6 +55,14 @@ struct FlexLine {
 
 impl Stretch {
     pub(crate) fn compute(&mut self, root: Node, size: Size<Number>) -> Result<(), Box<Any>> {
+        // Anything sized in viewport units needs redoing if the viewport's changed, which the
+        // layout cache has no way of knowing about.
+        if size != self.viewport {
+            self.viewport = size;
+            Self::clear_layout_cache(&mut self.layout_cache, &self.children, root);
+        }
+
+        let viewport = self.viewport;
         let style = self.style[&root];
         let has_root_min_max = style.min_size.width.is_defined()
             || style.min_size.height.is_defined()
@@ -64,7 +72,7 @@ impl Stretch {
         let result = if has_root_min_max {
             let first_pass = self.compute_internal(
                 root,
-                Size { width: style.size.width.resolve(size.width), height: style.size.height.resolve(size.height) },
+                Size { width: style.size.width.resolve(size.width, viewport), height: style.size.height.resolve(size.height, viewport) },
                 size,
                 false,
             )?;
@@ -75,14 +83,14 @@ impl Stretch {
                     width: first_pass
                         .size
                         .width
-                        .maybe_max(style.min_size.width.resolve(size.width))
-                        .maybe_min(style.max_size.width.resolve(size.width))
+                        .maybe_max(style.min_size.width.resolve(size.width, viewport))
+                        .maybe_min(style.max_size.width.resolve(size.width, viewport))
                         .to_number(),
                     height: first_pass
                         .size
                         .height
-                        .maybe_max(style.min_size.height.resolve(size.height))
-                        .maybe_min(style.max_size.height.resolve(size.height))
+                        .maybe_max(style.min_size.height.resolve(size.height, viewport))
+                        .maybe_min(style.max_size.height.resolve(size.height, viewport))
                         .to_number(),
                 },
                 size,
@@ -91,7 +99,7 @@ impl Stretch {
         } else {
             self.compute_internal(
                 root,
-                Size { width: style.size.width.resolve(size.width), height: style.size.height.resolve(size.height) },
+                Size { width: style.size.width.resolve(size.width, viewport), height: style.size.height.resolve(size.height, viewport) },
                 size,
                 true,
             )?
@@ -107,6 +115,35 @@ impl Stretch {
         Ok(())
     }
 
+    /// Lays out everything under `node` again, against the size it was given the last time
+    /// layout was computed. Its own size and location are left as they were.
+    pub(crate) fn compute_in_place(&mut self, node: Node) -> Result<(), Box<Any>> {
+        let layout = self.layout[&node];
+        let parent = self.parents[&node].first().cloned();
+        let parent_size = match parent {
+            Some(parent) => self.layout[&parent].size.map(Defined),
+            None => Size::undefined()
+        };
+
+        self.compute_internal(node, layout.size.map(Defined), parent_size, true)?;
+        *self.layout.get_mut(node).unwrap() = layout;
+
+        // Rounding works off of absolute positions, so that things line up across the tree.
+        let mut location = layout.location;
+        let mut current = parent;
+        while let Some(ancestor) = current {
+            location.x += self.layout[&ancestor].location.x;
+            location.y += self.layout[&ancestor].location.y;
+            current = self.parents[&ancestor].first().cloned();
+        }
+
+        for child in &self.children[&node] {
+            Self::round_layout(&mut self.layout, &self.children, *child, location.x, location.y);
+        }
+
+        Ok(())
+    }
+
     fn round_layout(
         layouts: &mut Storage<result::Layout>,
         children: &Storage<Vec<Node>>,
@@ -127,6 +164,16 @@ impl Stretch {
         }
     }
 
+    fn clear_layout_cache(layout_cache: &mut Storage<Option<result::Cache>>, children: &Storage<Vec<Node>>, root: Node) {
+        if let Ok(cache) = layout_cache.get_mut(root) {
+            *cache = None;
+        }
+
+        for child in &children[&root] {
+            Self::clear_layout_cache(layout_cache, children, *child);
+        }
+    }
+
     fn compute_internal(
         &mut self,
         node: Node,
@@ -134,6 +181,7 @@ impl Stretch {
         parent_size: Size<Number>,
         perform_layout: bool,
     ) -> Result<ComputeResult, Box<Any>> {
+        let viewport = self.viewport;
         *self.is_dirty.get_mut(node).unwrap() = false;
 
         // First we check if we have a result for the given input
@@ -169,9 +217,9 @@ impl Stretch {
         let is_column = dir.is_column();
         let is_wrap_reverse = self.style[&node].flex_wrap == FlexWrap::WrapReverse;
 
-        let margin = self.style[&node].margin.map(|n| n.resolve(parent_size.width).or_else(0.0));
-        let padding = self.style[&node].padding.map(|n| n.resolve(parent_size.width).or_else(0.0));
-        let border = self.style[&node].border.map(|n| n.resolve(parent_size.width).or_else(0.0));
+        let margin = self.style[&node].margin.map(|n| n.resolve(parent_size.width, viewport).or_else(0.0));
+        let padding = self.style[&node].padding.map(|n| n.resolve(parent_size.width, viewport).or_else(0.0));
+        let border = self.style[&node].border.map(|n| n.resolve(parent_size.width, viewport).or_else(0.0));
 
         let padding_border = Rect {
             start: padding.start + border.start,
@@ -235,24 +283,24 @@ impl Stretch {
                 node: *child,
 
                 size: Size {
-                    width: child_style.size.width.resolve(node_inner_size.width),
-                    height: child_style.size.height.resolve(node_inner_size.height),
+                    width: child_style.size.width.resolve(node_inner_size.width, viewport),
+                    height: child_style.size.height.resolve(node_inner_size.height, viewport),
                 },
 
                 min_size: Size {
-                    width: child_style.min_size.width.resolve(node_inner_size.width),
-                    height: child_style.min_size.height.resolve(node_inner_size.height),
+                    width: child_style.min_size.width.resolve(node_inner_size.width, viewport),
+                    height: child_style.min_size.height.resolve(node_inner_size.height, viewport),
                 },
 
                 max_size: Size {
-                    width: child_style.max_size.width.resolve(node_inner_size.width),
-                    height: child_style.max_size.height.resolve(node_inner_size.height),
+                    width: child_style.max_size.width.resolve(node_inner_size.width, viewport),
+                    height: child_style.max_size.height.resolve(node_inner_size.height, viewport),
                 },
 
-                position: child_style.position.map(|p| p.resolve(node_inner_size.width)),
-                margin: child_style.margin.map(|m| m.resolve(node_inner_size.width).or_else(0.0)),
-                padding: child_style.padding.map(|p| p.resolve(node_inner_size.width).or_else(0.0)),
-                border: child_style.border.map(|b| b.resolve(node_inner_size.width).or_else(0.0)),
+                position: child_style.position.map(|p| p.resolve(node_inner_size.width, viewport)),
+                margin: child_style.margin.map(|m| m.resolve(node_inner_size.width, viewport).or_else(0.0)),
+                padding: child_style.padding.map(|p| p.resolve(node_inner_size.width, viewport).or_else(0.0)),
+                border: child_style.border.map(|b| b.resolve(node_inner_size.width, viewport).or_else(0.0)),
 
                 flex_basis: 0.0,
                 inner_flex_basis: 0.0,
@@ -282,7 +330,7 @@ impl Stretch {
 
             // A. If the item has a definite used flex basis, that’s the flex base size.
 
-            let flex_basis = child_style.flex_basis.resolve(node_inner_size.main(dir));
+            let flex_basis = child_style.flex_basis.resolve(node_inner_size.main(dir), viewport);
             if flex_basis.is_defined() {
                 child.flex_basis = flex_basis.or_else(0.0);
                 return Ok(());
@@ -1226,14 +1274,14 @@ impl Stretch {
 
                 let child_style = self.style[&child];
 
-                let start = child_style.position.start.resolve(container_width)
-                    + child_style.margin.start.resolve(container_width);
+                let start = child_style.position.start.resolve(container_width, viewport)
+                    + child_style.margin.start.resolve(container_width, viewport);
                 let end =
-                    child_style.position.end.resolve(container_width) + child_style.margin.end.resolve(container_width);
-                let top = child_style.position.top.resolve(container_height)
-                    + child_style.margin.top.resolve(container_height);
-                let bottom = child_style.position.bottom.resolve(container_height)
-                    + child_style.margin.bottom.resolve(container_height);
+                    child_style.position.end.resolve(container_width, viewport) + child_style.margin.end.resolve(container_width, viewport);
+                let top = child_style.position.top.resolve(container_height, viewport)
+                    + child_style.margin.top.resolve(container_height, viewport);
+                let bottom = child_style.position.bottom.resolve(container_height, viewport)
+                    + child_style.margin.bottom.resolve(container_height, viewport);
 
                 let (start_main, end_main) = if is_row { (start, end) } else { (top, bottom) };
                 let (start_cross, end_cross) = if is_row { (top, bottom) } else { (start, end) };
@@ -1241,9 +1289,9 @@ impl Stretch {
                 let width = child_style
                     .size
                     .width
-                    .resolve(container_width)
-                    .maybe_max(child_style.min_size.width.resolve(container_width))
-                    .maybe_min(child_style.max_size.width.resolve(container_width))
+                    .resolve(container_width, viewport)
+                    .maybe_max(child_style.min_size.width.resolve(container_width, viewport))
+                    .maybe_min(child_style.max_size.width.resolve(container_width, viewport))
                     .or_else(if start.is_defined() && end.is_defined() {
                         container_width - start - end
                     } else {
@@ -1253,9 +1301,9 @@ impl Stretch {
                 let height = child_style
                     .size
                     .height
-                    .resolve(container_height)
-                    .maybe_max(child_style.min_size.height.resolve(container_height))
-                    .maybe_min(child_style.max_size.height.resolve(container_height))
+                    .resolve(container_height, viewport)
+                    .maybe_max(child_style.min_size.height.resolve(container_height, viewport))
+                    .maybe_min(child_style.max_size.height.resolve(container_height, viewport))
                     .or_else(if top.is_defined() && bottom.is_defined() {
                         container_height - top - bottom
                     } else {
@@ -1273,15 +1321,15 @@ impl Stretch {
                     - result
                         .size
                         .main(dir)
-                        .maybe_max(child_style.min_main_size(dir).resolve(node_inner_size.main(dir)))
-                        .maybe_min(child_style.max_main_size(dir).resolve(node_inner_size.main(dir)));
+                        .maybe_max(child_style.min_main_size(dir).resolve(node_inner_size.main(dir), viewport))
+                        .maybe_min(child_style.max_main_size(dir).resolve(node_inner_size.main(dir), viewport));
 
                 let free_cross_space = container_size.cross(dir)
                     - result
                         .size
                         .cross(dir)
-                        .maybe_max(child_style.min_cross_size(dir).resolve(node_inner_size.cross(dir)))
-                        .maybe_min(child_style.max_cross_size(dir).resolve(node_inner_size.cross(dir)));
+                        .maybe_max(child_style.min_cross_size(dir).resolve(node_inner_size.cross(dir), viewport))
+                        .maybe_min(child_style.max_cross_size(dir).resolve(node_inner_size.cross(dir), viewport));
 
                 let offset_main = if start_main.is_defined() {
                     start_main.or_else(0.0) + border.main_start(dir)
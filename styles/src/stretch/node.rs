@@ -53,6 +53,10 @@ impl<T> Storage<T> {
     pub fn insert(&mut self, node: Node, value: T) -> Option<T> {
         self.0.insert(node, value)
     }
+
+    pub fn remove(&mut self, node: Node) -> Option<T> {
+        self.0.remove(&node)
+    }
 }
 
 impl<T> std::ops::Index<&Node> for Storage<T> {
@@ -73,6 +77,9 @@ pub struct Stretch {
     pub(crate) layout: Storage<Layout>,
     pub(crate) layout_cache: Storage<Option<Cache>>,
     pub(crate) is_dirty: Storage<bool>,
+
+    /// The size layout was last computed against, which viewport units are relative to.
+    pub(crate) viewport: Size<Number>,
 }
 
 impl Stretch {
@@ -87,6 +94,7 @@ impl Stretch {
             layout: Storage::new(),
             layout_cache: Storage::new(),
             is_dirty: Storage::new(),
+            viewport: Size::undefined(),
         }
     }
 
@@ -127,6 +135,30 @@ impl Stretch {
         Ok(node)
     }
 
+    /// Removes `node` entirely, detaching it from any parents and children first. The children
+    /// themselves are left alone; they're simply orphaned.
+    pub fn remove(&mut self, node: Node) -> Result<(), Error> {
+        for parent in self.parents.get(node)?.clone() {
+            self.children.get_mut(parent)?.retain(|n| *n != node);
+            self.mark_dirty(parent)?;
+        }
+
+        for child in self.children.get(node)?.clone() {
+            self.parents.get_mut(child)?.retain(|p| *p != node);
+        }
+
+        self.style.remove(node);
+        self.parents.remove(node);
+        self.children.remove(node);
+        self.measure.remove(node);
+        self.layout.remove(node);
+        self.layout_cache.remove(node);
+        self.is_dirty.remove(node);
+        self.nodes.free(&[node.local]);
+
+        Ok(())
+    }
+
     pub fn set_measure(&mut self, node: Node, measure: Option<MeasureFunc>) -> Result<(), Error> {
         *self.measure.get_mut(node)? = measure;
         self.mark_dirty(node)?;
@@ -156,6 +188,12 @@ impl Stretch {
         self.mark_dirty(node)
     }
 
+    pub fn insert_child_at_index(&mut self, node: Node, index: usize, child: Node) -> Result<(), Error> {
+        self.parents.get_mut(child)?.push(node);
+        self.children.get_mut(node)?.insert(index, child);
+        self.mark_dirty(node)
+    }
+
     pub fn remove_child(&mut self, node: Node, child: Node) -> Result<Node, Error> {
         match self.children(node)?.iter().position(|n| *n == child) {
             Some(index) => self.remove_child_at_index(node, index),
@@ -233,6 +271,16 @@ impl Stretch {
             _ => Err(Error::InvalidNode(node)),
         }
     }
+
+    /// Recomputes layout for everything under `node`, keeping the size and location it ended up
+    /// with the last time layout was computed. This is only correct if nothing outside of the
+    /// subtree has changed since, and `node`'s size doesn't depend on what's inside of it.
+    pub fn compute_layout_in_place(&mut self, node: Node) -> Result<(), Error> {
+        match self.layout.get(node) {
+            Ok(_) => self.compute_in_place(node).map_err(|err| Error::Measure(err)),
+            _ => Err(Error::InvalidNode(node)),
+        }
+    }
 }
 
 impl Drop for Stretch {
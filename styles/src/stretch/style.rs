@@ -164,6 +164,20 @@ pub enum Dimension {
     Auto,
     Points(f32),
     Percent(f32),
+
+    /// Relative to the font size. These are resolved into `Points` when styles are applied,
+    /// and are treated as `Undefined` should one make it through to layout.
+    Em(f32),
+
+    /// Relative to the root font size. As with `Em`, these are resolved into `Points` when
+    /// styles are applied.
+    Rem(f32),
+
+    /// A fraction of the viewport (i.e, the size layout is computed against) width.
+    ViewportWidth(f32),
+
+    /// A fraction of the viewport height.
+    ViewportHeight(f32),
 }
 
 impl Default for Dimension {
@@ -173,10 +187,12 @@ impl Default for Dimension {
 }
 
 impl Dimension {
-    pub(crate) fn resolve(self, parent_width: Number) -> Number {
+    pub(crate) fn resolve(self, parent_width: Number, viewport: Size<Number>) -> Number {
         match self {
             Dimension::Points(points) => Number::Defined(points),
             Dimension::Percent(percent) => parent_width * percent,
+            Dimension::ViewportWidth(fraction) => viewport.width * fraction,
+            Dimension::ViewportHeight(fraction) => viewport.height * fraction,
             _ => Number::Undefined,
         }
     }
@@ -185,6 +201,8 @@ impl Dimension {
         match self {
             Dimension::Points(_) => true,
             Dimension::Percent(_) => true,
+            Dimension::ViewportWidth(_) => true,
+            Dimension::ViewportHeight(_) => true,
             _ => false,
         }
     }
@@ -202,7 +220,7 @@ impl Default for Size<Dimension> {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Style {
     pub display: Display,
     pub position_type: PositionType,
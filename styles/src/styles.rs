@@ -9,6 +9,10 @@ use quote::{quote, ToTokens};
 
 pub use crate::color::Color;
 
+pub use crate::media::{ColorScheme, MediaFeature, MediaQuery, MediaQueryList, Orientation};
+pub use crate::selector::{Combinator, CompoundSelector, PseudoClass, Selector};
+pub use crate::stylesheet::StyleRule;
+
 pub use crate::stretch::geometry::{Point, Rect, Size};
 pub use crate::stretch::number::Number;
 pub use crate::stretch::result::Layout;
@@ -101,14 +105,88 @@ impl Default for FontFamily {
     }
 }
 
+/// Radii for each corner of a border.
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+pub struct CornerRadii {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32
+}
+
+/// The properties that are inherited - that is, a node starts out with the values its parent
+/// computed for them, rather than the initial ones. These are also what the `inherit` and
+/// `initial` keywords can be used with.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum InheritedProperty {
+    Color,
+    FontSize,
+    FontStyle,
+    FontWeight,
+    LineHeight,
+    TextAlignment
+}
+
+impl InheritedProperty {
+    /// Every inherited property, for walking over.
+    pub const ALL: [InheritedProperty; 6] = [
+        InheritedProperty::Color,
+        InheritedProperty::FontSize,
+        InheritedProperty::FontStyle,
+        InheritedProperty::FontWeight,
+        InheritedProperty::LineHeight,
+        InheritedProperty::TextAlignment
+    ];
+
+    /// Looks up an inherited property by its CSS name.
+    pub fn from_name(name: &str) -> Option<InheritedProperty> {
+        match name {
+            "color" => Some(InheritedProperty::Color),
+            "font-size" => Some(InheritedProperty::FontSize),
+            "font-style" => Some(InheritedProperty::FontStyle),
+            "font-weight" => Some(InheritedProperty::FontWeight),
+            "line-height" => Some(InheritedProperty::LineHeight),
+            "text-align" => Some(InheritedProperty::TextAlignment),
+            _ => None
+        }
+    }
+
+    /// Copies this property's value from one `Appearance` to another.
+    pub fn copy(&self, from: &Appearance, to: &mut Appearance) {
+        match self {
+            InheritedProperty::Color => { to.text_color = from.text_color; },
+            InheritedProperty::FontSize => { to.font_size = from.font_size; },
+            InheritedProperty::FontStyle => { to.font_style = from.font_style; },
+            InheritedProperty::FontWeight => { to.font_weight = from.font_weight; },
+            InheritedProperty::LineHeight => { to.line_height = from.line_height; },
+            InheritedProperty::TextAlignment => { to.text_alignment = from.text_alignment; }
+        }
+    }
+}
+
+/// The font size an `Appearance` starts out with, which `rem` lengths are relative to.
+pub const ROOT_FONT_SIZE: f32 = 14.;
+
 /// When applying layout to a backing view, you'll get two calls - one with a `Layout`, 
 /// which contains the computed frame, and one with an `Appearance`, which contains things 
 /// like colors, fonts, and so on.
+#[derive(Clone)]
 pub struct Appearance {
     pub background_color: Color,
+
+    /// Border widths, per edge. These also factor into layout, so are just here for drawing.
+    pub border_width: Rect<f32>,
+    pub border_color: Rect<Color>,
+    pub border_style: Rect<BorderStyle>,
+    pub border_radius: CornerRadii,
+
     pub font_size: f32,
     pub font_style: FontStyle,
     pub font_weight: FontWeight,
+
+    /// A multiple of the font size; `None` leaves it up to the platform.
+    pub line_height: Option<f32>,
+
     pub opacity: f32,
     pub text_alignment: TextAlignment,
     pub text_color: Color,
@@ -121,10 +199,25 @@ impl Default for Appearance {
     fn default() -> Appearance {
         Appearance {
             background_color: Color::transparent(),
+            border_width: Rect { start: 0., end: 0., top: 0., bottom: 0. },
+            border_color: Rect {
+                start: Color::transparent(),
+                end: Color::transparent(),
+                top: Color::transparent(),
+                bottom: Color::transparent()
+            },
+            border_style: Rect {
+                start: BorderStyle::default(),
+                end: BorderStyle::default(),
+                top: BorderStyle::default(),
+                bottom: BorderStyle::default()
+            },
+            border_radius: CornerRadii::default(),
             // @TODO: We can definitely judge a default value better here. 
-            font_size: 14.,
+            font_size: ROOT_FONT_SIZE,
             font_style: FontStyle::default(),
             font_weight: FontWeight::default(),
+            line_height: None,
             opacity: 1.,
             text_alignment: TextAlignment::default(),
             text_color: Color::transparent(),
@@ -135,12 +228,22 @@ impl Default for Appearance {
     }
 }
 
+impl Appearance {
+    /// Takes the values of inherited properties (`color`, fonts, and so on) from `parent`, as a
+    /// node does before any of its own styles are applied.
+    pub fn inherit_from(&mut self, parent: &Appearance) {
+        for property in &InheritedProperty::ALL {
+            property.copy(parent, self);
+        }
+    }
+}
+
 /// These exist purely for use in the parser code.
 ///
 /// A `Style` is what's used for a node; `Styles` are what's parsed and stored.
 /// At render-time, the rendering engine takes n styles and reduces them down into 1 `Style`
 /// that's applied to the node in question.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Styles {
     AlignContent(AlignContent),
     AlignItems(AlignItems),
@@ -183,51 +286,69 @@ pub enum Styles {
     BorderTopEndRadius(f32),
     BorderTopStartRadius(f32),
     
-    Bottom(f32),
+    Bottom(Dimension),
+
+    /// A custom property (e.g, `--accent: #307ace`), which `var()` references pick up from the
+    /// node it's set on and anything underneath. Holds the name (with its leading `--`), and the
+    /// value as written.
+    CustomProperty(String, String),
+
     Direction(Direction),
     Display(Display),
-    End(f32),
-    FlexBasis(f32),
+    End(Dimension),
+    FlexBasis(Dimension),
     FlexDirection(FlexDirection),
     FlexGrow(f32),
     FlexShrink(f32),
     FlexWrap(FlexWrap),
     FontFamily(FontFamily),
     FontLineHeight(f32),
-    FontSize(f32),
+    FontSize(Dimension),
     FontStyle(FontStyle),
     FontWeight(FontWeight),
-    Height(f32),
+    Height(Dimension),
+
+    /// `inherit`: takes the parent's value for a property.
+    Inherit(InheritedProperty),
+
+    /// `initial`: resets a property to the value it'd have with no styles at all.
+    Initial(InheritedProperty),
+
     JustifyContent(JustifyContent),
-    Left(f32),
-    MarginBottom(f32),
-    MarginEnd(f32),
-    MarginLeft(f32),
-    MarginRight(f32),
-    MarginStart(f32),
-    MarginTop(f32),
-    MaxHeight(f32),
-    MaxWidth(f32),
-    MinHeight(f32),
-    MinWidth(f32),
+    Left(Dimension),
+    MarginBottom(Dimension),
+    MarginEnd(Dimension),
+    MarginLeft(Dimension),
+    MarginRight(Dimension),
+    MarginStart(Dimension),
+    MarginTop(Dimension),
+    MaxHeight(Dimension),
+    MaxWidth(Dimension),
+    MinHeight(Dimension),
+    MinWidth(Dimension),
     Opacity(f32),
     Overflow(Overflow),
-    PaddingBottom(f32),
-    PaddingEnd(f32),
-    PaddingLeft(f32),
-    PaddingRight(f32),
-    PaddingStart(f32),
-    PaddingTop(f32),
+    PaddingBottom(Dimension),
+    PaddingEnd(Dimension),
+    PaddingLeft(Dimension),
+    PaddingRight(Dimension),
+    PaddingStart(Dimension),
+    PaddingTop(Dimension),
+
+    /// A declaration whose value uses `var()`, and so can't be parsed until custom properties are
+    /// known for the node it's applied to. Holds the property name, and the value as written.
+    PendingSubstitution(String, String),
+
     PositionType(PositionType),
-    Right(f32),
-    Start(f32),
+    Right(Dimension),
+    Start(Dimension),
     TextAlignment(TextAlignment),
     TextColor(Color),
     TextDecorationColor(Color),
     TextShadowColor(Color),
     TintColor(Color),
-    Top(f32),
-    Width(f32)
+    Top(Dimension),
+    Width(Dimension)
 }
 
 /// A method for tokenizing a `Color` for a given attribute (e.g, `BackgroundColor`).
@@ -247,6 +368,50 @@ fn color_tokens(tokens: &mut TokenStream, color: &Color, style: &str) {
     })));
 }
 
+/// A method for tokenizing a `BorderStyle` for a given attribute (e.g, `BorderTopStyle`).
+#[cfg(feature="tokenize")]
+fn border_style_tokens(tokens: &mut TokenStream, style: &BorderStyle, attribute: &str) {
+    let s = Ident::new(attribute, Span::call_site());
+
+    tokens.extend(match style {
+        BorderStyle::None => quote!(Styles::#s(BorderStyle::None)),
+        BorderStyle::Hidden => quote!(Styles::#s(BorderStyle::Hidden)),
+        BorderStyle::Solid => quote!(Styles::#s(BorderStyle::Solid))
+    });
+}
+
+/// A method for tokenizing a `Dimension` for a given attribute (e.g, `Width`).
+#[cfg(feature="tokenize")]
+fn dimension_tokens(tokens: &mut TokenStream, dimension: &Dimension, style: &str) {
+    let s = Ident::new(style, Span::call_site());
+
+    tokens.extend(match dimension {
+        Dimension::Undefined => quote!(Styles::#s(Dimension::Undefined)),
+        Dimension::Auto => quote!(Styles::#s(Dimension::Auto)),
+        Dimension::Points(value) => quote!(Styles::#s(Dimension::Points(#value))),
+        Dimension::Percent(value) => quote!(Styles::#s(Dimension::Percent(#value))),
+        Dimension::Em(value) => quote!(Styles::#s(Dimension::Em(#value))),
+        Dimension::Rem(value) => quote!(Styles::#s(Dimension::Rem(#value))),
+        Dimension::ViewportWidth(value) => quote!(Styles::#s(Dimension::ViewportWidth(#value))),
+        Dimension::ViewportHeight(value) => quote!(Styles::#s(Dimension::ViewportHeight(#value)))
+    });
+}
+
+/// Converts an `InheritedProperty` into tokens, for `Styles::Inherit` and `Styles::Initial`.
+#[cfg(feature="tokenize")]
+impl ToTokens for InheritedProperty {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            InheritedProperty::Color => quote!(InheritedProperty::Color),
+            InheritedProperty::FontSize => quote!(InheritedProperty::FontSize),
+            InheritedProperty::FontStyle => quote!(InheritedProperty::FontStyle),
+            InheritedProperty::FontWeight => quote!(InheritedProperty::FontWeight),
+            InheritedProperty::LineHeight => quote!(InheritedProperty::LineHeight),
+            InheritedProperty::TextAlignment => quote!(InheritedProperty::TextAlignment)
+        });
+    }
+}
+
 /// Converts `Styles` into tokenized `Styles` representations, for use in the `styles! {}` macro.
 #[cfg(feature="tokenize")]
 impl ToTokens for Styles {
@@ -292,13 +457,13 @@ impl ToTokens for Styles {
         Styles::BorderRightColor(color) => color_tokens(tokens, color, "BorderRightColor"),
         Styles::BorderTopColor(color) => color_tokens(tokens, color, "BorderTopColor"),
         Styles::BorderStartColor(color) => color_tokens(tokens, color, "BorderStartColor"),
-        Styles::BorderStyle(_) => {},
-        Styles::BorderEndStyle(_) => {},
-        Styles::BorderBottomStyle(_) => {},
-        Styles::BorderLeftStyle(_) => {},
-        Styles::BorderRightStyle(_) => {},
-        Styles::BorderTopStyle(_) => {},
-        Styles::BorderStartStyle(_) => {},
+        Styles::BorderStyle(style) => border_style_tokens(tokens, style, "BorderStyle"),
+        Styles::BorderEndStyle(style) => border_style_tokens(tokens, style, "BorderEndStyle"),
+        Styles::BorderBottomStyle(style) => border_style_tokens(tokens, style, "BorderBottomStyle"),
+        Styles::BorderLeftStyle(style) => border_style_tokens(tokens, style, "BorderLeftStyle"),
+        Styles::BorderRightStyle(style) => border_style_tokens(tokens, style, "BorderRightStyle"),
+        Styles::BorderTopStyle(style) => border_style_tokens(tokens, style, "BorderTopStyle"),
+        Styles::BorderStartStyle(style) => border_style_tokens(tokens, style, "BorderStartStyle"),
         Styles::BorderWidth(border_width) => tokens.extend(quote!(Styles::BorderWidth(#border_width))),
         Styles::BorderEndWidth(border_end_width) => tokens.extend(quote!(Styles::BorderEndWidth(#border_end_width))),
         Styles::BorderBottomWidth(border_bottom_width) => tokens.extend(quote!(Styles::BorderBottomWidth(#border_bottom_width))),
@@ -315,7 +480,11 @@ impl ToTokens for Styles {
         Styles::BorderTopRightRadius(border_top_right_radius) => tokens.extend(quote!(Styles::BorderTopRightRadius(#border_top_right_radius))),
         Styles::BorderTopEndRadius(border_top_end_radius) => tokens.extend(quote!(Styles::BorderTopEndRadius(#border_top_end_radius))),
         Styles::BorderTopStartRadius(border_top_start_radius) => tokens.extend(quote!(Styles::BorderTopStartRadius(#border_top_start_radius))),
-        Styles::Bottom(bottom) => tokens.extend(quote!(Styles::Bottom(#bottom))),
+        Styles::Bottom(bottom) => dimension_tokens(tokens, bottom, "Bottom"),
+        
+        Styles::CustomProperty(name, value) => tokens.extend(quote!(
+            Styles::CustomProperty(String::from(#name), String::from(#value))
+        )),
         
         Styles::Direction(direction) => { match direction {
             Direction::Inherit => tokens.extend(quote!(Styles::Direction(Direction::Inherit))),
@@ -328,8 +497,8 @@ impl ToTokens for Styles {
             Display::None => tokens.extend(quote!(Styles::Display(Display::None)))
         }},
 
-        Styles::End(end) => tokens.extend(quote!(Styles::End(#end))),
-        Styles::FlexBasis(flex_basis) => tokens.extend(quote!(Styles::FlexBasis(#flex_basis))),
+        Styles::End(end) => dimension_tokens(tokens, end, "End"),
+        Styles::FlexBasis(flex_basis) => dimension_tokens(tokens, flex_basis, "FlexBasis"),
         
         Styles::FlexDirection(direction) => { match direction {
             FlexDirection::Row => tokens.extend(quote!(Styles::FlexDirection(FlexDirection::Row))),
@@ -348,11 +517,23 @@ impl ToTokens for Styles {
         }},
         
         Styles::FontFamily(_family) => {},
-        Styles::FontLineHeight(line_height) => tokens.extend(quote!(Styles::LineHeight(#line_height))),
-        Styles::FontSize(font_size) => tokens.extend(quote!(Styles::FontSize(#font_size))),
-        Styles::FontStyle(_style) => {},
-        Styles::FontWeight(_weight) => {},
-        Styles::Height(height) => tokens.extend(quote!(Styles::Height(#height))),
+        Styles::FontLineHeight(line_height) => tokens.extend(quote!(Styles::FontLineHeight(#line_height))),
+        Styles::FontSize(font_size) => dimension_tokens(tokens, font_size, "FontSize"),
+
+        Styles::FontStyle(style) => { match style {
+            FontStyle::Normal => tokens.extend(quote!(Styles::FontStyle(FontStyle::Normal))),
+            FontStyle::Italic => tokens.extend(quote!(Styles::FontStyle(FontStyle::Italic))),
+            FontStyle::Oblique => tokens.extend(quote!(Styles::FontStyle(FontStyle::Oblique)))
+        }},
+
+        Styles::FontWeight(weight) => { match weight {
+            FontWeight::Normal => tokens.extend(quote!(Styles::FontWeight(FontWeight::Normal))),
+            FontWeight::Bold => tokens.extend(quote!(Styles::FontWeight(FontWeight::Bold)))
+        }},
+
+        Styles::Height(height) => dimension_tokens(tokens, height, "Height"),
+        Styles::Inherit(property) => tokens.extend(quote!(Styles::Inherit(#property))),
+        Styles::Initial(property) => tokens.extend(quote!(Styles::Initial(#property))),
         
         Styles::JustifyContent(justify) => { match justify {
             JustifyContent::FlexStart => tokens.extend(quote!(Styles::JustifyContent(JustifyContent::FlexStart))),
@@ -363,17 +544,17 @@ impl ToTokens for Styles {
             JustifyContent::SpaceEvenly => tokens.extend(quote!(Styles::JustifyContent(JustifyContent::SpaceEvenly)))
         }},
         
-        Styles::Left(left) => tokens.extend(quote!(Styles::Left(#left))),
-        Styles::MarginBottom(margin_bottom) => tokens.extend(quote!(Styles::MarginBottom(#margin_bottom))),
-        Styles::MarginEnd(margin_end) => tokens.extend(quote!(Styles::MarginEnd(#margin_end))),
-        Styles::MarginLeft(margin_left) => tokens.extend(quote!(Styles::MarginLeft(#margin_left))),
-        Styles::MarginRight(margin_right) => tokens.extend(quote!(Styles::MarginRight(#margin_right))),
-        Styles::MarginStart(margin_start) => tokens.extend(quote!(Styles::MarginStart(#margin_start))),
-        Styles::MarginTop(top) => tokens.extend(quote!(Styles::Top(#top))),
-        Styles::MaxHeight(max_height) => tokens.extend(quote!(Styles::MaxHeight(#max_height))),
-        Styles::MaxWidth(max_width) => tokens.extend(quote!(Styles::MaxWidth(#max_width))),
-        Styles::MinHeight(min_height) => tokens.extend(quote!(Styles::MinHeight(#min_height))),
-        Styles::MinWidth(min_width) => tokens.extend(quote!(Styles::MinWidth(#min_width))),
+        Styles::Left(left) => dimension_tokens(tokens, left, "Left"),
+        Styles::MarginBottom(margin_bottom) => dimension_tokens(tokens, margin_bottom, "MarginBottom"),
+        Styles::MarginEnd(margin_end) => dimension_tokens(tokens, margin_end, "MarginEnd"),
+        Styles::MarginLeft(margin_left) => dimension_tokens(tokens, margin_left, "MarginLeft"),
+        Styles::MarginRight(margin_right) => dimension_tokens(tokens, margin_right, "MarginRight"),
+        Styles::MarginStart(margin_start) => dimension_tokens(tokens, margin_start, "MarginStart"),
+        Styles::MarginTop(top) => dimension_tokens(tokens, top, "MarginTop"),
+        Styles::MaxHeight(max_height) => dimension_tokens(tokens, max_height, "MaxHeight"),
+        Styles::MaxWidth(max_width) => dimension_tokens(tokens, max_width, "MaxWidth"),
+        Styles::MinHeight(min_height) => dimension_tokens(tokens, min_height, "MinHeight"),
+        Styles::MinWidth(min_width) => dimension_tokens(tokens, min_width, "MinWidth"),
         Styles::Opacity(opacity) => tokens.extend(quote!(Styles::Opacity(#opacity))),
         
         Styles::Overflow(overflow) => { match overflow {
@@ -382,20 +563,24 @@ impl ToTokens for Styles {
             Overflow::Scroll => tokens.extend(quote!(Styles::Overflow(Overflow::Scroll)))
         }},
         
-        Styles::PaddingBottom(padding_bottom) => tokens.extend(quote!(Styles::PaddingBottom(#padding_bottom))),
-        Styles::PaddingEnd(padding_end) => tokens.extend(quote!(Styles::PaddingEnd(#padding_end))),
-        Styles::PaddingLeft(padding_left) => tokens.extend(quote!(Styles::PaddingLeft(#padding_left))),
-        Styles::PaddingRight(padding_right) => tokens.extend(quote!(Styles::PaddingRight(#padding_right))),
-        Styles::PaddingStart(padding_start) => tokens.extend(quote!(Styles::PaddingStart(#padding_start))),
-        Styles::PaddingTop(padding_top) => tokens.extend(quote!(Styles::PaddingTop(#padding_top))),
+        Styles::PaddingBottom(padding_bottom) => dimension_tokens(tokens, padding_bottom, "PaddingBottom"),
+        Styles::PaddingEnd(padding_end) => dimension_tokens(tokens, padding_end, "PaddingEnd"),
+        Styles::PaddingLeft(padding_left) => dimension_tokens(tokens, padding_left, "PaddingLeft"),
+        Styles::PaddingRight(padding_right) => dimension_tokens(tokens, padding_right, "PaddingRight"),
+        Styles::PaddingStart(padding_start) => dimension_tokens(tokens, padding_start, "PaddingStart"),
+        Styles::PaddingTop(padding_top) => dimension_tokens(tokens, padding_top, "PaddingTop"),
+        
+        Styles::PendingSubstitution(property, value) => tokens.extend(quote!(
+            Styles::PendingSubstitution(String::from(#property), String::from(#value))
+        )),
         
         Styles::PositionType(position_type) => { match position_type {
             PositionType::Relative => tokens.extend(quote!(Styles::PositionType(PositionType::Relative))),
             PositionType::Absolute => tokens.extend(quote!(Styles::PositionType(PositionType::Absolute)))
         }},
         
-        Styles::Right(right) => tokens.extend(quote!(Styles::Right(#right))),
-        Styles::Start(start) => tokens.extend(quote!(Styles::Start(#start))),
+        Styles::Right(right) => dimension_tokens(tokens, right, "Right"),
+        Styles::Start(start) => dimension_tokens(tokens, start, "Start"),
         
         Styles::TextAlignment(alignment) => { match alignment {
             TextAlignment::Auto => tokens.extend(quote!(Styles::TextAlignment(TextAlignment::Auto))),
@@ -409,7 +594,7 @@ impl ToTokens for Styles {
         Styles::TextDecorationColor(color) => color_tokens(tokens, color, "TextDecorationColor"),
         Styles::TextShadowColor(color) => color_tokens(tokens, color, "TextShadowColor"),
         Styles::TintColor(color) => color_tokens(tokens, color, "TintColor"),
-        Styles::Top(top) => tokens.extend(quote!(Styles::Top(#top))),
-        Styles::Width(width) => tokens.extend(quote!(Styles::Width(#width)))
+        Styles::Top(top) => dimension_tokens(tokens, top, "Top"),
+        Styles::Width(width) => dimension_tokens(tokens, width, "Width")
     }}
 }
@@ -2,84 +2,486 @@
 //! slightly modified to fit the `Styles` structure we want internally.
 
 use cssparser::{
-    AtRuleParser, BasicParseError, CowRcStr,
+    AtRuleParser, AtRuleType, BasicParseError, BasicParseErrorKind, CowRcStr,
     DeclarationListParser, DeclarationParser,
-    Parser, ParseError, QualifiedRuleParser,
-    SourceLocation, Token
+    Parser, ParserInput, ParseError, ParseErrorKind, QualifiedRuleParser, RuleListParser,
+    SourceLocation, ToCss, Token
 };
 
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
 use crate::styles::*;
 
-/// Represents a style rule, a `key: [values...];` pair.
+/// Something in a stylesheet that couldn't be parsed, and so was skipped. Lines and columns
+/// start at 1.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CssError {
+    pub line: u32,
+    pub column: u32,
+    pub message: String
+}
+
+impl CssError {
+    /// Creates a `CssError` for `error`, which occurred while parsing `source`.
+    fn new<'i>(error: &ParseError<'i, BasicParseError<'i>>, source: &str) -> CssError {
+        let kind = match &error.kind {
+            // Only unknown properties are reported as custom errors. They're reported from just
+            // after the colon, so they're moved back to the start of the property name.
+            ParseErrorKind::Custom(BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(Token::Ident(name)), ..
+            }) => {
+                let prefix = source.find(':').map_or("", |colon| &source[..=colon]);
+                let column = match prefix.contains('\n') {
+                    true => error.location.column,
+                    false => error.location.column.saturating_sub(prefix.encode_utf16().count() as u32)
+                };
+
+                return CssError {
+                    line: error.location.line + 1,
+                    column: column,
+                    message: format!("unknown property `{}`", name)
+                };
+            },
+
+            ParseErrorKind::Basic(kind) => kind,
+            ParseErrorKind::Custom(error) => &error.kind
+        };
+
+        let message = match kind {
+            BasicParseErrorKind::UnexpectedToken(token) => format!("unexpected `{}`", token.to_css_string()),
+            BasicParseErrorKind::EndOfInput => "unexpected end of input".to_string(),
+            BasicParseErrorKind::AtRuleInvalid(name) => format!("unsupported at-rule `@{}`", name),
+            BasicParseErrorKind::AtRuleBodyInvalid => "invalid at-rule body".to_string(),
+            BasicParseErrorKind::QualifiedRuleInvalid => "invalid rule".to_string()
+        };
+
+        CssError {
+            line: error.location.line + 1,
+            column: error.location.column,
+            message: format!("{}, in `{}`", message, source.trim())
+        }
+    }
+}
+
+impl fmt::Display for CssError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for CssError {}
+
+/// Parses a stylesheet, returning the rules that parsed, along with errors for anything that
+/// didn't (in the order they occurred).
+pub fn parse_rules(css: &str) -> (Vec<Rule>, Vec<CssError>) {
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    let mut rule_list = RuleListParser::new_for_stylesheet(&mut parser, RuleParser::new());
+    let mut rules = vec![];
+    let mut errors = vec![];
+
+    while let Some(rule) = rule_list.next() {
+        match rule {
+            Ok(rule) => { rules.extend(rule); },
+            Err((error, source)) => { errors.push(CssError::new(&error, source)); }
+        }
+    }
+
+    errors.append(&mut rule_list.parser.errors);
+    errors.sort_by_key(|error| (error.line, error.column));
+    (rules, errors)
+}
+
+impl From<Rule> for StyleRule {
+    fn from(rule: Rule) -> StyleRule {
+        StyleRule {
+            media: rule.media,
+            selectors: rule.selectors,
+            styles: rule.styles
+        }
+    }
+}
+
+/// Represents a style rule: selectors, and a list of `key: value;` pairs, along with any `@media`
+/// rules it's nested in.
 #[derive(Debug)]
 pub struct Rule {
-    pub key: String,
+    pub media: Vec<MediaQueryList>,
+    pub selectors: Vec<Selector>,
     pub styles: Vec<Styles>
 }
 
-/// The parser itself.
-#[derive(Debug)]
-pub struct RuleParser;
+/// The parser itself. Each top-level rule in a stylesheet parses into a list of `Rule`s: a
+/// style rule into just the one, and an `@media` block into one for each rule inside of it.
+///
+/// Errors in declarations and nested rules don't fail the rule they're in; they're collected in
+/// `errors` instead. You'll generally want `parse_rules()`, which handles this for you.
+#[derive(Debug, Default)]
+pub struct RuleParser {
+    pub errors: Vec<CssError>
+}
+
+impl RuleParser {
+    pub fn new() -> RuleParser {
+        RuleParser { errors: vec![] }
+    }
+}
 
-/// Some type information for our parser.
+/// Handles `@media` blocks. Any other at-rules are rejected.
 impl<'i> AtRuleParser<'i> for RuleParser {
-    type PreludeBlock = ();
+    type PreludeBlock = MediaQueryList;
     type PreludeNoBlock = ();
-    type AtRule = Rule;
+    type AtRule = Vec<Rule>;
     type Error = BasicParseError<'i>;
+
+    /// Parses the media queries for an `@media` rule.
+    fn parse_prelude<'t>(
+        &mut self,
+        name: CowRcStr<'i>,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<AtRuleType<Self::PreludeNoBlock, Self::PreludeBlock>, ParseError<'i, Self::Error>> {
+        match &*name {
+            "media" => Ok(AtRuleType::WithBlock(parse_media_query_list(input)?)),
+            _ => Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)))
+        }
+    }
+
+    /// Parses the rules inside of an `@media` block, which only apply when its queries match.
+    fn parse_block<'t>(
+        &mut self,
+        media: Self::PreludeBlock,
+        _location: SourceLocation,
+        input: &mut Parser<'i, 't>,
+    ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
+        let mut nested = RuleListParser::new_for_nested_rule(input, RuleParser::new());
+        let mut rules = vec![];
+
+        while let Some(rule) = nested.next() {
+            match rule {
+                Ok(rule) => { rules.extend(rule); },
+                Err((error, source)) => { self.errors.push(CssError::new(&error, source)); }
+            }
+        }
+
+        self.errors.append(&mut nested.parser.errors);
+
+        Ok(rules.into_iter().map(|mut rule| {
+            rule.media.insert(0, media.clone());
+            rule
+        }).collect())
+    }
 }
 
 /// The actual work our parser does. Walks style rules and attempts to
 /// extract the key/value pairings from a given stylesheet string.
 impl<'i> QualifiedRuleParser<'i> for RuleParser {
-    type Prelude = String;
-    type QualifiedRule = Rule;
+    type Prelude = Vec<Selector>;
+    type QualifiedRule = Vec<Rule>;
     type Error = BasicParseError<'i>;
 
-    /// Parses out the selector.
+    /// Parses out the selectors (there can be several, separated by commas).
     fn parse_prelude<'t>(
         &mut self,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
-        let location = input.current_source_location();
-
-        let selector = match input.next()? {
-            Token::Ident(ref element_name) => element_name.to_string(),
-            t => { return Err(location.new_unexpected_token_error(t.clone())); }
-        };
-
-        // If there's a next, someone is writing their code assuming cascading. Let's... warn them.
-        /*match input.next()? {
-            Ok(_) => {},
-            Err(e) => {}
-        };*/
-
-        Ok(selector)
+        input.parse_comma_separated(|input| parse_selector(input).map_err(ParseError::from))
     }
 
     /// Parses the block (`{...}`) into a Rule struct.
     fn parse_block<'t>(
         &mut self,
-        key: Self::Prelude,
+        selectors: Self::Prelude,
         _location: SourceLocation,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::QualifiedRule, ParseError<'i, Self::Error>> {
-        let styles = DeclarationListParser::new(input, StyleParser {}).collect::<Vec<_>>();
+        let mut styles = vec![];
+
+        for declaration in DeclarationListParser::new(input, StyleParser {}) {
+            match declaration {
+                Ok(declaration) => { styles.extend(declaration); },
+                Err((error, source)) => { self.errors.push(CssError::new(&error, source)); }
+            }
+        }
+
+        Ok(vec![Rule {
+            media: vec![],
+            selectors: selectors,
+            styles: styles
+        }])
+    }
+}
+
+/// Consumes the rest of `input`, returning it as written (minus surrounding whitespace).
+fn raw_value<'i, 't>(input: &mut Parser<'i, 't>) -> String {
+    let start = input.position();
+    while input.next_including_whitespace_and_comments().is_ok() {}
+    input.slice_from(start).trim().to_string()
+}
+
+/// Checks whether a value contains a `var()` reference anywhere, without consuming it.
+fn uses_variables<'i, 't>(input: &mut Parser<'i, 't>) -> bool {
+    fn find_var<'i, 't>(input: &mut Parser<'i, 't>) -> bool {
+        while let Ok(token) = input.next() {
+            let nested = match token {
+                Token::Function(ref name) if name.eq_ignore_ascii_case("var") => { return true; },
+                Token::Function(_) | Token::ParenthesisBlock |
+                Token::SquareBracketBlock | Token::CurlyBracketBlock => true,
+                _ => false
+            };
 
-        Ok(Rule {
-            key: key,
-            styles: styles.into_iter().filter_map(|decl| {
-                if !decl.is_ok() {
-                    eprintln!("{:?}", decl);
+            if nested {
+                let found = input.parse_nested_block(|input| -> Result<bool, ParseError<'i, ()>> {
+                    Ok(find_var(input))
+                });
+
+                if found.unwrap_or(false) {
+                    return true;
                 }
+            }
+        }
+
+        false
+    }
 
-                decl.ok()
-            }).collect()
-        })
+    let start = input.state();
+    let found = find_var(input);
+    input.reset(&start);
+    found
+}
+
+/// Substitutes the `var(--name)` and `var(--name, fallback)` references in `value` with values
+/// from `variables`, which should already have been substituted themselves. Returns `None` if a
+/// variable isn't set and has no fallback.
+pub fn substitute_variables(value: &str, variables: &HashMap<String, String>) -> Option<String> {
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+    let mut output = String::new();
+
+    match substitute(&mut parser, variables, &mut output) {
+        Ok(()) => Some(output),
+        Err(_) => None
     }
 }
 
-/// Contains logic for matching CSS attributes to their `Styles` counterpart.
+/// Handles the actual work for `substitute_variables()`, recursing into blocks and functions.
+fn substitute<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    variables: &HashMap<String, String>,
+    output: &mut String
+) -> Result<(), ParseError<'i, ()>> {
+    loop {
+        let start = input.position();
+        let token = match input.next_including_whitespace_and_comments() {
+            Ok(token) => token.clone(),
+            Err(_) => { return Ok(()); }
+        };
+
+        let closing = match token {
+            Token::Function(ref name) if name.eq_ignore_ascii_case("var") => {
+                input.parse_nested_block(|input| substitute_var(input, variables, output))?;
+                continue;
+            },
+
+            Token::Function(_) | Token::ParenthesisBlock => ")",
+            Token::SquareBracketBlock => "]",
+            Token::CurlyBracketBlock => "}",
+
+            _ => {
+                output.push_str(input.slice_from(start));
+                continue;
+            }
+        };
+
+        output.push_str(input.slice_from(start));
+        input.parse_nested_block(|input| substitute(input, variables, output))?;
+        output.push_str(closing);
+    }
+}
+
+/// Handles the inside of a `var()` reference.
+fn substitute_var<'i, 't>(
+    input: &mut Parser<'i, 't>,
+    variables: &HashMap<String, String>,
+    output: &mut String
+) -> Result<(), ParseError<'i, ()>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident_cloned()?;
+    if !name.starts_with("--") {
+        return Err(location.new_custom_error(()));
+    }
+
+    if let Some(value) = variables.get(&*name) {
+        output.push_str(value);
+
+        // The fallback isn't needed, but still has to be consumed.
+        while input.next_including_whitespace_and_comments().is_ok() {}
+        return Ok(());
+    }
+
+    input.expect_comma()?;
+    substitute(input, variables, output)
+}
+
+/// Parses a lone declaration value for `property` (e.g, `width` and `100px`) into `Styles`.
+/// This is used for declarations that had to wait on `var()` substitution.
+pub fn parse_declaration(property: &str, value: &str) -> Option<Vec<Styles>> {
+    let mut input = ParserInput::new(value);
+    let mut parser = Parser::new(&mut input);
+
+    parser.parse_entirely(|input| StyleParser {}.parse_value(CowRcStr::from(property), input)).ok()
+}
+
+/// Parses the comma-separated media queries in an `@media` prelude.
+fn parse_media_query_list<'i, 't>(input: &mut Parser<'i, 't>) -> Result<MediaQueryList, ParseError<'i, BasicParseError<'i>>> {
+    Ok(MediaQueryList(input.parse_comma_separated(parse_media_query)?))
+}
+
+/// Parses a single media query, e.g `screen and (min-width: 600px) and (orientation: landscape)`.
+/// Alchemy only ever renders to a screen, so `all` and `screen` are the only media types allowed.
+fn parse_media_query<'i, 't>(input: &mut Parser<'i, 't>) -> Result<MediaQuery, ParseError<'i, BasicParseError<'i>>> {
+    let mut features = vec![];
+    let _ = input.try_parse(|i| i.expect_ident_matching("only"));
+
+    let location = input.current_source_location();
+    if let Ok(media_type) = input.try_parse(|i| i.expect_ident_cloned()) {
+        match &*media_type {
+            "all" | "screen" => {},
+            _ => { return Err(location.new_unexpected_token_error(Token::Ident(media_type))); }
+        }
+
+        if input.is_exhausted() {
+            return Ok(MediaQuery(features));
+        }
+
+        input.expect_ident_matching("and")?;
+    }
+
+    loop {
+        input.expect_parenthesis_block()?;
+        features.push(input.parse_nested_block(|i| parse_media_feature(i).map_err(ParseError::from))?);
+
+        if input.try_parse(|i| i.expect_ident_matching("and")).is_err() {
+            break;
+        }
+    }
+
+    Ok(MediaQuery(features))
+}
+
+/// Parses the inside of a `(feature: value)` test in a media query.
+fn parse_media_feature<'i, 't>(input: &mut Parser<'i, 't>) -> Result<MediaFeature, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let name = input.expect_ident_cloned()?;
+    input.expect_colon()?;
+
+    match &*name {
+        "min-width" => Ok(MediaFeature::MinWidth(parse_pixels(input)?)),
+        "max-width" => Ok(MediaFeature::MaxWidth(parse_pixels(input)?)),
+        "min-height" => Ok(MediaFeature::MinHeight(parse_pixels(input)?)),
+        "max-height" => Ok(MediaFeature::MaxHeight(parse_pixels(input)?)),
+
+        "orientation" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
+            "portrait" => Ok(MediaFeature::Orientation(Orientation::Portrait)),
+            "landscape" => Ok(MediaFeature::Orientation(Orientation::Landscape)),
+            _ => Err(s.new_basic_unexpected_token_error(t.clone()))
+        }},
+
+        "prefers-color-scheme" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
+            "light" => Ok(MediaFeature::PrefersColorScheme(ColorScheme::Light)),
+            "dark" => Ok(MediaFeature::PrefersColorScheme(ColorScheme::Dark)),
+            _ => Err(s.new_basic_unexpected_token_error(t.clone()))
+        }},
+
+        _ => Err(location.new_basic_unexpected_token_error(Token::Ident(name)))
+    }
+}
+
+/// Parses a single selector, e.g `.sidebar > Text.title`. See the `selector` module for what
+/// these can contain.
+fn parse_selector<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Selector, BasicParseError<'i>> {
+    let mut compounds = vec![];
+    let mut combinators = vec![];
+    let mut compound = CompoundSelector::default();
+    let mut combinator = None;
+
+    // Whitespace is significant here (it's the descendant combinator), so this works through
+    // tokens by hand rather than leaning on the usual whitespace-skipping methods.
+    loop {
+        let location = input.current_source_location();
+        let token = match input.next_including_whitespace() {
+            Ok(token) => token.clone(),
+            Err(_) => { break; }
+        };
+
+        match token {
+            Token::WhiteSpace(_) => {
+                if !compound.is_empty() && combinator.is_none() {
+                    combinator = Some(Combinator::Descendant);
+                }
+            },
+
+            Token::Delim('>') if combinator != Some(Combinator::Child) && !(compound.is_empty() && compounds.is_empty()) => {
+                combinator = Some(Combinator::Child);
+            },
+
+            Token::Delim('.') | Token::Colon | Token::Ident(_) => {
+                // A combinator's pending, so this starts the next compound selector.
+                if let Some(c) = combinator.take() {
+                    if !compound.is_empty() {
+                        compounds.push(compound);
+                        compound = CompoundSelector::default();
+                    }
+
+                    combinators.push(c);
+                }
+
+                match token {
+                    Token::Ident(name) => { compound.names.push(name.to_string()); },
+
+                    Token::Colon => match input.next_including_whitespace()? {
+                        Token::Ident(name) => match PseudoClass::from_name(name) {
+                            Some(pseudo_class) => { compound.pseudo_classes.push(pseudo_class); },
+                            None => { return Err(location.new_basic_unexpected_token_error(Token::Ident(name.clone()))); }
+                        },
+
+                        t => { return Err(location.new_basic_unexpected_token_error(t.clone())); }
+                    },
+
+                    _ => match input.next_including_whitespace()? {
+                        Token::Ident(key) => { compound.keys.push(key.to_string()); },
+                        t => { return Err(location.new_basic_unexpected_token_error(t.clone())); }
+                    }
+                }
+            },
+
+            t => { return Err(location.new_basic_unexpected_token_error(t)); }
+        }
+    }
+
+    // A trailing child combinator (e.g, `a >`) has nothing to apply to.
+    if compound.is_empty() || combinator == Some(Combinator::Child) {
+        return Err(BasicParseError {
+            kind: BasicParseErrorKind::EndOfInput,
+            location: input.current_source_location()
+        });
+    }
+
+    let mut ancestors = vec![];
+    for (combinator, compound) in combinators.into_iter().rev().zip(compounds.into_iter().rev()) {
+        ancestors.push((combinator, compound));
+    }
+
+    Ok(Selector {
+        subject: compound,
+        ancestors: ancestors
+    })
+}
+
+/// Contains logic for matching CSS attributes to their `Styles` counterpart. Shorthands (e.g,
+/// `margin`) expand into the longhand `Styles` they cover, so each declaration can produce
+/// several `Styles`.
 #[derive(Debug)]
 pub struct StyleParser;
 
@@ -87,7 +489,7 @@ pub struct StyleParser;
 impl<'i> AtRuleParser<'i> for StyleParser {
     type PreludeBlock = ();
     type PreludeNoBlock = ();
-    type AtRule = Styles;
+    type AtRule = Vec<Styles>;
     type Error = BasicParseError<'i>;
 }
 
@@ -100,34 +502,81 @@ fn ident<'a>(token: &'a Token) -> &'a str {
 }
 
 impl<'i> DeclarationParser<'i> for StyleParser {
-    type Declaration = Styles;
+    type Declaration = Vec<Styles>;
     type Error = BasicParseError<'i>;
 
-    /// Parses a value (e.g, `background-color: #307ace;`) into a `Styles` value.
+    /// Parses a value (e.g, `background-color: #307ace;`) into `Styles` values.
     fn parse_value<'t>(
         &mut self,
         name: CowRcStr<'i>,
         input: &mut Parser<'i, 't>,
     ) -> Result<Self::Declaration, ParseError<'i, Self::Error>> {
+        // Custom properties can hold just about anything, so they're kept as written; the same
+        // goes for anything using `var()`, until the variables it references are known.
+        if name.starts_with("--") {
+            return Ok(vec![Styles::CustomProperty(name.to_string(), raw_value(input))]);
+        }
+
+        if uses_variables(input) {
+            return Ok(vec![Styles::PendingSubstitution(name.to_string(), raw_value(input))]);
+        }
+
+        // Inherited properties can also take their parent's value, or be reset to their initial one.
+        if let Some(property) = InheritedProperty::from_name(&name) {
+            if input.try_parse(|i| i.expect_ident_matching("inherit")).is_ok() {
+                return Ok(vec![Styles::Inherit(property)]);
+            }
+
+            if input.try_parse(|i| i.expect_ident_matching("initial")).is_ok() {
+                return Ok(vec![Styles::Initial(property)]);
+            }
+        }
+
         let style = match &*name {
-            "align-content" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
-                "flex-start" => Styles::AlignContent(AlignContent::FlexStart),
-                "flex-end" => Styles::AlignContent(AlignContent::FlexEnd),
-                "center" => Styles::AlignContent(AlignContent::Center),
-                "stretch" => Styles::AlignContent(AlignContent::Stretch),
-                "space-between" => Styles::AlignContent(AlignContent::SpaceBetween),
-                "space-around" => Styles::AlignContent(AlignContent::SpaceAround),
-                _ => { return Err(s.new_unexpected_token_error(t.clone())); }
-            }},
+            // Shorthands, which expand into several `Styles` and so return early.
+            "border" => { return parse_border(input); },
             
-            "align-items" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
-                "flex-start" => Styles::AlignItems(AlignItems::FlexStart),
-                "flex-end" => Styles::AlignItems(AlignItems::FlexEnd),
-                "center" => Styles::AlignItems(AlignItems::Center),
-                "baseline" => Styles::AlignItems(AlignItems::Baseline),
-                "stretch" => Styles::AlignItems(AlignItems::Stretch),
-                _ => { return Err(s.new_unexpected_token_error(t.clone())); }
-            }},
+            "border-color" => { return parse_box(input, |i| Color::parse(i), [
+                Styles::BorderTopColor, Styles::BorderRightColor,
+                Styles::BorderBottomColor, Styles::BorderLeftColor
+            ]); },
+            
+            "border-radius" => { return parse_box(input, parse_pixels, [
+                Styles::BorderTopLeftRadius, Styles::BorderTopRightRadius,
+                Styles::BorderBottomRightRadius, Styles::BorderBottomLeftRadius
+            ]); },
+            
+            "border-style" => { return parse_box(input, parse_border_style, [
+                Styles::BorderTopStyle, Styles::BorderRightStyle,
+                Styles::BorderBottomStyle, Styles::BorderLeftStyle
+            ]); },
+            
+            "border-width" => { return parse_box(input, parse_border_width, [
+                Styles::BorderTopWidth, Styles::BorderRightWidth,
+                Styles::BorderBottomWidth, Styles::BorderLeftWidth
+            ]); },
+            
+            "flex" => { return parse_flex(input); },
+            "flex-flow" => { return parse_flex_flow(input); },
+            
+            "inset" => { return parse_box(input, parse_length, [
+                Styles::Top, Styles::Right, Styles::Bottom, Styles::Left
+            ]); },
+            
+            "margin" => { return parse_box(input, parse_length, [
+                Styles::MarginTop, Styles::MarginRight, Styles::MarginBottom, Styles::MarginLeft
+            ]); },
+            
+            "padding" => { return parse_box(input, parse_length, [
+                Styles::PaddingTop, Styles::PaddingRight, Styles::PaddingBottom, Styles::PaddingLeft
+            ]); },
+            
+            "place-content" => { return parse_place_content(input); },
+            "place-items" => { return parse_place_items(input); },
+
+            "align-content" => Styles::AlignContent(parse_align_content(input)?),
+            
+            "align-items" => Styles::AlignItems(parse_align_items(input)?),
             
             "align_self" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
                 "auto" => Styles::AlignSelf(AlignSelf::Auto),
@@ -151,13 +600,27 @@ impl<'i> DeclarationParser<'i> for StyleParser {
             "background-color" => Styles::BackgroundColor(Color::parse(input)?),
             
             // Border values~
-            "border-color" => Styles::BorderColor(Color::parse(input)?),
             "border-top-color" => Styles::BorderTopColor(Color::parse(input)?),
             "border-bottom-color" => Styles::BorderBottomColor(Color::parse(input)?),
             "border-left-color" => Styles::BorderLeftColor(Color::parse(input)?),
             "border-right-color" => Styles::BorderRightColor(Color::parse(input)?),
+
+            "border-top-style" => Styles::BorderTopStyle(parse_border_style(input)?),
+            "border-bottom-style" => Styles::BorderBottomStyle(parse_border_style(input)?),
+            "border-left-style" => Styles::BorderLeftStyle(parse_border_style(input)?),
+            "border-right-style" => Styles::BorderRightStyle(parse_border_style(input)?),
+
+            "border-top-width" => Styles::BorderTopWidth(parse_border_width(input)?),
+            "border-bottom-width" => Styles::BorderBottomWidth(parse_border_width(input)?),
+            "border-left-width" => Styles::BorderLeftWidth(parse_border_width(input)?),
+            "border-right-width" => Styles::BorderRightWidth(parse_border_width(input)?),
+
+            "border-top-left-radius" => Styles::BorderTopLeftRadius(parse_pixels(input)?),
+            "border-top-right-radius" => Styles::BorderTopRightRadius(parse_pixels(input)?),
+            "border-bottom-right-radius" => Styles::BorderBottomRightRadius(parse_pixels(input)?),
+            "border-bottom-left-radius" => Styles::BorderBottomLeftRadius(parse_pixels(input)?),
             
-            "bottom" => Styles::Bottom(parse_floaty_mcfloatface_value(input)?),
+            "bottom" => Styles::Bottom(parse_length(input)?),
 
             "color" => Styles::TextColor(Color::parse(input)?),
 
@@ -174,30 +637,19 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 _ => { return Err(s.new_unexpected_token_error(t.clone())); }
             }},
             
-            "end" => Styles::End(parse_floaty_mcfloatface_value(input)?),
+            "end" => Styles::End(parse_length(input)?),
 
-            "flex-basis" => Styles::FlexBasis(parse_floaty_mcfloatface_value(input)?),
+            "flex-basis" => Styles::FlexBasis(parse_length(input)?),
             
-            "flex-direction" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
-                "row" => Styles::FlexDirection(FlexDirection::Row),
-                "row-reverse" => Styles::FlexDirection(FlexDirection::RowReverse),
-                "column" => Styles::FlexDirection(FlexDirection::Column),
-                "column-reverse" => Styles::FlexDirection(FlexDirection::ColumnReverse),
-                _ => { return Err(s.new_unexpected_token_error(t.clone())); }
-            }},
+            "flex-direction" => Styles::FlexDirection(parse_flex_direction(input)?),
 
             "flex-grow" => Styles::FlexGrow(parse_floaty_mcfloatface_value(input)?),
             "flex-shrink" => Styles::FlexShrink(parse_floaty_mcfloatface_value(input)?),
             
-            "flex-wrap" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
-                "no-wrap" => Styles::FlexWrap(FlexWrap::NoWrap),
-                "wrap" => Styles::FlexWrap(FlexWrap::Wrap),
-                "wrap-reverse" => Styles::FlexWrap(FlexWrap::WrapReverse),
-                _ => { return Err(s.new_unexpected_token_error(t.clone())); }
-            }},
+            "flex-wrap" => Styles::FlexWrap(parse_flex_wrap(input)?),
             
             //FontFamily(FontFamily),
-            "font-size" => Styles::FontSize(parse_floaty_mcfloatface_value(input)?),
+            "font-size" => Styles::FontSize(parse_font_size(input)?),
             
             "font-style" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
                 "normal" => Styles::FontStyle(FontStyle::Normal),
@@ -212,33 +664,25 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 _ => { return Err(s.new_unexpected_token_error(t.clone())); }
             }},
             
-            "height" => Styles::Height(parse_floaty_mcfloatface_value(input)?),
-
-            "justify-content" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
-                "flex-start" => Styles::JustifyContent(JustifyContent::FlexStart),
-                "flex-end" => Styles::JustifyContent(JustifyContent::FlexEnd),
-                "center" => Styles::JustifyContent(JustifyContent::Center),
-                "space-between" => Styles::JustifyContent(JustifyContent::SpaceBetween),
-                "space-around" => Styles::JustifyContent(JustifyContent::SpaceAround),
-                "space-evenly" => Styles::JustifyContent(JustifyContent::SpaceEvenly),
-                _ => { return Err(s.new_unexpected_token_error(t.clone())); }
-            }},
+            "height" => Styles::Height(parse_length(input)?),
+
+            "justify-content" => Styles::JustifyContent(parse_justify_content(input)?),
             
-            "left" => Styles::Left(parse_floaty_mcfloatface_value(input)?),
+            "left" => Styles::Left(parse_length(input)?),
             "line-height" => Styles::FontLineHeight(parse_floaty_mcfloatface_value(input)?),
 
-            "margin-bottom" => Styles::MarginBottom(parse_floaty_mcfloatface_value(input)?),
-            "margin-end" => Styles::MarginEnd(parse_floaty_mcfloatface_value(input)?),
-            "margin-left" => Styles::MarginLeft(parse_floaty_mcfloatface_value(input)?),
-            "margin-right" => Styles::MarginRight(parse_floaty_mcfloatface_value(input)?),
-            "margin-start" => Styles::MarginStart(parse_floaty_mcfloatface_value(input)?),
-            "margin-top" => Styles::MarginTop(parse_floaty_mcfloatface_value(input)?),
+            "margin-bottom" => Styles::MarginBottom(parse_length(input)?),
+            "margin-end" => Styles::MarginEnd(parse_length(input)?),
+            "margin-left" => Styles::MarginLeft(parse_length(input)?),
+            "margin-right" => Styles::MarginRight(parse_length(input)?),
+            "margin-start" => Styles::MarginStart(parse_length(input)?),
+            "margin-top" => Styles::MarginTop(parse_length(input)?),
 
-            "max-height" => Styles::MaxHeight(parse_floaty_mcfloatface_value(input)?),
-            "max-width" => Styles::MaxWidth(parse_floaty_mcfloatface_value(input)?),
+            "max-height" => Styles::MaxHeight(parse_length(input)?),
+            "max-width" => Styles::MaxWidth(parse_length(input)?),
             
-            "min-height" => Styles::MinHeight(parse_floaty_mcfloatface_value(input)?),
-            "min-width" => Styles::MinWidth(parse_floaty_mcfloatface_value(input)?),
+            "min-height" => Styles::MinHeight(parse_length(input)?),
+            "min-width" => Styles::MinWidth(parse_length(input)?),
 
             "opacity" => Styles::Opacity(parse_floaty_mcfloatface_value(input)?),
             
@@ -249,12 +693,12 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 _ => { return Err(s.new_unexpected_token_error(t.clone())); }
             }},
             
-            "padding-bottom" => Styles::PaddingBottom(parse_floaty_mcfloatface_value(input)?),
-            "padding-end" => Styles::PaddingEnd(parse_floaty_mcfloatface_value(input)?),
-            "padding-left" => Styles::PaddingLeft(parse_floaty_mcfloatface_value(input)?),
-            "padding-right" => Styles::PaddingRight(parse_floaty_mcfloatface_value(input)?),
-            "padding-start" => Styles::PaddingStart(parse_floaty_mcfloatface_value(input)?),
-            "padding-top" => Styles::PaddingTop(parse_floaty_mcfloatface_value(input)?),
+            "padding-bottom" => Styles::PaddingBottom(parse_length(input)?),
+            "padding-end" => Styles::PaddingEnd(parse_length(input)?),
+            "padding-left" => Styles::PaddingLeft(parse_length(input)?),
+            "padding-right" => Styles::PaddingRight(parse_length(input)?),
+            "padding-start" => Styles::PaddingStart(parse_length(input)?),
+            "padding-top" => Styles::PaddingTop(parse_length(input)?),
             
             "position" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
                 "absolute" => Styles::PositionType(PositionType::Absolute),
@@ -262,8 +706,8 @@ impl<'i> DeclarationParser<'i> for StyleParser {
                 _ => { return Err(s.new_unexpected_token_error(t.clone())); }
             }},
             
-            "right" => Styles::Right(parse_floaty_mcfloatface_value(input)?),
-            "start" => Styles::Start(parse_floaty_mcfloatface_value(input)?),
+            "right" => Styles::Right(parse_length(input)?),
+            "start" => Styles::Start(parse_length(input)?),
             
             "text-align" => { let s = input.current_source_location(); let t = input.next()?; match ident(&t) {
                 "auto" => Styles::TextAlignment(TextAlignment::Auto),
@@ -278,16 +722,18 @@ impl<'i> DeclarationParser<'i> for StyleParser {
             "text-shadow-color" => Styles::TextShadowColor(Color::parse(input)?),
             "tint-color" => Styles::TintColor(Color::parse(input)?),
             
-            "top" => Styles::Top(parse_floaty_mcfloatface_value(input)?),
-            "width" => Styles::Width(parse_floaty_mcfloatface_value(input)?),
+            "top" => Styles::Top(parse_length(input)?),
+            "width" => Styles::Width(parse_length(input)?),
             
             t => {
                 let location = input.current_source_location();
-                return Err(location.new_unexpected_token_error(Token::Ident(t.to_string().into())));
+                return Err(location.new_custom_error(
+                    location.new_basic_unexpected_token_error(Token::Ident(t.to_string().into()))
+                ));
             }
         };
 
-        Ok(style)
+        Ok(vec![style])
     }
 }
 
@@ -302,3 +748,355 @@ fn parse_floaty_mcfloatface_value<'i, 't>(input: &mut Parser<'i, 't>) -> Result<
         _ => Err(location.new_basic_unexpected_token_error(token.clone()))
     }
 }
+
+/// Parses a length, for `Styles` that take a `Dimension`. Accepts `auto`, percentages, and
+/// `px`, `em`, `rem`, `vw` and `vh` units; bare numbers are treated as `px`.
+fn parse_length<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Dimension, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match token {
+        Token::Number { value, .. } => Ok(Dimension::Points(*value)),
+        Token::Percentage { unit_value, .. } => Ok(Dimension::Percent(*unit_value)),
+        Token::Ident(ref value) if value.eq_ignore_ascii_case("auto") => Ok(Dimension::Auto),
+
+        Token::Dimension { value, ref unit, .. } => match &*unit.to_ascii_lowercase() {
+            "px" => Ok(Dimension::Points(*value)),
+            "em" => Ok(Dimension::Em(*value)),
+            "rem" => Ok(Dimension::Rem(*value)),
+            "vw" => Ok(Dimension::ViewportWidth(*value / 100.)),
+            "vh" => Ok(Dimension::ViewportHeight(*value / 100.)),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+        },
+
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+/// Parses a `font-size`, which is a length - but not `auto`, or relative to the viewport, neither
+/// of which a font size can be resolved from.
+fn parse_font_size<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Dimension, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let state = input.state();
+
+    match parse_length(input)? {
+        Dimension::Auto | Dimension::ViewportWidth(_) | Dimension::ViewportHeight(_) => {
+            input.reset(&state);
+            let token = input.next()?.clone();
+            Err(location.new_basic_unexpected_token_error(token))
+        },
+
+        length => Ok(length)
+    }
+}
+
+/// Parses a box shorthand (e.g, `margin`), which takes 1-4 values, and expands it into the
+/// `Styles` for each side. `sides` are ordered top, right, bottom, left, as with the values.
+fn parse_box<'i, 't, T, F>(
+    input: &mut Parser<'i, 't>,
+    parse: F,
+    sides: [fn(T) -> Styles; 4]
+) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>>
+where
+    T: Copy,
+    F: Fn(&mut Parser<'i, 't>) -> Result<T, BasicParseError<'i>>
+{
+    let mut values = vec![parse(input)?];
+    while values.len() < 4 {
+        match input.try_parse(|i| parse(i)) {
+            Ok(value) => { values.push(value); },
+            Err(_) => { break; }
+        }
+    }
+
+    input.expect_exhausted()?;
+
+    let (top, right, bottom, left) = match values.len() {
+        1 => (values[0], values[0], values[0], values[0]),
+        2 => (values[0], values[1], values[0], values[1]),
+        3 => (values[0], values[1], values[2], values[1]),
+        _ => (values[0], values[1], values[2], values[3])
+    };
+
+    Ok(vec![sides[0](top), sides[1](right), sides[2](bottom), sides[3](left)])
+}
+
+/// Parses `border: width style color`, where each part is optional and can come in any order.
+/// As in CSS, an omitted width or style is reset (to `medium` and `none`, respectively).
+fn parse_border<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>> {
+    let mut width = None;
+    let mut style = None;
+    let mut color = None;
+
+    loop {
+        if width.is_none() {
+            if let Ok(value) = input.try_parse(parse_border_width) { width = Some(value); continue; }
+        }
+
+        if style.is_none() {
+            if let Ok(value) = input.try_parse(parse_border_style) { style = Some(value); continue; }
+        }
+
+        if color.is_none() {
+            if let Ok(value) = input.try_parse(Color::parse) { color = Some(value); continue; }
+        }
+
+        break;
+    }
+
+    if width.is_none() && style.is_none() && color.is_none() {
+        let location = input.current_source_location();
+        let token = input.next()?;
+        return Err(location.new_unexpected_token_error(token.clone()));
+    }
+
+    input.expect_exhausted()?;
+
+    let width = width.unwrap_or(3.);
+    let style = style.unwrap_or(BorderStyle::None);
+    let mut styles = vec![
+        Styles::BorderTopWidth(width), Styles::BorderRightWidth(width),
+        Styles::BorderBottomWidth(width), Styles::BorderLeftWidth(width),
+        Styles::BorderTopStyle(style), Styles::BorderRightStyle(style),
+        Styles::BorderBottomStyle(style), Styles::BorderLeftStyle(style)
+    ];
+
+    if let Some(color) = color {
+        styles.extend(vec![
+            Styles::BorderTopColor(color), Styles::BorderRightColor(color),
+            Styles::BorderBottomColor(color), Styles::BorderLeftColor(color)
+        ]);
+    }
+
+    Ok(styles)
+}
+
+/// Parses `flex: grow shrink basis`, along with the `none`, `auto` and `initial` keywords. As
+/// in CSS, an omitted grow or shrink factor defaults to `1`, and an omitted basis to `0`.
+fn parse_flex<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>> {
+    let keyword = input.try_parse(|i| -> Result<(f32, f32), BasicParseError<'i>> {
+        let location = i.current_source_location();
+        let token = i.next()?;
+
+        match ident(&token) {
+            "none" => Ok((0., 0.)),
+            "auto" => Ok((1., 1.)),
+            "initial" => Ok((0., 1.)),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+        }
+    });
+
+    if let Ok((grow, shrink)) = keyword {
+        input.expect_exhausted()?;
+        return Ok(vec![Styles::FlexGrow(grow), Styles::FlexShrink(shrink), Styles::FlexBasis(Dimension::Auto)]);
+    }
+
+    let mut grow = None;
+    let mut shrink = None;
+    let mut basis = None;
+
+    loop {
+        // Unitless numbers are read as factors first, so `flex: 1 1 0` does what you'd expect.
+        if grow.is_none() {
+            if let Ok(value) = input.try_parse(parse_floaty_mcfloatface_value) {
+                grow = Some(value);
+                shrink = input.try_parse(parse_floaty_mcfloatface_value).ok();
+                continue;
+            }
+        }
+
+        if basis.is_none() {
+            if let Ok(value) = input.try_parse(parse_length) { basis = Some(value); continue; }
+        }
+
+        break;
+    }
+
+    if grow.is_none() && basis.is_none() {
+        let location = input.current_source_location();
+        let token = input.next()?;
+        return Err(location.new_unexpected_token_error(token.clone()));
+    }
+
+    input.expect_exhausted()?;
+
+    Ok(vec![
+        Styles::FlexGrow(grow.unwrap_or(1.)),
+        Styles::FlexShrink(shrink.unwrap_or(1.)),
+        Styles::FlexBasis(basis.unwrap_or(Dimension::Points(0.)))
+    ])
+}
+
+/// Parses `flex-flow: direction wrap`, where either part can be omitted (and is then reset).
+fn parse_flex_flow<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>> {
+    let mut direction = None;
+    let mut wrap = None;
+
+    loop {
+        if direction.is_none() {
+            if let Ok(value) = input.try_parse(parse_flex_direction) { direction = Some(value); continue; }
+        }
+
+        if wrap.is_none() {
+            if let Ok(value) = input.try_parse(parse_flex_wrap) { wrap = Some(value); continue; }
+        }
+
+        break;
+    }
+
+    if direction.is_none() && wrap.is_none() {
+        let location = input.current_source_location();
+        let token = input.next()?;
+        return Err(location.new_unexpected_token_error(token.clone()));
+    }
+
+    input.expect_exhausted()?;
+
+    Ok(vec![
+        Styles::FlexDirection(direction.unwrap_or(FlexDirection::Row)),
+        Styles::FlexWrap(wrap.unwrap_or(FlexWrap::NoWrap))
+    ])
+}
+
+/// Parses `place-content: align justify`. With only one value, it's used for both; `stretch`
+/// isn't a valid `justify-content`, and behaves as `flex-start` there, as in CSS.
+fn parse_place_content<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>> {
+    let align = parse_align_content(input)?;
+    let justify = match input.try_parse(parse_justify_content) {
+        Ok(justify) => justify,
+        Err(_) => match align {
+            AlignContent::FlexStart | AlignContent::Stretch => JustifyContent::FlexStart,
+            AlignContent::FlexEnd => JustifyContent::FlexEnd,
+            AlignContent::Center => JustifyContent::Center,
+            AlignContent::SpaceBetween => JustifyContent::SpaceBetween,
+            AlignContent::SpaceAround => JustifyContent::SpaceAround
+        }
+    };
+
+    input.expect_exhausted()?;
+    Ok(vec![Styles::AlignContent(align), Styles::JustifyContent(justify)])
+}
+
+/// Parses `place-items: align justify`. `justify-items` doesn't do anything in flex layout, so
+/// the second value is accepted but otherwise ignored.
+fn parse_place_items<'i, 't>(input: &mut Parser<'i, 't>) -> Result<Vec<Styles>, ParseError<'i, BasicParseError<'i>>> {
+    let align = parse_align_items(input)?;
+    let _ = input.try_parse(parse_align_items);
+
+    input.expect_exhausted()?;
+    Ok(vec![Styles::AlignItems(align)])
+}
+
+/// Parses a border width, which can be a length in `px` or one of `thin`, `medium` or `thick`.
+fn parse_border_width<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BasicParseError<'i>> {
+    let keyword = input.try_parse(|i| -> Result<f32, BasicParseError<'i>> {
+        let location = i.current_source_location();
+        let token = i.next()?;
+
+        match ident(&token) {
+            "thin" => Ok(1.),
+            "medium" => Ok(3.),
+            "thick" => Ok(5.),
+            _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+        }
+    });
+
+    match keyword {
+        Ok(width) => Ok(width),
+        Err(_) => parse_pixels(input)
+    }
+}
+
+/// Parses a length in `px`, for values that can't be relative to anything (e.g, border radii).
+/// Bare numbers are treated as `px`.
+fn parse_pixels<'i, 't>(input: &mut Parser<'i, 't>) -> Result<f32, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match token {
+        Token::Number { value, .. } => Ok(*value),
+        Token::Dimension { value, ref unit, .. } if unit.eq_ignore_ascii_case("px") => Ok(*value),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_border_style<'i, 't>(input: &mut Parser<'i, 't>) -> Result<BorderStyle, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "none" => Ok(BorderStyle::None),
+        "hidden" => Ok(BorderStyle::Hidden),
+        "solid" => Ok(BorderStyle::Solid),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_align_content<'i, 't>(input: &mut Parser<'i, 't>) -> Result<AlignContent, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "flex-start" => Ok(AlignContent::FlexStart),
+        "flex-end" => Ok(AlignContent::FlexEnd),
+        "center" => Ok(AlignContent::Center),
+        "stretch" => Ok(AlignContent::Stretch),
+        "space-between" => Ok(AlignContent::SpaceBetween),
+        "space-around" => Ok(AlignContent::SpaceAround),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_align_items<'i, 't>(input: &mut Parser<'i, 't>) -> Result<AlignItems, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "flex-start" => Ok(AlignItems::FlexStart),
+        "flex-end" => Ok(AlignItems::FlexEnd),
+        "center" => Ok(AlignItems::Center),
+        "baseline" => Ok(AlignItems::Baseline),
+        "stretch" => Ok(AlignItems::Stretch),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_flex_direction<'i, 't>(input: &mut Parser<'i, 't>) -> Result<FlexDirection, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "row" => Ok(FlexDirection::Row),
+        "row-reverse" => Ok(FlexDirection::RowReverse),
+        "column" => Ok(FlexDirection::Column),
+        "column-reverse" => Ok(FlexDirection::ColumnReverse),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_flex_wrap<'i, 't>(input: &mut Parser<'i, 't>) -> Result<FlexWrap, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "nowrap" | "no-wrap" => Ok(FlexWrap::NoWrap),
+        "wrap" => Ok(FlexWrap::Wrap),
+        "wrap-reverse" => Ok(FlexWrap::WrapReverse),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
+
+fn parse_justify_content<'i, 't>(input: &mut Parser<'i, 't>) -> Result<JustifyContent, BasicParseError<'i>> {
+    let location = input.current_source_location();
+    let token = input.next()?;
+
+    match ident(&token) {
+        "flex-start" => Ok(JustifyContent::FlexStart),
+        "flex-end" => Ok(JustifyContent::FlexEnd),
+        "center" => Ok(JustifyContent::Center),
+        "space-between" => Ok(JustifyContent::SpaceBetween),
+        "space-around" => Ok(JustifyContent::SpaceAround),
+        "space-evenly" => Ok(JustifyContent::SpaceEvenly),
+        _ => Err(location.new_basic_unexpected_token_error(token.clone()))
+    }
+}
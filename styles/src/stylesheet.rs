@@ -7,31 +7,175 @@
 
 use std::collections::HashMap;
 
+use crate::media::{MediaEnvironment, MediaQueryList};
+use crate::selector::{Selector, StyledNode};
 use crate::stretch::style::Style;
-use crate::styles::{Appearance, Dimension, Rect, Size, Styles};
+use crate::styles::{Appearance, CornerRadii, Dimension, Rect, Size, Styles, ROOT_FONT_SIZE};
 
-/// A `StyleSheet` contains selectors and parsed `Styles` attributes.
-/// It also has some logic to apply styles for n keys to a given `Style` node.
-#[derive(Debug)]
-pub struct StyleSheet(HashMap<&'static str, Vec<Styles>>);
+/// A rule in a `StyleSheet`: a set of `Styles`, and the selectors it applies for.
+#[derive(Clone, Debug)]
+pub struct StyleRule {
+    /// The `@media` rules this rule is nested in, all of which have to match for it to apply.
+    pub media: Vec<MediaQueryList>,
+
+    pub selectors: Vec<Selector>,
+    pub styles: Vec<Styles>
+}
+
+/// A `StyleSheet` contains rules, each made up of selectors and parsed `Styles` attributes.
+/// It also has some logic to apply the matching rules for a node to a given `Style` node.
+///
+/// Stylesheets merged together keep their rules in separate layers, in the order they were
+/// merged; where layers conflict, the later one wins, regardless of specificity.
+#[derive(Clone, Debug, Default)]
+pub struct StyleSheet(Vec<Vec<StyleRule>>);
 
 impl StyleSheet {
-    /// Creates a new `Stylesheet`.
-    pub fn new(styles: HashMap<&'static str, Vec<Styles>>) -> Self {
-        StyleSheet(styles)
+    /// Creates a new `Stylesheet`. Rules should be in source order, which breaks ties between
+    /// rules of the same specificity.
+    pub fn new(rules: Vec<StyleRule>) -> Self {
+        StyleSheet(vec![rules])
+    }
+
+    /// Merges `other` into this stylesheet, layered on top of it: where they both set the same
+    /// property for a node, `other` wins - even if this stylesheet's rule is more specific.
+    pub fn merge(&mut self, other: StyleSheet) {
+        self.0.extend(other.0);
+    }
+
+    /// Applies every rule matching the last node in `path` (the rest of which are its
+    /// ancestors, from the root down), in order of specificity and then source order. Rules
+    /// inside of `@media` blocks only apply if `media` satisfies them.
+    ///
+    /// Declarations using `var()` are resolved against the custom properties set on the node and
+    /// its ancestors. `appearance` should come in holding whatever the node inherited from its
+    /// parent, which is what `inherit` resolves to.
+    pub fn apply_styles(
+        &self,
+        path: &[StyledNode],
+        media: &MediaEnvironment,
+        style: &mut Style,
+        appearance: &mut Appearance
+    ) {
+        apply_layered_styles(&[self], path, media, style, appearance)
+    }
+
+    /// Returns the rules matching the last node in `path`, in the order they should be applied:
+    /// layer by layer, and within each, by specificity and then source order.
+    fn matching_rules(&self, path: &[StyledNode], media: &MediaEnvironment) -> Vec<&StyleRule> {
+        self.0.iter().flat_map(|layer| {
+            let rules = layer.iter().filter(|rule| rule.media.iter().all(|queries| queries.matches(media)));
+
+            let mut matched: Vec<_> = rules.filter_map(|rule| {
+                rule.selectors.iter()
+                    .filter(|selector| selector.matches(path))
+                    .map(|selector| selector.specificity())
+                    .max()
+                    .map(|specificity| (specificity, rule))
+            }).collect();
+
+            // This is a stable sort, so rules of the same specificity stay in source order.
+            matched.sort_by_key(|(specificity, _)| *specificity);
+            matched.into_iter().map(|(_, rule)| rule)
+        }).collect()
     }
+}
 
-    pub fn apply_styles(&self, key: &str, style: &mut Style, appearance: &mut Appearance) {
-        match self.0.get(key) {
-            Some(styles) => { reduce_styles_into_style(styles, style, appearance); },
-            None => {}
+/// Applies styles from each of `stylesheets` in turn, as `StyleSheet::apply_styles()` does - so
+/// where they conflict, later stylesheets win, regardless of specificity. Custom properties are
+/// shared between them, so a later stylesheet can use variables set in an earlier one.
+pub fn apply_layered_styles(
+    stylesheets: &[&StyleSheet],
+    path: &[StyledNode],
+    media: &MediaEnvironment,
+    style: &mut Style,
+    appearance: &mut Appearance
+) {
+    let matched = matching_rules(stylesheets, path, media);
+
+    let needs_variables = matched.iter().any(|rule| rule.styles.iter().any(|style| match style {
+        Styles::PendingSubstitution(_, _) => true,
+        _ => false
+    }));
+
+    let variables = match needs_variables {
+        true => custom_properties(stylesheets, path, media),
+        false => HashMap::new()
+    };
+
+    let inherited = appearance.clone();
+    for rule in matched {
+        reduce_styles_into_style(&rule.styles, &variables, &inherited, style, appearance);
+    }
+}
+
+/// Returns the rules in `stylesheets` matching the last node in `path`, in the order they should
+/// be applied.
+fn matching_rules<'a>(stylesheets: &[&'a StyleSheet], path: &[StyledNode], media: &MediaEnvironment) -> Vec<&'a StyleRule> {
+    stylesheets.iter().flat_map(|stylesheet| stylesheet.matching_rules(path, media)).collect()
+}
+
+/// Works out the custom properties in effect for the last node in `path`. These are inherited, so
+/// this works down from the root, with each node's rules overriding whatever it inherited. Any
+/// `var()` references in their values are resolved as they're set.
+fn custom_properties(stylesheets: &[&StyleSheet], path: &[StyledNode], media: &MediaEnvironment) -> HashMap<String, String> {
+    let mut variables = HashMap::new();
+
+    for i in 0..path.len() {
+        for rule in matching_rules(stylesheets, &path[..=i], media) {
+            for style in &rule.styles {
+                if let Styles::CustomProperty(name, value) = style {
+                    match substitute_variables(value, &variables) {
+                        Some(value) => { variables.insert(name.clone(), value); },
+                        None => { variables.remove(name); }
+                    }
+                }
+            }
         }
     }
+
+    variables
+}
+
+/// Substitutes any `var()` references in `value`. Returns `None` if one can't be resolved (i.e,
+/// the variable isn't set, and there's no fallback).
+#[cfg(feature="parser")]
+fn substitute_variables(value: &str, variables: &HashMap<String, String>) -> Option<String> {
+    crate::styles_parser::substitute_variables(value, variables)
+}
+
+/// Without the parser, there's no way to make sense of `var()`; values are taken as-is.
+#[cfg(not(feature="parser"))]
+fn substitute_variables(value: &str, _variables: &HashMap<String, String>) -> Option<String> {
+    Some(value.to_string())
+}
+
+/// Resolves a declaration that uses `var()` into `Styles`, now that the custom properties for the
+/// node are known. If it references a variable that isn't set (with no fallback), or doesn't
+/// parse once substituted, it's dropped.
+#[cfg(feature="parser")]
+fn resolve_pending_substitution(property: &str, value: &str, variables: &HashMap<String, String>) -> Vec<Styles> {
+    substitute_variables(value, variables)
+        .and_then(|value| crate::styles_parser::parse_declaration(property, &value))
+        .unwrap_or_default()
+}
+
+/// Declarations using `var()` need the parser to be resolved; without it, they're dropped.
+#[cfg(not(feature="parser"))]
+fn resolve_pending_substitution(_property: &str, _value: &str, _variables: &HashMap<String, String>) -> Vec<Styles> {
+    vec![]
 }
 
 /// This takes a list of styles, and a mutable style object, and attempts to configure the
-/// style object in a way that makes sense given n styles.
-fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance: &mut Appearance) {
+/// style object in a way that makes sense given n styles. `variables` are the custom properties
+/// that any `var()` references resolve against, and `inherited` is what `inherit` resolves to.
+fn reduce_styles_into_style(
+    styles: &Vec<Styles>,
+    variables: &HashMap<String, String>,
+    inherited: &Appearance,
+    layout: &mut Style,
+    appearance: &mut Appearance
+) {
     for style in styles { match style {
         Styles::AlignContent(val) => { layout.align_content = *val; },
         Styles::AlignItems(val) => { layout.align_items = *val; },
@@ -40,85 +184,160 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         Styles::BackfaceVisibility(_val) => { },
         Styles::BackgroundColor(val) => { appearance.background_color = *val; },
 
-        Styles::BorderColor(_val) => { },
-        Styles::BorderEndColor(_val) => { },
-        Styles::BorderBottomColor(_val) => { },
-        Styles::BorderLeftColor(_val) => { },
-        Styles::BorderRightColor(_val) => { },
-        Styles::BorderTopColor(_val) => { },
-        Styles::BorderStartColor(_val) => { },
+        Styles::BorderColor(val) => {
+            appearance.border_color = Rect { start: *val, end: *val, top: *val, bottom: *val };
+        },
+
+        Styles::BorderEndColor(val) => { appearance.border_color.end = *val; },
+        Styles::BorderBottomColor(val) => { appearance.border_color.bottom = *val; },
+        Styles::BorderLeftColor(val) => { appearance.border_color.start = *val; },
+        Styles::BorderRightColor(val) => { appearance.border_color.end = *val; },
+        Styles::BorderTopColor(val) => { appearance.border_color.top = *val; },
+        Styles::BorderStartColor(val) => { appearance.border_color.start = *val; },
     
-        Styles::BorderStyle(_val) => { },
-        Styles::BorderEndStyle(_val) => { },
-        Styles::BorderBottomStyle(_val) => { },
-        Styles::BorderLeftStyle(_val) => { },
-        Styles::BorderRightStyle(_val) => { },
-        Styles::BorderTopStyle(_val) => { },
-        Styles::BorderStartStyle(_val) => { },
+        Styles::BorderStyle(val) => {
+            appearance.border_style = Rect { start: *val, end: *val, top: *val, bottom: *val };
+        },
+
+        Styles::BorderEndStyle(val) => { appearance.border_style.end = *val; },
+        Styles::BorderBottomStyle(val) => { appearance.border_style.bottom = *val; },
+        Styles::BorderLeftStyle(val) => { appearance.border_style.start = *val; },
+        Styles::BorderRightStyle(val) => { appearance.border_style.end = *val; },
+        Styles::BorderTopStyle(val) => { appearance.border_style.top = *val; },
+        Styles::BorderStartStyle(val) => { appearance.border_style.start = *val; },
     
-        Styles::BorderWidth(_val) => { },
-        Styles::BorderEndWidth(_val) => { },
-        Styles::BorderBottomWidth(_val) => { },
-        Styles::BorderLeftWidth(_val) => { },
-        Styles::BorderRightWidth(_val) => { },
-        Styles::BorderTopWidth(_val) => { },
-        Styles::BorderStartWidth(_val) => { },
-
-        Styles::BorderRadius(_val) => { },
-        Styles::BorderBottomEndRadius(_val) => { },
-        Styles::BorderBottomLeftRadius(_val) => { },
-        Styles::BorderBottomRightRadius(_val) => { },
-        Styles::BorderBottomStartRadius(_val) => { },
-        Styles::BorderTopLeftRadius(_val) => { },
-        Styles::BorderTopRightRadius(_val) => { },
-        Styles::BorderTopEndRadius(_val) => { },
-        Styles::BorderTopStartRadius(_val) => { },
+        // Border widths take up space, so they go to layout as well as appearance.
+        Styles::BorderWidth(val) => {
+            layout.border = Rect {
+                start: Dimension::Points(*val),
+                end: Dimension::Points(*val),
+                top: Dimension::Points(*val),
+                bottom: Dimension::Points(*val)
+            };
+
+            appearance.border_width = Rect { start: *val, end: *val, top: *val, bottom: *val };
+        },
+
+        Styles::BorderEndWidth(val) | Styles::BorderRightWidth(val) => {
+            layout.border = Rect {
+                start: layout.border.start,
+                end: Dimension::Points(*val),
+                top: layout.border.top,
+                bottom: layout.border.bottom
+            };
+
+            appearance.border_width.end = *val;
+        },
+
+        Styles::BorderBottomWidth(val) => {
+            layout.border = Rect {
+                start: layout.border.start,
+                end: layout.border.end,
+                top: layout.border.top,
+                bottom: Dimension::Points(*val)
+            };
+
+            appearance.border_width.bottom = *val;
+        },
+
+        Styles::BorderStartWidth(val) | Styles::BorderLeftWidth(val) => {
+            layout.border = Rect {
+                start: Dimension::Points(*val),
+                end: layout.border.end,
+                top: layout.border.top,
+                bottom: layout.border.bottom
+            };
+
+            appearance.border_width.start = *val;
+        },
+
+        Styles::BorderTopWidth(val) => {
+            layout.border = Rect {
+                start: layout.border.start,
+                end: layout.border.end,
+                top: Dimension::Points(*val),
+                bottom: layout.border.bottom
+            };
+
+            appearance.border_width.top = *val;
+        },
+
+        Styles::BorderRadius(val) => {
+            appearance.border_radius = CornerRadii {
+                top_left: *val,
+                top_right: *val,
+                bottom_right: *val,
+                bottom_left: *val
+            };
+        },
+
+        Styles::BorderBottomEndRadius(val) => { appearance.border_radius.bottom_right = *val; },
+        Styles::BorderBottomLeftRadius(val) => { appearance.border_radius.bottom_left = *val; },
+        Styles::BorderBottomRightRadius(val) => { appearance.border_radius.bottom_right = *val; },
+        Styles::BorderBottomStartRadius(val) => { appearance.border_radius.bottom_left = *val; },
+        Styles::BorderTopLeftRadius(val) => { appearance.border_radius.top_left = *val; },
+        Styles::BorderTopRightRadius(val) => { appearance.border_radius.top_right = *val; },
+        Styles::BorderTopEndRadius(val) => { appearance.border_radius.top_right = *val; },
+        Styles::BorderTopStartRadius(val) => { appearance.border_radius.top_left = *val; },
     
         Styles::Bottom(val) => {
             layout.position = Rect {
                 start: layout.position.start,
                 end: layout.position.end,
                 top: layout.position.top,
-                bottom: Dimension::Points(*val)
+                bottom: *val
             };
         },
 
+        // Custom properties are gathered up front, in `StyleSheet::custom_properties()`.
+        Styles::CustomProperty(_, _) => {},
+
         Styles::Direction(val) => { layout.direction = *val; },
         Styles::Display(val) => { layout.display = *val; },
         
         Styles::End(val) => {
             layout.position = Rect {
                 start: layout.position.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.position.top,
                 bottom: layout.position.bottom
             };
         },
         
-        Styles::FlexBasis(val) => { layout.flex_basis = Dimension::Points(*val); },
+        Styles::FlexBasis(val) => { layout.flex_basis = *val; },
         Styles::FlexDirection(val) => { layout.flex_direction = *val; },
         Styles::FlexGrow(val) => { layout.flex_grow = *val; },
         Styles::FlexShrink(val) => { layout.flex_shrink = *val; },
         Styles::FlexWrap(val) => { layout.flex_wrap = *val; },
         
         Styles::FontFamily(_val) => { },
-        Styles::FontLineHeight(_val) => { },
-        Styles::FontSize(val) => { appearance.font_size = *val; },
+        Styles::FontLineHeight(val) => { appearance.line_height = Some(*val); },
+        Styles::FontSize(val) => { match val {
+            Dimension::Points(points) => { appearance.font_size = *points; },
+            Dimension::Percent(fraction) | Dimension::Em(fraction) => { appearance.font_size = inherited.font_size * *fraction; },
+            Dimension::Rem(fraction) => { appearance.font_size = ROOT_FONT_SIZE * *fraction; },
+
+            // The parser doesn't accept anything else for `font-size`.
+            _ => {}
+        }},
         Styles::FontStyle(val) => { appearance.font_style = *val; },
         Styles::FontWeight(val) => { appearance.font_weight = *val; },
         
         Styles::Height(val) => {
             layout.size = Size {
                 width: layout.size.width,
-                height: Dimension::Points(*val)
+                height: *val
             };
         },
 
+        Styles::Inherit(property) => { property.copy(inherited, appearance); },
+        Styles::Initial(property) => { property.copy(&Appearance::default(), appearance); },
+
         Styles::JustifyContent(val) => { layout.justify_content = *val; },
 
         Styles::Left(val) => {
             layout.position = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.position.end,
                 top: layout.position.top,
                 bottom: layout.position.bottom
@@ -130,14 +349,14 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
                 start: layout.margin.start,
                 end: layout.margin.end,
                 top: layout.margin.top,
-                bottom: Dimension::Points(*val)
+                bottom: *val
             };
         },
 
         Styles::MarginEnd(val) => {
             layout.margin = Rect {
                 start: layout.margin.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.margin.top,
                 bottom: layout.margin.bottom
             };
@@ -145,7 +364,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
 
         Styles::MarginLeft(val) => {
             layout.margin = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.margin.end,
                 top: layout.margin.top,
                 bottom: layout.margin.bottom
@@ -155,7 +374,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         Styles::MarginRight(val) => {
             layout.margin = Rect {
                 start: layout.margin.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.margin.top,
                 bottom: layout.margin.bottom
             };
@@ -163,7 +382,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         
         Styles::MarginStart(val) => {
             layout.margin = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.margin.end,
                 top: layout.margin.top,
                 bottom: layout.margin.bottom
@@ -174,7 +393,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
             layout.margin = Rect {
                 start: layout.margin.start,
                 end: layout.margin.end,
-                top: Dimension::Points(*val),
+                top: *val,
                 bottom: layout.margin.bottom
             };
         },
@@ -182,13 +401,13 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         Styles::MaxHeight(val) => {
             layout.max_size = Size {
                 width: layout.max_size.width,
-                height: Dimension::Points(*val)
+                height: *val
             };
         },
 
         Styles::MaxWidth(val) => {
             layout.max_size = Size {
-                width: Dimension::Points(*val),
+                width: *val,
                 height: layout.max_size.height
             };
         },
@@ -196,13 +415,13 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         Styles::MinHeight(val) => {
             layout.min_size = Size {
                 width: layout.min_size.width,
-                height: Dimension::Points(*val)
+                height: *val
             };
         },
 
         Styles::MinWidth(val) => {
             layout.min_size = Size {
-                width: Dimension::Points(*val),
+                width: *val,
                 height: layout.min_size.height
             };
         },
@@ -215,14 +434,14 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
                 start: layout.padding.start,
                 end: layout.padding.end,
                 top: layout.padding.top,
-                bottom: Dimension::Points(*val)
+                bottom: *val
             };
         },
 
         Styles::PaddingEnd(val) => {
             layout.padding = Rect {
                 start: layout.padding.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.padding.top,
                 bottom: layout.padding.bottom
             };
@@ -230,7 +449,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
 
         Styles::PaddingLeft(val) => {
             layout.padding = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.padding.end,
                 top: layout.padding.top,
                 bottom: layout.padding.bottom
@@ -240,7 +459,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         Styles::PaddingRight(val) => {
             layout.padding = Rect {
                 start: layout.padding.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.padding.top,
                 bottom: layout.padding.bottom
             };
@@ -248,7 +467,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
 
         Styles::PaddingStart(val) => {
             layout.padding = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.padding.end,
                 top: layout.padding.top,
                 bottom: layout.padding.bottom
@@ -259,17 +478,22 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
             layout.padding = Rect {
                 start: layout.padding.start,
                 end: layout.padding.end,
-                top: Dimension::Points(*val),
+                top: *val,
                 bottom: layout.padding.bottom
             };
         },
 
+        Styles::PendingSubstitution(property, value) => {
+            let resolved = resolve_pending_substitution(property, value, variables);
+            reduce_styles_into_style(&resolved, variables, inherited, layout, appearance);
+        },
+
         Styles::PositionType(val) => { layout.position_type = *val; },
 
         Styles::Right(val) => {
             layout.position = Rect {
                 start: layout.position.start,
-                end: Dimension::Points(*val),
+                end: *val,
                 top: layout.position.top,
                 bottom: layout.position.bottom
             };
@@ -277,7 +501,7 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
         
         Styles::Start(val) => {
             layout.position = Rect {
-                start: Dimension::Points(*val),
+                start: *val,
                 end: layout.position.end,
                 top: layout.position.top,
                 bottom: layout.position.bottom
@@ -294,16 +518,35 @@ fn reduce_styles_into_style(styles: &Vec<Styles>, layout: &mut Style, appearance
             layout.position = Rect {
                 start: layout.position.start,
                 end: layout.position.end,
-                top: Dimension::Points(*val),
+                top: *val,
                 bottom: layout.position.bottom
             };
         },
         
         Styles::Width(val) => {
             layout.size = Size {
-                width: Dimension::Points(*val),
+                width: *val,
                 height: layout.size.height
             };
         }
     }}
 }
+
+/// `em` and `rem` lengths can't be handed off to layout as-is, so once every style has been
+/// applied (and the font size is settled), this resolves them into points.
+pub(crate) fn resolve_font_relative_lengths(layout: &mut Style, appearance: &Appearance) {
+    let resolve = |dimension: Dimension| match dimension {
+        Dimension::Em(fraction) => Dimension::Points(appearance.font_size * fraction),
+        Dimension::Rem(fraction) => Dimension::Points(ROOT_FONT_SIZE * fraction),
+        dimension => dimension
+    };
+
+    layout.position = layout.position.map(resolve);
+    layout.margin = layout.margin.map(resolve);
+    layout.padding = layout.padding.map(resolve);
+    layout.border = layout.border.map(resolve);
+    layout.flex_basis = resolve(layout.flex_basis);
+    layout.size = layout.size.map(resolve);
+    layout.min_size = layout.min_size.map(resolve);
+    layout.max_size = layout.max_size.map(resolve);
+}
@@ -0,0 +1,52 @@
+//! Tests for borders: widths feed into layout as well as `Appearance`, while colors, styles and
+//! radii are only for drawing.
+
+mod common;
+
+use alchemy_styles::Color;
+use alchemy_styles::styles::{BorderStyle, CornerRadii, Dimension, Rect};
+
+use common::{points, styled};
+
+#[test]
+fn border_widths_go_to_layout_and_appearance() {
+    let (style, appearance) = styled("border-top-width: 1px; border-right-width: thick; border-bottom-width: 3px; border-left-width: thin;");
+    assert_eq!(style.border, points(1., 5., 3., 1.));
+    assert_eq!(appearance.border_width, Rect { start: 1., end: 5., top: 1., bottom: 3. });
+
+    // Unset edges are left alone.
+    let (style, appearance) = styled("border-left-width: 2px;");
+    assert_eq!((style.border.start, style.border.top), (Dimension::Points(2.), Dimension::Undefined));
+    assert_eq!(appearance.border_width, Rect { start: 2., end: 0., top: 0., bottom: 0. });
+}
+
+#[test]
+fn border_colors_and_styles_are_kept_per_edge() {
+    let appearance = styled("border-top-color: #ff0000; border-left-color: #0000ff; border-bottom-style: solid; border-right-style: hidden;").1;
+    assert_eq!(appearance.border_color.top, Color::new(255, 0, 0, 255));
+    assert_eq!(appearance.border_color.start, Color::new(0, 0, 255, 255));
+    assert_eq!(appearance.border_color.end, Color::transparent());
+    assert_eq!(appearance.border_style, Rect {
+        start: BorderStyle::None,
+        end: BorderStyle::Hidden,
+        top: BorderStyle::None,
+        bottom: BorderStyle::Solid
+    });
+}
+
+#[test]
+fn border_radii_are_kept_per_corner() {
+    assert_eq!(styled("border-radius: 4px;").1.border_radius, CornerRadii {
+        top_left: 4.,
+        top_right: 4.,
+        bottom_right: 4.,
+        bottom_left: 4.
+    });
+
+    assert_eq!(styled("border-radius: 1px 2px 3px 4px; border-bottom-left-radius: 8px;").1.border_radius, CornerRadii {
+        top_left: 1.,
+        top_right: 2.,
+        bottom_right: 3.,
+        bottom_left: 8.
+    });
+}
@@ -0,0 +1,33 @@
+//! Helpers shared between the stylesheet tests.
+
+#![allow(dead_code)]
+
+use alchemy_styles::{Appearance, MediaEnvironment, Style, StyleRule, StyleSheet, StylesList};
+use alchemy_styles::selector::{ElementState, StyledNode};
+use alchemy_styles::styles::{Dimension, Rect};
+use alchemy_styles::styles_parser::parse_rules;
+
+/// Applies `declarations` to a lone node, returning the resulting layout style and appearance.
+pub fn styled(declarations: &str) -> (Style, Appearance) {
+    let (rules, errors) = parse_rules(&format!(".node {{ {} }}", declarations));
+    assert_eq!(errors, []);
+
+    let stylesheet = StyleSheet::new(rules.into_iter().map(StyleRule::from).collect());
+    let style_keys: StylesList = "node".into();
+    let path = [StyledNode { tag: "View", style_keys: &style_keys, state: ElementState::default() }];
+
+    let mut style = Style::default();
+    let mut appearance = Appearance::default();
+    stylesheet.apply_styles(&path, &MediaEnvironment::default(), &mut style, &mut appearance);
+    (style, appearance)
+}
+
+/// A `Rect` of points, in the same order as CSS box shorthands: top, right, bottom, left.
+pub fn points(top: f32, end: f32, bottom: f32, start: f32) -> Rect<Dimension> {
+    Rect {
+        start: Dimension::Points(start),
+        end: Dimension::Points(end),
+        top: Dimension::Points(top),
+        bottom: Dimension::Points(bottom)
+    }
+}
@@ -0,0 +1,90 @@
+//! Tests for CSS shorthands, which expand into the longhand properties they cover.
+
+mod common;
+
+use alchemy_styles::Color;
+use alchemy_styles::styles::{
+    AlignContent, AlignItems, BorderStyle, Dimension, FlexDirection, FlexWrap, JustifyContent, Rect
+};
+use alchemy_styles::styles_parser::parse_rules;
+
+use common::{points, styled};
+
+#[test]
+fn box_shorthands_take_one_to_four_values() {
+    assert_eq!(styled("margin: 1px;").0.margin, points(1., 1., 1., 1.));
+    assert_eq!(styled("margin: 1px 2px;").0.margin, points(1., 2., 1., 2.));
+    assert_eq!(styled("margin: 1px 2px 3px;").0.margin, points(1., 2., 3., 2.));
+    assert_eq!(styled("padding: 1px 2px 3px 4px;").0.padding, points(1., 2., 3., 4.));
+    assert_eq!(styled("inset: 1px 2px 3px 4px;").0.position, points(1., 2., 3., 4.));
+
+    let margin = styled("margin: 10% auto;").0.margin;
+    assert_eq!((margin.top, margin.start), (Dimension::Percent(0.1), Dimension::Auto));
+
+    // Longhands that come later still win.
+    assert_eq!(styled("margin: 1px; margin-left: 5px;").0.margin, points(1., 1., 1., 5.));
+}
+
+#[test]
+fn border_shorthands_expand_per_edge() {
+    let red = Color::new(255, 0, 0, 255);
+    let appearance = styled("border: 2px solid #ff0000;").1;
+    assert_eq!(appearance.border_width, Rect { start: 2., end: 2., top: 2., bottom: 2. });
+    assert_eq!(appearance.border_style, Rect {
+        start: BorderStyle::Solid,
+        end: BorderStyle::Solid,
+        top: BorderStyle::Solid,
+        bottom: BorderStyle::Solid
+    });
+    assert_eq!(appearance.border_color, Rect { start: red, end: red, top: red, bottom: red });
+
+    // Parts can come in any order; an omitted width is reset to `medium`.
+    let appearance = styled("border: #ff0000 solid;").1;
+    assert_eq!(appearance.border_width.top, 3.);
+    assert_eq!(appearance.border_color.top, red);
+
+    let appearance = styled("border-width: thin 2px; border-style: solid hidden; border-color: #ff0000 #00ff00 #0000ff;").1;
+    assert_eq!(appearance.border_width, Rect { start: 2., end: 2., top: 1., bottom: 1. });
+    assert_eq!((appearance.border_style.top, appearance.border_style.end), (BorderStyle::Solid, BorderStyle::Hidden));
+    assert_eq!(appearance.border_color.bottom, Color::new(0, 0, 255, 255));
+    assert_eq!(appearance.border_color.start, Color::new(0, 255, 0, 255));
+}
+
+#[test]
+fn flex_shorthands_expand_and_reset_what_they_omit() {
+    let flex = |declarations| {
+        let style = styled(declarations).0;
+        (style.flex_grow, style.flex_shrink, style.flex_basis)
+    };
+
+    assert_eq!(flex("flex: 2 3 10px;"), (2., 3., Dimension::Points(10.)));
+    assert_eq!(flex("flex: 2;"), (2., 1., Dimension::Points(0.)));
+    assert_eq!(flex("flex: 50%;"), (1., 1., Dimension::Percent(0.5)));
+    assert_eq!(flex("flex: none;"), (0., 0., Dimension::Auto));
+    assert_eq!(flex("flex: auto;"), (1., 1., Dimension::Auto));
+
+    let style = styled("flex-flow: column wrap;").0;
+    assert_eq!((style.flex_direction, style.flex_wrap), (FlexDirection::Column, FlexWrap::Wrap));
+
+    let style = styled("flex-wrap: wrap; flex-flow: column;").0;
+    assert_eq!((style.flex_direction, style.flex_wrap), (FlexDirection::Column, FlexWrap::NoWrap));
+}
+
+#[test]
+fn place_shorthands_set_both_axes() {
+    let style = styled("place-content: center space-between;").0;
+    assert_eq!((style.align_content, style.justify_content), (AlignContent::Center, JustifyContent::SpaceBetween));
+
+    let style = styled("place-content: flex-end;").0;
+    assert_eq!((style.align_content, style.justify_content), (AlignContent::FlexEnd, JustifyContent::FlexEnd));
+
+    assert_eq!(styled("place-items: center stretch;").0.align_items, AlignItems::Center);
+}
+
+#[test]
+fn malformed_shorthands_are_reported() {
+    for declarations in &["margin: 1px 2px 3px 4px 5px;", "border: solid dashed;", "flex: 1 1 1 1;", "flex-flow: sideways;"] {
+        let (_, errors) = parse_rules(&format!(".node {{ {} }}", declarations));
+        assert_eq!(errors.len(), 1, "{}", declarations);
+    }
+}
@@ -0,0 +1,138 @@
+//! Tests for loading themes from `.css` files in a `ThemeEngine`'s theme dirs, and reloading them
+//! as they change.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use alchemy_styles::{Appearance, MediaEnvironment, Style, StyleRule, StyleSheet, StylesList, ThemeEngine};
+use alchemy_styles::selector::{ElementState, StyledNode};
+use alchemy_styles::styles::Dimension;
+use alchemy_styles::styles_parser::parse_rules;
+
+/// Creates an empty directory to load themes from, named after the test using it.
+fn theme_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("alchemy-themes-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(path: &Path, css: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, css).unwrap();
+}
+
+/// Styles a lone `.swatch` node in `theme`, returning its width and background color.
+fn swatch(engine: &ThemeEngine, theme: &str) -> (Dimension, (u8, u8, u8)) {
+    let style_keys: StylesList = "swatch".into();
+    let path = [StyledNode { tag: "View", style_keys: &style_keys, state: ElementState::default() }];
+
+    let mut style = Style::default();
+    let mut appearance = Appearance::default();
+    engine.configure_styles_for_path_in_theme(theme, &path, &MediaEnvironment::default(), &mut style, &mut appearance);
+
+    let color = appearance.background_color;
+    (style.size.width, (color.red, color.green, color.blue))
+}
+
+#[test]
+fn themes_load_from_files_and_directories() {
+    let dir = theme_dir("load");
+    write(&dir.join("default.css"), ".swatch { width: 10px; background-color: #000000; }");
+    write(&dir.join("ocean.css"), ".swatch { background-color: #0000ff; }");
+    write(&dir.join("forest/b.css"), ".swatch { background-color: #00ff00; }");
+    write(&dir.join("forest/a.css"), ".swatch { width: 20px; background-color: #ff0000; }");
+    write(&dir.join("notes.txt"), ".swatch { background-color: #ffffff; }");
+
+    let engine = ThemeEngine::with_dirs(vec![dir.clone()]);
+    let mut themes: Vec<String> = engine.themes.read().unwrap().keys().cloned().collect();
+    themes.sort();
+    assert_eq!(themes, ["default", "forest", "ocean"]);
+
+    // Themes fall back to `default`, and a directory's files load in alphabetical order.
+    assert_eq!(swatch(&engine, "default"), (Dimension::Points(10.), (0, 0, 0)));
+    assert_eq!(swatch(&engine, "ocean"), (Dimension::Points(10.), (0, 0, 0xff)));
+    assert_eq!(swatch(&engine, "forest"), (Dimension::Points(20.), (0, 0xff, 0)));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn later_dirs_add_to_themes_from_earlier_ones() {
+    let first = theme_dir("first");
+    let second = theme_dir("second");
+    write(&first.join("default.css"), ".swatch { width: 10px; background-color: #000000; }");
+    write(&second.join("default.css"), ".swatch { background-color: #ff0000; }");
+
+    let engine = ThemeEngine::with_dirs(vec![first.clone(), second.clone(), first.join("missing")]);
+    assert_eq!(swatch(&engine, "default"), (Dimension::Points(10.), (0xff, 0, 0)));
+
+    let _ = fs::remove_dir_all(&first);
+    let _ = fs::remove_dir_all(&second);
+}
+
+#[test]
+fn rules_that_fail_to_parse_dont_take_the_theme_down() {
+    let dir = theme_dir("errors");
+    write(&dir.join("default.css"), "
+        .swatch { width: 10px; }
+        .swatch { background-color: not-a-color; }
+        .swatch { background-color: #00ff00; }
+    ");
+
+    let engine = ThemeEngine::with_dirs(vec![dir.clone()]);
+    assert_eq!(swatch(&engine, "default"), (Dimension::Points(10.), (0, 0xff, 0)));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn parse_errors_point_at_the_line_and_column() {
+    let (_, errors) = parse_rules(".swatch { width: 10px; }\n.swatch {\n    colour: #ff0000;\n}\n");
+    assert_eq!(errors.len(), 1);
+    assert_eq!((errors[0].line, errors[0].column), (3, 5));
+    assert_eq!(errors[0].to_string(), "3:5: unknown property `colour`");
+}
+
+#[test]
+fn reloading_picks_up_changed_and_removed_files() {
+    let dir = theme_dir("reload");
+    write(&dir.join("default.css"), ".swatch { width: 10px; background-color: #000000; }");
+    write(&dir.join("ocean.css"), ".swatch { background-color: #0000ff; }");
+
+    let engine = ThemeEngine::with_dirs(vec![dir.clone()]);
+
+    // Stylesheets registered in code stay on top of ones from theme files, across reloads.
+    let (rules, _) = parse_rules(".swatch { width: 30px; }");
+    engine.register_styles("default", StyleSheet::new(rules.into_iter().map(StyleRule::from).collect()));
+
+    write(&dir.join("default.css"), ".swatch { width: 20px; background-color: #ff0000; }");
+    fs::remove_file(dir.join("ocean.css")).unwrap();
+    engine.load_themes();
+
+    assert_eq!(swatch(&engine, "default"), (Dimension::Points(30.), (0xff, 0, 0)));
+    assert!(!engine.themes.read().unwrap().contains_key("ocean"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn watching_reloads_themes_as_files_change() {
+    let dir = theme_dir("watch");
+    write(&dir.join("default.css"), ".swatch { background-color: #000000; }");
+
+    // `watch()` runs for as long as the engine's around, so it has to live forever.
+    let engine: &'static ThemeEngine = Box::leak(Box::new(ThemeEngine::with_dirs(vec![dir.clone()])));
+    let (reloaded, on_reload) = mpsc::channel();
+    engine.watch(move || { let _ = reloaded.send(()); });
+
+    write(&dir.join("ocean.css"), ".swatch { background-color: #0000ff; }");
+    on_reload.recv_timeout(Duration::from_secs(5)).expect("themes weren't reloaded");
+    assert_eq!(swatch(engine, "ocean").1, (0, 0, 0xff));
+
+    let _ = fs::remove_dir_all(&dir);
+}